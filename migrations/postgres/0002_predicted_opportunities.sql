@@ -0,0 +1,18 @@
+CREATE TABLE IF NOT EXISTS predicted_opportunities (
+    id TEXT PRIMARY KEY,
+    timestamp TIMESTAMPTZ NOT NULL,
+    trigger_tx TEXT NOT NULL,
+    trigger_venue TEXT NOT NULL,
+    target_block BIGINT NOT NULL,
+    buy_exchange TEXT NOT NULL,
+    sell_exchange TEXT NOT NULL,
+    buy_price DOUBLE PRECISION NOT NULL,
+    sell_price DOUBLE PRECISION NOT NULL,
+    estimated_profit DOUBLE PRECISION NOT NULL,
+    price_difference DOUBLE PRECISION NOT NULL,
+    gas_cost_estimate DOUBLE PRECISION NOT NULL,
+    profit_percentage DOUBLE PRECISION NOT NULL,
+    confirmed BOOLEAN,
+    resolved_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS opportunity_lifecycles (
+    id TEXT PRIMARY KEY,
+    pair TEXT NOT NULL,
+    buy_exchange TEXT NOT NULL,
+    sell_exchange TEXT NOT NULL,
+    first_seen_block BIGINT NOT NULL,
+    last_seen_block BIGINT NOT NULL,
+    first_seen_at TIMESTAMPTZ NOT NULL,
+    last_seen_at TIMESTAMPTZ NOT NULL,
+    peak_profit DOUBLE PRECISION NOT NULL,
+    peak_profit_percentage DOUBLE PRECISION NOT NULL,
+    observations BIGINT NOT NULL,
+    closed_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_opportunity_lifecycles_open
+    ON opportunity_lifecycles (closed_at);
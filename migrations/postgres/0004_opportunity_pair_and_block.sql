@@ -0,0 +1,3 @@
+ALTER TABLE arbitrage_opportunities ADD COLUMN pair TEXT NOT NULL DEFAULT 'WETH/USDC';
+ALTER TABLE arbitrage_opportunities ADD COLUMN block_number BIGINT;
+ALTER TABLE predicted_opportunities ADD COLUMN pair TEXT NOT NULL DEFAULT 'WETH/USDC';
@@ -0,0 +1,26 @@
+CREATE TABLE IF NOT EXISTS price_snapshots (
+    id BIGINT GENERATED ALWAYS AS IDENTITY,
+    check_id TEXT NOT NULL,
+    timestamp TIMESTAMPTZ NOT NULL,
+    block_number BIGINT NOT NULL,
+    pair TEXT NOT NULL,
+    venue TEXT NOT NULL,
+    amount_in NUMERIC(78, 0) NOT NULL,
+    amount_out NUMERIC(78, 0) NOT NULL,
+    price DOUBLE PRECISION NOT NULL,
+    latency_ms DOUBLE PRECISION NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_price_snapshots_timestamp
+    ON price_snapshots (timestamp);
+CREATE INDEX IF NOT EXISTS idx_price_snapshots_check
+    ON price_snapshots (check_id);
+
+-- Partition by time when TimescaleDB is installed; plain PostgreSQL keeps a regular table
+DO $$
+BEGIN
+    IF EXISTS (SELECT 1 FROM pg_extension WHERE extname = 'timescaledb') THEN
+        PERFORM create_hypertable('price_snapshots', 'timestamp', if_not_exists => TRUE, migrate_data => TRUE);
+    END IF;
+END
+$$;
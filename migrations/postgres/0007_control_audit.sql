@@ -0,0 +1,12 @@
+-- Every change made through the runtime control API.
+CREATE TABLE IF NOT EXISTS control_audit (
+    id BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    timestamp TIMESTAMPTZ NOT NULL,
+    actor TEXT NOT NULL,
+    action TEXT NOT NULL,
+    previous_value TEXT,
+    new_value TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_control_audit_timestamp
+    ON control_audit (timestamp);
@@ -0,0 +1,15 @@
+-- Databases created before versioned migrations already have this table,
+-- so it is created only when missing.
+CREATE TABLE IF NOT EXISTS arbitrage_opportunities (
+    id TEXT PRIMARY KEY,
+    timestamp TEXT NOT NULL,
+    buy_exchange TEXT NOT NULL,
+    sell_exchange TEXT NOT NULL,
+    buy_price REAL NOT NULL,
+    sell_price REAL NOT NULL,
+    estimated_profit REAL NOT NULL,
+    price_difference REAL NOT NULL,
+    gas_cost_estimate REAL NOT NULL,
+    profit_percentage REAL NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
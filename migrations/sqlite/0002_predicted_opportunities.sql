@@ -0,0 +1,18 @@
+CREATE TABLE IF NOT EXISTS predicted_opportunities (
+    id TEXT PRIMARY KEY,
+    timestamp TEXT NOT NULL,
+    trigger_tx TEXT NOT NULL,
+    trigger_venue TEXT NOT NULL,
+    target_block INTEGER NOT NULL,
+    buy_exchange TEXT NOT NULL,
+    sell_exchange TEXT NOT NULL,
+    buy_price REAL NOT NULL,
+    sell_price REAL NOT NULL,
+    estimated_profit REAL NOT NULL,
+    price_difference REAL NOT NULL,
+    gas_cost_estimate REAL NOT NULL,
+    profit_percentage REAL NOT NULL,
+    confirmed INTEGER,
+    resolved_at TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS opportunity_lifecycles (
+    id TEXT PRIMARY KEY,
+    pair TEXT NOT NULL,
+    buy_exchange TEXT NOT NULL,
+    sell_exchange TEXT NOT NULL,
+    first_seen_block INTEGER NOT NULL,
+    last_seen_block INTEGER NOT NULL,
+    first_seen_at TEXT NOT NULL,
+    last_seen_at TEXT NOT NULL,
+    peak_profit REAL NOT NULL,
+    peak_profit_percentage REAL NOT NULL,
+    observations INTEGER NOT NULL,
+    closed_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_opportunity_lifecycles_open
+    ON opportunity_lifecycles (closed_at);
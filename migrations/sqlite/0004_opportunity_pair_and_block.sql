@@ -0,0 +1,5 @@
+-- Rows recorded before this migration only ever covered WETH/USDC and did
+-- not capture the block they were observed at.
+ALTER TABLE arbitrage_opportunities ADD COLUMN pair TEXT NOT NULL DEFAULT 'WETH/USDC';
+ALTER TABLE arbitrage_opportunities ADD COLUMN block_number INTEGER;
+ALTER TABLE predicted_opportunities ADD COLUMN pair TEXT NOT NULL DEFAULT 'WETH/USDC';
@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS price_snapshots (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    check_id TEXT NOT NULL,
+    timestamp TEXT NOT NULL,
+    block_number INTEGER NOT NULL,
+    pair TEXT NOT NULL,
+    venue TEXT NOT NULL,
+    amount_in TEXT NOT NULL,
+    amount_out TEXT NOT NULL,
+    price REAL NOT NULL,
+    latency_ms REAL NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_price_snapshots_timestamp
+    ON price_snapshots (timestamp);
+CREATE INDEX IF NOT EXISTS idx_price_snapshots_check
+    ON price_snapshots (check_id);
@@ -0,0 +1,7 @@
+-- Back the filtered, keyset-paginated opportunities query. The id column is
+-- included so ties on the sort key stay ordered within the index.
+CREATE INDEX IF NOT EXISTS idx_arbitrage_opportunities_timestamp
+    ON arbitrage_opportunities (timestamp, id);
+
+CREATE INDEX IF NOT EXISTS idx_arbitrage_opportunities_profit
+    ON arbitrage_opportunities (estimated_profit, id);
@@ -1,83 +1,822 @@
-use actix_web::{get, web, HttpResponse, Responder};
-use crate::db::Database;
-use log::{error, info};
-use std::sync::Arc;
-
-#[get("/api/opportunities")]
-pub async fn get_opportunities(db: web::Data<Arc<Database>>) -> impl Responder {
-    info!("API: Fetching opportunities from database...");
-    
-    match db.get_recent_opportunities(50).await {
-        Ok(opportunities) => {
-            info!("API: Found {} opportunities", opportunities.len());
-            
-            let json_data: Vec<serde_json::Value> = opportunities.into_iter().map(|opp| {
-                serde_json::json!({
-                    "id": opp.id,
-                    "timestamp": opp.timestamp.to_rfc3339(),
-                    "buy_exchange": opp.buy_exchange,
-                    "sell_exchange": opp.sell_exchange,
-                    "buy_price": opp.buy_price,
-                    "sell_price": opp.sell_price,
-                    "estimated_profit": opp.estimated_profit,
-                    "profit_percentage": opp.profit_percentage,
-                    "created_at": opp.timestamp.to_rfc3339()
-                })
-            }).collect();
-            
-            HttpResponse::Ok().json(serde_json::json!({
-                "success": true,
-                "data": json_data
-            }))
-        },
-        Err(e) => {
-            error!("API: Database error in get_opportunities: {}", e);
-            println!("API: Database error in get_opportunities: {}", e);
-            HttpResponse::InternalServerError().json(serde_json::json!({
-                "success": false,
-                "error": format!("Database query failed: {}", e)
-            }))
-        }
-    }
-}
-
-#[get("/api/stats")]
-pub async fn get_stats(db: web::Data<Arc<Database>>) -> impl Responder {
-    info!("API: Fetching stats from database...");
-    
-    match db.get_stats().await {
-        Ok((total, avg, max)) => {
-            info!("API: Stats - total={}, avg={:.2}, max={:.2}", total, avg, max);
-            HttpResponse::Ok().json(serde_json::json!({
-                "success": true,
-                "data": {
-                    "total_opportunities": total,
-                    "average_profit": avg,
-                    "best_profit": max,
-                    "runtime": "Active"
-                }
-            }))
-        },
-        Err(e) => {
-            error!("API: Database error in get_stats: {}", e);
-            println!("API: Database error in get_stats: {}", e);
-            HttpResponse::InternalServerError().json(serde_json::json!({
-                "success": false,
-                "error": format!("Stats query failed: {}", e)
-            }))
-        }
-    }
-}
-
-#[get("/api/status")]
-pub async fn get_status() -> impl Responder {
-    info!("API: Status endpoint called");
-    HttpResponse::Ok().json(serde_json::json!({
-        "success": true,
-        "data": {
-            "running": true,
-            "last_check": chrono::Utc::now().format("%H:%M:%S").to_string(),
-            "status": "Monitoring Polygon DEXs"
-        }
-    }))
-}
+use actix_web::{get, post, http::{header, StatusCode}, web, HttpRequest, HttpResponse, Responder};
+use actix_ws::Message;
+use crate::auth;
+use crate::control::{BotControl, ControlAction, RuntimeSettings, SettingsUpdate};
+use crate::db::Store;
+use crate::events::{BotEvent, EventBus, EventFilter};
+use crate::export;
+use crate::metrics;
+use crate::models::{
+    AnalyticsParams, ApiResponse, CheckRequested, ErrorResponse, ExportParams, LifecycleSummary, LivenessResponse,
+    OpportunityPageResponse, OpportunityParams, PauseState, PredictionSummary, ProfitBuckets,
+    ReadinessResponse, StatsSummary, StatusSummary, StreamParams, VenueLeaderboard,
+};
+use crate::snapshots::PriceSnapshot;
+use crate::status::StatusTracker;
+use chrono::Utc;
+use futures_util::TryStreamExt;
+use tracing::{debug, error, info, warn};
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+
+fn success<T: Serialize>(data: T) -> HttpResponse {
+    HttpResponse::Ok().json(ApiResponse::ok(data))
+}
+
+fn failure(status: StatusCode, error: impl Into<String>) -> HttpResponse {
+    HttpResponse::build(status).json(ErrorResponse::new(error))
+}
+
+/// Recent opportunities, filtered, sorted and paginated.
+#[utoipa::path(
+    tag = "opportunities",
+    params(OpportunityParams),
+    responses(
+        (status = 200, body = OpportunityPageResponse),
+        (status = 400, description = "Invalid filter or cursor", body = ErrorResponse),
+        (status = 500, body = ErrorResponse),
+    )
+)]
+#[get("/api/opportunities")]
+pub async fn get_opportunities(db: web::Data<Arc<dyn Store>>, params: web::Query<OpportunityParams>) -> impl Responder {
+    info!("API: Fetching opportunities from database...");
+    
+    let query = match params.into_inner().into_query() {
+        Ok(query) => query,
+        Err(e) => return failure(StatusCode::BAD_REQUEST, format!("{:#}", e)),
+    };
+    
+    match db.query_opportunities(&query).await {
+        Ok(page) => {
+            info!("API: Found {} opportunities ({} total)", page.opportunities.len(), page.total);
+            
+            HttpResponse::Ok().json(OpportunityPageResponse {
+                success: true,
+                data: page.opportunities.into_iter().map(Into::into).collect(),
+                total: page.total,
+                next_cursor: page.next_cursor,
+            })
+        },
+        Err(e) => {
+            error!("API: Database error in get_opportunities: {}", e);
+            failure(StatusCode::INTERNAL_SERVER_ERROR, format!("Database query failed: {}", e))
+        }
+    }
+}
+
+/// Totals across every recorded opportunity, alongside how many distinct
+/// opportunity lifecycles they belong to.
+#[utoipa::path(
+    tag = "opportunities",
+    responses(
+        (status = 200, body = ApiResponse<StatsSummary>),
+        (status = 500, body = ErrorResponse),
+    )
+)]
+#[get("/api/stats")]
+pub async fn get_stats(db: web::Data<Arc<dyn Store>>) -> impl Responder {
+    info!("API: Fetching stats from database...");
+    
+    let result = async {
+        let stats = db.get_stats().await?;
+        let (distinct, ..) = db.get_lifecycle_stats().await?;
+        anyhow::Ok((stats, distinct))
+    }.await;
+    
+    match result {
+        Ok(((total, avg, max), distinct)) => {
+            info!("API: Stats - total={}, distinct={}, avg={:.2}, max={:.2}", total, distinct, avg, max);
+            success(StatsSummary {
+                total_opportunities: total,
+                distinct_opportunities: distinct,
+                average_profit: avg,
+                best_profit: max,
+                runtime: "Active".to_string(),
+            })
+        },
+        Err(e) => {
+            error!("API: Database error in get_stats: {}", e);
+            failure(StatusCode::INTERNAL_SERVER_ERROR, format!("Stats query failed: {}", e))
+        }
+    }
+}
+
+/// Monitoring loop state, runtime settings and recent control changes.
+#[utoipa::path(
+    tag = "monitoring",
+    responses((status = 200, body = ApiResponse<StatusSummary>))
+)]
+#[get("/api/status")]
+pub async fn get_status(
+    status: web::Data<Arc<StatusTracker>>,
+    control: web::Data<Arc<BotControl>>,
+    db: web::Data<Arc<dyn Store>>,
+) -> impl Responder {
+    info!("API: Status endpoint called");
+    let report = status.report(Utc::now());
+    let paused = control.is_paused();
+    
+    let summary = match (&report.last_error, report.running, report.healthy) {
+        _ if !report.monitoring => "Monitoring disabled, serving stored data".to_string(),
+        (_, false, _) => "Monitoring loop stalled".to_string(),
+        _ if paused => "Monitoring paused".to_string(),
+        (Some(error), true, false) => format!("Checks failing: {}", error),
+        _ => "Monitoring Polygon DEXs".to_string(),
+    };
+    
+    let recent_changes = db.get_recent_control_actions(RECENT_CONTROL_ACTIONS).await
+        .unwrap_or_else(|e| {
+            error!("API: Failed to load control audit: {}", e);
+            Vec::new()
+        });
+    
+    success(StatusSummary {
+        report,
+        status: summary,
+        paused,
+        settings: control.settings(),
+        recent_changes,
+    })
+}
+
+/// Control changes shown on `/api/status`
+const RECENT_CONTROL_ACTIONS: i64 = 5;
+
+/// Liveness: fails when the monitoring loop has stopped attempting checks.
+#[utoipa::path(
+    tag = "monitoring",
+    security(()),
+    responses(
+        (status = 200, body = LivenessResponse),
+        (status = 503, description = "Monitoring loop stalled", body = LivenessResponse),
+    )
+)]
+#[get("/healthz")]
+pub async fn healthz(status: web::Data<Arc<StatusTracker>>) -> impl Responder {
+    let report = status.report(Utc::now());
+    
+    if report.running {
+        HttpResponse::Ok().json(LivenessResponse { status: "ok".to_string(), last_check: report.last_check })
+    } else {
+        HttpResponse::ServiceUnavailable().json(LivenessResponse { status: "stalled".to_string(), last_check: report.last_check })
+    }
+}
+
+/// Readiness: fails until a check has succeeded recently.
+#[utoipa::path(
+    tag = "monitoring",
+    security(()),
+    responses(
+        (status = 200, body = ReadinessResponse),
+        (status = 503, description = "No recent successful check", body = ReadinessResponse),
+    )
+)]
+#[get("/readyz")]
+pub async fn readyz(status: web::Data<Arc<StatusTracker>>) -> impl Responder {
+    let report = status.report(Utc::now());
+    
+    if report.ready {
+        HttpResponse::Ok().json(ReadinessResponse {
+            status: "ready".to_string(),
+            last_success: report.last_success,
+            last_error: None,
+        })
+    } else {
+        HttpResponse::ServiceUnavailable().json(ReadinessResponse {
+            status: "not_ready".to_string(),
+            last_success: report.last_success,
+            last_error: report.last_error,
+        })
+    }
+}
+
+/// Prometheus scrape endpoint.
+#[utoipa::path(
+    tag = "monitoring",
+    responses((status = 200, description = "Prometheus text exposition format", body = String, content_type = "text/plain"))
+)]
+#[get("/metrics")]
+pub async fn get_metrics() -> impl Responder {
+    match metrics::get().render() {
+        Ok(text) => HttpResponse::Ok()
+            .content_type(prometheus::TEXT_FORMAT)
+            .body(text),
+        Err(e) => {
+            error!("API: Failed to render metrics: {}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
+/// Opportunities predicted from pending swaps and how many came true.
+#[utoipa::path(
+    tag = "opportunities",
+    responses(
+        (status = 200, body = ApiResponse<PredictionSummary>),
+        (status = 500, body = ErrorResponse),
+    )
+)]
+#[get("/api/predictions")]
+pub async fn get_predictions(db: web::Data<Arc<dyn Store>>) -> impl Responder {
+    info!("API: Fetching predicted opportunities from database...");
+    
+    let result = async {
+        let predictions = db.get_recent_predictions(50).await?;
+        let stats = db.get_prediction_stats().await?;
+        anyhow::Ok((predictions, stats))
+    }.await;
+    
+    match result {
+        Ok((predictions, (total, confirmed, refuted, expired))) => {
+            let resolved = confirmed + refuted;
+            let confirmation_rate = if resolved > 0 {
+                confirmed as f64 / resolved as f64 * 100.0
+            } else {
+                0.0
+            };
+            
+            success(PredictionSummary {
+                total_predictions: total,
+                confirmed,
+                refuted,
+                expired,
+                confirmation_rate,
+                recent: predictions,
+            })
+        },
+        Err(e) => {
+            error!("API: Database error in get_predictions: {}", e);
+            failure(StatusCode::INTERNAL_SERVER_ERROR, format!("Predictions query failed: {}", e))
+        }
+    }
+}
+
+/// How long opportunities stayed open.
+#[utoipa::path(
+    tag = "opportunities",
+    responses(
+        (status = 200, body = ApiResponse<LifecycleSummary>),
+        (status = 500, body = ErrorResponse),
+    )
+)]
+#[get("/api/lifecycles")]
+pub async fn get_lifecycles(db: web::Data<Arc<dyn Store>>) -> impl Responder {
+    info!("API: Fetching opportunity lifecycles from database...");
+    
+    let result = async {
+        let lifecycles = db.get_recent_lifecycles(50).await?;
+        let stats = db.get_lifecycle_stats().await?;
+        anyhow::Ok((lifecycles, stats))
+    }.await;
+    
+    match result {
+        Ok((lifecycles, (total, open, avg_duration, avg_observations))) => {
+            success(LifecycleSummary {
+                total_lifecycles: total,
+                open_lifecycles: open,
+                average_duration_seconds: avg_duration,
+                average_observations: avg_observations,
+                recent: lifecycles.into_iter().map(Into::into).collect(),
+            })
+        },
+        Err(e) => {
+            error!("API: Database error in get_lifecycles: {}", e);
+            failure(StatusCode::INTERNAL_SERVER_ERROR, format!("Lifecycles query failed: {}", e))
+        }
+    }
+}
+
+/// The most recent venue quotes.
+#[utoipa::path(
+    tag = "analytics",
+    responses(
+        (status = 200, body = ApiResponse<Vec<PriceSnapshot>>),
+        (status = 500, body = ErrorResponse),
+    )
+)]
+#[get("/api/snapshots")]
+pub async fn get_snapshots(db: web::Data<Arc<dyn Store>>) -> impl Responder {
+    info!("API: Price snapshots endpoint called");
+    
+    match db.get_recent_price_snapshots(100).await {
+        Ok(snapshots) => success(snapshots),
+        Err(e) => {
+            error!("API: Database error in get_snapshots: {}", e);
+            failure(StatusCode::INTERNAL_SERVER_ERROR, format!("Snapshots query failed: {}", e))
+        }
+    }
+}
+
+/// Profit statistics per hour or day and trade direction.
+#[utoipa::path(
+    tag = "analytics",
+    params(AnalyticsParams),
+    responses(
+        (status = 200, body = ApiResponse<ProfitBuckets>),
+        (status = 500, body = ErrorResponse),
+    )
+)]
+#[get("/api/analytics/buckets")]
+pub async fn get_profit_buckets(db: web::Data<Arc<dyn Store>>, params: web::Query<AnalyticsParams>) -> impl Responder {
+    info!("API: Profit buckets endpoint called");
+    
+    let (from, to) = params.range();
+    match db.get_profit_buckets(params.interval, from, to).await {
+        Ok(buckets) => success(ProfitBuckets { interval: params.interval, from, to, buckets }),
+        Err(e) => {
+            error!("API: Database error in get_profit_buckets: {}", e);
+            failure(StatusCode::INTERNAL_SERVER_ERROR, format!("Analytics query failed: {}", e))
+        }
+    }
+}
+
+/// Trade directions ranked by how many opportunities they won.
+#[utoipa::path(
+    tag = "analytics",
+    params(AnalyticsParams),
+    responses(
+        (status = 200, body = ApiResponse<VenueLeaderboard>),
+        (status = 500, body = ErrorResponse),
+    )
+)]
+#[get("/api/analytics/leaderboard")]
+pub async fn get_venue_leaderboard(db: web::Data<Arc<dyn Store>>, params: web::Query<AnalyticsParams>) -> impl Responder {
+    info!("API: Venue leaderboard endpoint called");
+    
+    let (from, to) = params.range();
+    match db.get_venue_leaderboard(from, to).await {
+        Ok(standings) => success(VenueLeaderboard { from, to, standings }),
+        Err(e) => {
+            error!("API: Database error in get_venue_leaderboard: {}", e);
+            failure(StatusCode::INTERNAL_SERVER_ERROR, format!("Analytics query failed: {}", e))
+        }
+    }
+}
+
+/// Opportunities or price snapshots for a time range as a file download,
+/// streamed from the database batch by batch.
+#[utoipa::path(
+    tag = "analytics",
+    params(ExportParams),
+    responses(
+        (status = 200, description = "CSV with a header row", content_type = "text/csv"),
+        (status = 200, description = "One JSON object per line", content_type = "application/x-ndjson"),
+        (status = 200, description = "Parquet file with one row group per batch", content_type = "application/vnd.apache.parquet"),
+        (status = 400, description = "Invalid dataset, format or range", body = ErrorResponse),
+    )
+)]
+#[get("/api/export")]
+pub async fn export_data(db: web::Data<Arc<dyn Store>>, params: web::Query<ExportParams>) -> impl Responder {
+    let request = match params.into_inner().into_request() {
+        Ok(request) => request,
+        Err(e) => return failure(StatusCode::BAD_REQUEST, format!("{:#}", e)),
+    };
+    info!("API: Exporting {} as {:?} from {} to {}",
+          request.dataset.as_str(), request.format, request.from, request.to);
+    
+    // Headers are already sent when a batch fails, so the download is cut short
+    let body = export::export_stream(db.get_ref().clone(), request.clone())
+        .map_ok(web::Bytes::from)
+        .map_err(|e| {
+            error!("API: Export failed: {:#}", e);
+            actix_web::error::ErrorInternalServerError(e.to_string())
+        });
+    
+    HttpResponse::Ok()
+        .content_type(request.format.content_type())
+        .insert_header((header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", request.file_name())))
+        .streaming(body)
+}
+
+/// Live event stream over WebSocket. Clients may send an `EventFilter` as a
+/// JSON text message at any time to replace their subscription.
+#[utoipa::path(
+    tag = "streams",
+    responses((status = 101, description = "Switching to the WebSocket protocol"))
+)]
+#[get("/api/ws")]
+pub async fn stream_ws(
+    req: HttpRequest,
+    body: web::Payload,
+    events: web::Data<Arc<EventBus>>,
+) -> actix_web::Result<HttpResponse> {
+    let (response, mut session, mut messages) = actix_ws::handle(&req, body)?;
+    let mut receiver = events.subscribe();
+    info!("API: WebSocket client connected");
+    
+    actix_web::rt::spawn(async move {
+        let mut filter = EventFilter::default();
+        
+        loop {
+            let outgoing = tokio::select! {
+                event = receiver.recv() => match event {
+                    Ok(event) if filter.matches(&event) => serde_json::to_string(&filter.apply(event)),
+                    Ok(_) => continue,
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!("API: WebSocket client lagged, {} events dropped", skipped);
+                        Ok(serde_json::json!({"type": "lagged", "skipped": skipped}).to_string())
+                    },
+                    Err(RecvError::Closed) => break,
+                },
+                message = messages.recv() => match message {
+                    Some(Ok(Message::Text(text))) => match serde_json::from_str::<EventFilter>(&text) {
+                        Ok(new_filter) => {
+                            debug!("API: WebSocket subscription updated: {:?}", new_filter);
+                            filter = new_filter;
+                            Ok(serde_json::json!({"type": "subscribed", "filter": filter}).to_string())
+                        },
+                        Err(e) => Ok(serde_json::json!({"type": "error", "error": format!("Invalid filter: {}", e)}).to_string()),
+                    },
+                    Some(Ok(Message::Ping(bytes))) => {
+                        if session.pong(&bytes).await.is_err() {
+                            break;
+                        }
+                        continue;
+                    },
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => continue,
+                },
+            };
+            
+            match outgoing {
+                Ok(text) => {
+                    if session.text(text).await.is_err() {
+                        break;
+                    }
+                },
+                Err(e) => error!("API: Failed to serialize event: {}", e),
+            }
+        }
+        
+        let _ = session.close(None).await;
+        info!("API: WebSocket client disconnected");
+    });
+    
+    Ok(response)
+}
+
+const SSE_KEEPALIVE: std::time::Duration = std::time::Duration::from_secs(15);
+
+fn sse_frame(event: &BotEvent) -> web::Bytes {
+    let data = serde_json::to_string(event).unwrap_or_else(|e| {
+        serde_json::json!({"type": "error", "error": e.to_string()}).to_string()
+    });
+    web::Bytes::from(format!("event: {}\ndata: {}\n\n", event.kind().as_str(), data))
+}
+
+/// Live event stream as Server-Sent Events, filtered by query parameters.
+#[utoipa::path(
+    tag = "streams",
+    params(StreamParams),
+    responses(
+        (status = 200, description = "`text/event-stream` of bot events", content_type = "text/event-stream"),
+        (status = 400, description = "Unknown event type", body = ErrorResponse),
+    )
+)]
+#[get("/api/stream")]
+pub async fn stream_sse(events: web::Data<Arc<EventBus>>, params: web::Query<StreamParams>) -> impl Responder {
+    let filter = match params.into_inner().into_filter() {
+        Ok(filter) => filter,
+        Err(e) => return failure(StatusCode::BAD_REQUEST, format!("{:#}", e)),
+    };
+    info!("API: SSE client connected");
+    
+    let stream = futures_util::stream::unfold((events.subscribe(), filter), |(mut receiver, filter)| async move {
+        loop {
+            let frame = match tokio::time::timeout(SSE_KEEPALIVE, receiver.recv()).await {
+                Err(_) => web::Bytes::from_static(b": keep-alive\n\n"),
+                Ok(Ok(event)) if filter.matches(&event) => sse_frame(&filter.apply(event)),
+                Ok(Ok(_)) => continue,
+                Ok(Err(RecvError::Lagged(skipped))) => {
+                    warn!("API: SSE client lagged, {} events dropped", skipped);
+                    continue;
+                },
+                Ok(Err(RecvError::Closed)) => return None,
+            };
+            return Some((Ok::<_, actix_web::Error>(frame), (receiver, filter)));
+        }
+    });
+    
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        .streaming(stream)
+}
+
+fn control_response<T: Serialize>(result: anyhow::Result<T>) -> HttpResponse {
+    match result {
+        Ok(data) => success(data),
+        Err(e) => {
+            error!("API: Control action failed: {:#}", e);
+            failure(StatusCode::INTERNAL_SERVER_ERROR, format!("{:#}", e))
+        }
+    }
+}
+
+/// Stops scheduled checks; manual triggers still run.
+#[utoipa::path(
+    tag = "control",
+    responses(
+        (status = 200, body = ApiResponse<PauseState>),
+        (status = 500, body = ErrorResponse),
+    )
+)]
+#[post("/api/control/pause")]
+pub async fn pause_monitoring(req: HttpRequest, control: web::Data<Arc<BotControl>>) -> impl Responder {
+    let actor = auth::actor(&req);
+    
+    control_response(control.pause(&actor).await
+        .map(|changed| PauseState { paused: true, changed }))
+}
+
+/// Resumes scheduled checks.
+#[utoipa::path(
+    tag = "control",
+    responses(
+        (status = 200, body = ApiResponse<PauseState>),
+        (status = 500, body = ErrorResponse),
+    )
+)]
+#[post("/api/control/resume")]
+pub async fn resume_monitoring(req: HttpRequest, control: web::Data<Arc<BotControl>>) -> impl Responder {
+    let actor = auth::actor(&req);
+    
+    control_response(control.resume(&actor).await
+        .map(|changed| PauseState { paused: false, changed }))
+}
+
+/// Runs one check as soon as the monitoring loop is free.
+#[utoipa::path(
+    tag = "control",
+    responses(
+        (status = 200, body = ApiResponse<CheckRequested>),
+        (status = 500, body = ErrorResponse),
+    )
+)]
+#[post("/api/control/check")]
+pub async fn trigger_check(req: HttpRequest, control: web::Data<Arc<BotControl>>) -> impl Responder {
+    let actor = auth::actor(&req);
+    
+    control_response(control.request_check(&actor).await
+        .map(|_| CheckRequested { requested: true }))
+}
+
+/// Changes thresholds, trade size or check interval without a restart.
+#[utoipa::path(
+    tag = "control",
+    request_body = SettingsUpdate,
+    responses(
+        (status = 200, body = ApiResponse<RuntimeSettings>),
+        (status = 400, description = "Invalid value; nothing was changed", body = ErrorResponse),
+    )
+)]
+#[post("/api/control/settings")]
+pub async fn update_settings(
+    req: HttpRequest,
+    control: web::Data<Arc<BotControl>>,
+    update: web::Json<SettingsUpdate>,
+) -> impl Responder {
+    let actor = auth::actor(&req);
+    
+    match control.update_settings(&actor, update.into_inner()).await {
+        Ok(settings) => success(settings),
+        Err(e) => failure(StatusCode::BAD_REQUEST, format!("{:#}", e)),
+    }
+}
+
+/// The last 100 audited control changes.
+#[utoipa::path(
+    tag = "control",
+    responses(
+        (status = 200, body = ApiResponse<Vec<ControlAction>>),
+        (status = 500, body = ErrorResponse),
+    )
+)]
+#[get("/api/control/audit")]
+pub async fn get_control_audit(db: web::Data<Arc<dyn Store>>) -> impl Responder {
+    control_response(db.get_recent_control_actions(100).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::ApiAuth;
+    use crate::config::{ApiKeyConfig, ApiScope};
+    use crate::control::RuntimeSettings;
+    use crate::db::tests::opportunity;
+    use actix_web::{body::MessageBody, middleware::from_fn, test, App, HttpServer};
+    use futures_util::{SinkExt, StreamExt};
+    use tokio_tungstenite::tungstenite;
+
+    #[actix_web::test]
+    async fn test_sse_stream_filters_events() {
+        let events = Arc::new(EventBus::new(16));
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(events.clone()))
+                .service(stream_sse)
+        ).await;
+
+        let response = test::call_service(
+            &app,
+            test::TestRequest::get().uri("/api/stream?types=opportunity&min_profit=5").to_request(),
+        ).await;
+        assert!(response.status().is_success());
+        assert_eq!(response.headers().get(header::CONTENT_TYPE).unwrap(), "text/event-stream");
+
+        events.publish(BotEvent::Opportunity { opportunity: opportunity(1.0) });
+        events.publish(BotEvent::Opportunity { opportunity: opportunity(7.0) });
+
+        let body = response.into_body();
+        let mut body = std::pin::pin!(body);
+        let frame = std::future::poll_fn(|cx| body.as_mut().poll_next(cx)).await.unwrap().unwrap();
+        let frame = String::from_utf8(frame.to_vec()).unwrap();
+
+        assert!(frame.starts_with("event: opportunity\ndata: "));
+        let data: serde_json::Value = serde_json::from_str(frame.lines().nth(1).unwrap().trim_start_matches("data: ")).unwrap();
+        assert_eq!(data["opportunity"]["estimated_profit"], 7.0);
+    }
+
+    #[actix_web::test]
+    async fn test_sse_rejects_unknown_type() {
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(Arc::new(EventBus::new(16))))
+                .service(stream_sse)
+        ).await;
+
+        let response = test::call_service(&app, test::TestRequest::get().uri("/api/stream?types=trades").to_request()).await;
+        assert_eq!(response.status(), 400);
+    }
+
+    async fn control() -> (Arc<dyn Store>, Arc<BotControl>) {
+        let store = crate::db::open(&crate::db::tests::temp_sqlite_url()).await.unwrap();
+        let settings = RuntimeSettings {
+            min_profit_usdc: 1_000_000,
+            gas_estimate_usdc: 500_000,
+            trade_amount_wei: 1_000_000_000_000_000_000,
+            check_interval_seconds: 10,
+        };
+        let control = Arc::new(BotControl::new(store.clone(), settings));
+        (store, control)
+    }
+
+    #[actix_web::test]
+    async fn test_health_endpoints_follow_loop() {
+        let status = Arc::new(StatusTracker::new("https://polygon-rpc.com/secret", 30));
+        let (store, control) = control().await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(status.clone()))
+                .app_data(web::Data::new(control))
+                .app_data(web::Data::new(store))
+                .service(get_status)
+                .service(healthz)
+                .service(readyz)
+        ).await;
+
+        let get = |uri: &str| test::TestRequest::get().uri(uri).to_request();
+        assert_eq!(test::call_service(&app, get("/healthz")).await.status(), 200);
+        assert_eq!(test::call_service(&app, get("/readyz")).await.status(), 503);
+
+        status.record_failure(&anyhow::anyhow!("rpc timeout"));
+        status.record_success(42);
+        status.record_failure(&anyhow::anyhow!("rpc timeout"));
+        assert_eq!(test::call_service(&app, get("/readyz")).await.status(), 200);
+
+        let body: serde_json::Value = test::call_and_read_body_json(&app, get("/api/status")).await;
+        assert_eq!(body["data"]["last_block"], 42);
+        assert_eq!(body["data"]["iterations"], 3);
+        assert_eq!(body["data"]["consecutive_errors"], 1);
+        assert_eq!(body["data"]["rpc_endpoint"], "https://polygon-rpc.com");
+        assert_eq!(body["data"]["status"], "Checks failing: rpc timeout");
+        assert_eq!(body["data"]["paused"], false);
+    }
+
+    #[actix_web::test]
+    async fn test_control_endpoints() {
+        let status = Arc::new(StatusTracker::new("https://polygon-rpc.com", 30));
+        let (store, control) = control().await;
+        let api_auth = Arc::new(ApiAuth::new(vec![ApiKeyConfig {
+            name: "operator".to_string(),
+            token: "secret".to_string(),
+            scope: ApiScope::Admin,
+        }]));
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(api_auth))
+                .wrap(from_fn(auth::enforce))
+                .app_data(web::Data::new(status))
+                .app_data(web::Data::new(control.clone()))
+                .app_data(web::Data::new(store))
+                .service(get_status)
+                .service(pause_monitoring)
+                .service(update_settings)
+        ).await;
+
+        let pause = |token: &str| test::TestRequest::post()
+            .uri("/api/control/pause")
+            .insert_header((header::AUTHORIZATION, format!("Bearer {}", token)))
+            .to_request();
+        assert_eq!(test::call_service(&app, pause("wrong")).await.status(), 401);
+        assert!(!control.is_paused());
+        assert_eq!(test::call_service(&app, pause("secret")).await.status(), 200);
+        assert!(control.is_paused());
+
+        let invalid = test::TestRequest::post()
+            .uri("/api/control/settings")
+            .insert_header((header::AUTHORIZATION, "Bearer secret"))
+            .set_json(serde_json::json!({"min_profit_usdc": "lots"}))
+            .to_request();
+        assert_eq!(test::call_service(&app, invalid).await.status(), 400);
+
+        let body: serde_json::Value = test::call_and_read_body_json(
+            &app, test::TestRequest::get().uri("/api/status").insert_header(("X-API-Key", "secret")).to_request()).await;
+        assert_eq!(body["data"]["paused"], true);
+        assert_eq!(body["data"]["status"], "Monitoring paused");
+        assert_eq!(body["data"]["recent_changes"][0]["action"], "pause");
+        assert_eq!(body["data"]["recent_changes"][0]["actor"], "operator@unknown");
+        assert_eq!(body["data"]["settings"]["min_profit_usdc"], 1_000_000);
+    }
+
+    #[actix_web::test]
+    async fn test_export_download() {
+        let (store, _) = control().await;
+        store.store_opportunity(&opportunity(3.0)).await.unwrap();
+        let app = test::init_service(
+            App::new().app_data(web::Data::new(store)).service(export_data)
+        ).await;
+
+        let response = test::call_service(
+            &app, test::TestRequest::get().uri("/api/export?dataset=opportunities&format=jsonl").to_request()).await;
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.headers().get(header::CONTENT_TYPE).unwrap(), "application/x-ndjson");
+        let disposition = response.headers().get(header::CONTENT_DISPOSITION).unwrap().to_str().unwrap();
+        assert!(disposition.starts_with("attachment; filename=\"opportunities-") && disposition.ends_with(".jsonl\""));
+
+        let body = test::read_body(response).await;
+        let row: serde_json::Value = serde_json::from_slice(body.trim_ascii_end()).unwrap();
+        assert_eq!(row["estimated_profit"], 3.0);
+
+        let reversed = "/api/export?dataset=snapshots&from=2024-02-01T00:00:00Z&to=2024-01-01T00:00:00Z";
+        let response = test::call_service(&app, test::TestRequest::get().uri(reversed).to_request()).await;
+        assert_eq!(response.status(), 400);
+    }
+
+    #[actix_web::test]
+    async fn test_stats_count_detections_without_lifecycles() {
+        let (store, _) = control().await;
+        // Recorded before lifecycle tracking, so no lifecycle exists for either
+        store.store_opportunity(&opportunity(4.0)).await.unwrap();
+        store.store_opportunity(&opportunity(8.0)).await.unwrap();
+        let app = test::init_service(App::new().app_data(web::Data::new(store)).service(get_stats)).await;
+
+        let body: serde_json::Value = test::call_and_read_body_json(
+            &app, test::TestRequest::get().uri("/api/stats").to_request()).await;
+        assert_eq!(body["data"]["total_opportunities"], 2);
+        assert_eq!(body["data"]["distinct_opportunities"], 0);
+        assert_eq!(body["data"]["best_profit"], 8.0);
+    }
+
+    #[actix_web::test]
+    async fn test_metrics_endpoint() {
+        let app = test::init_service(App::new().service(get_metrics)).await;
+        metrics::get().opportunities_rejected.with_label_values(&["below_min_profit"]).inc();
+
+        let response = test::call_service(&app, test::TestRequest::get().uri("/metrics").to_request()).await;
+        assert_eq!(response.headers().get(header::CONTENT_TYPE).unwrap(), prometheus::TEXT_FORMAT);
+
+        let body = String::from_utf8(test::read_body(response).await.to_vec()).unwrap();
+        assert!(body.contains(r#"arb_opportunities_rejected_total{reason="below_min_profit"}"#));
+    }
+
+    #[actix_web::test]
+    async fn test_websocket_subscription() {
+        let events = Arc::new(EventBus::new(16));
+        let events_for_server = events.clone();
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(web::Data::new(events_for_server.clone()))
+                .service(stream_ws)
+        })
+        .workers(1)
+        .bind(("127.0.0.1", 0))
+        .unwrap();
+        let addr = server.addrs()[0];
+        actix_web::rt::spawn(server.run());
+
+        let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{}/api/ws", addr)).await.unwrap();
+        socket.send(tungstenite::Message::Text(r#"{"types": ["status"]}"#.to_string())).await.unwrap();
+
+        let ack: serde_json::Value = serde_json::from_str(&socket.next().await.unwrap().unwrap().into_text().unwrap()).unwrap();
+        assert_eq!(ack["type"], "subscribed");
+
+        events.publish(BotEvent::Opportunity { opportunity: opportunity(7.0) });
+        events.publish(BotEvent::Status { healthy: false, message: Some("rpc down".to_string()), at: Utc::now() });
+
+        let event: serde_json::Value = serde_json::from_str(&socket.next().await.unwrap().unwrap().into_text().unwrap()).unwrap();
+        assert_eq!(event["type"], "status");
+        assert_eq!(event["message"], "rpc down");
+    }
+}
@@ -1,85 +1,181 @@
-use crate::config::AppConfig;
-use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
-use uuid::Uuid;
-use log::debug;
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ArbitrageOpportunity {
-    pub id: String,
-    pub timestamp: DateTime<Utc>,
-    pub buy_exchange: String,
-    pub sell_exchange: String,
-    pub buy_price: f64,
-    pub sell_price: f64,
-    pub estimated_profit: f64,
-    pub price_difference: f64,
-    pub gas_cost_estimate: f64,
-    pub profit_percentage: f64,
-}
-
-pub struct ArbitrageDetector {
-    min_profit_threshold: f64,
-    gas_cost_estimate: f64,
-}
-
-impl ArbitrageDetector {
-    pub fn new(config: &AppConfig) -> Self {
-        let min_profit = config.trading.min_profit_usdc.parse::<u64>().unwrap() as f64 / 1_000_000.0;
-        let gas_cost = config.trading.gas_estimate_usdc.parse::<u64>().unwrap() as f64 / 1_000_000.0;
-        
-        debug!("Arbitrage detector initialized:");
-        debug!("  Min profit threshold: {:.6} USDC", min_profit);
-        debug!("  Gas cost estimate: {:.6} USDC", gas_cost);
-        
-        Self {
-            min_profit_threshold: min_profit,
-            gas_cost_estimate: gas_cost,
-        }
-    }
-    
-    pub fn detect_opportunity(
-        &self,
-        quickswap_price: f64,
-        sushiswap_price: f64,
-    ) -> Option<ArbitrageOpportunity> {
-        let price_difference = (quickswap_price - sushiswap_price).abs();
-        let estimated_profit = price_difference - self.gas_cost_estimate;
-        
-        debug!("Arbitrage analysis:");
-        debug!("  QuickSwap price: {:.6} USDC", quickswap_price);
-        debug!("  SushiSwap price: {:.6} USDC", sushiswap_price);
-        debug!("  Price difference: {:.6} USDC", price_difference);
-        debug!("  Gas cost estimate: {:.6} USDC", self.gas_cost_estimate);
-        debug!("  Estimated profit: {:.6} USDC", estimated_profit);
-        debug!("  Min profit threshold: {:.6} USDC", self.min_profit_threshold);
-        
-        if estimated_profit > self.min_profit_threshold {
-            let (buy_exchange, sell_exchange, buy_price, sell_price) = 
-                if quickswap_price < sushiswap_price {
-                    ("QuickSwap".to_string(), "SushiSwap".to_string(), quickswap_price, sushiswap_price)
-                } else {
-                    ("SushiSwap".to_string(), "QuickSwap".to_string(), sushiswap_price, quickswap_price)
-                };
-            
-            let profit_percentage = (estimated_profit / buy_price) * 100.0;
-            
-            Some(ArbitrageOpportunity {
-                id: Uuid::new_v4().to_string(),
-                timestamp: Utc::now(),
-                buy_exchange,
-                sell_exchange,
-                buy_price,
-                sell_price,
-                estimated_profit,
-                price_difference,
-                gas_cost_estimate: self.gas_cost_estimate,
-                profit_percentage,
-            })
-        } else {
-            debug!("No profitable opportunity found (profit {} < threshold {})",
-                   estimated_profit, self.min_profit_threshold);
-            None
-        }
-    }
-}
+use crate::config::AppConfig;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use tracing::debug;
+
+/// The token pair currently monitored on every venue.
+pub const MONITORED_PAIR: &str = "WETH/USDC";
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ArbitrageOpportunity {
+    pub id: String,
+    pub timestamp: DateTime<Utc>,
+    pub pair: String,
+    pub block_number: Option<u64>,
+    pub buy_exchange: String,
+    pub sell_exchange: String,
+    pub buy_price: f64,
+    pub sell_price: f64,
+    pub estimated_profit: f64,
+    pub price_difference: f64,
+    pub gas_cost_estimate: f64,
+    pub profit_percentage: f64,
+}
+
+/// Why a price difference was not reported as an opportunity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rejection {
+    /// The spread does not cover the gas estimate
+    UnprofitableAfterGas,
+    /// Profitable after gas, but below the configured threshold
+    BelowMinProfit,
+}
+
+impl Rejection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Rejection::UnprofitableAfterGas => "unprofitable_after_gas",
+            Rejection::BelowMinProfit => "below_min_profit",
+        }
+    }
+}
+
+/// An opportunity predicted from a pending swap, to be confirmed or refuted
+/// once its target block is mined.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PredictedOpportunity {
+    pub opportunity: ArbitrageOpportunity,
+    pub trigger_tx: String,
+    pub trigger_venue: String,
+    pub target_block: u64,
+    pub confirmed: Option<bool>,
+    /// The target block passed unobserved, so the prediction was never scored
+    #[serde(default)]
+    pub expired: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Thresholds {
+    min_profit: f64,
+    gas_cost: f64,
+}
+
+pub struct ArbitrageDetector {
+    // Adjustable at runtime through the control API
+    thresholds: RwLock<Thresholds>,
+}
+
+impl ArbitrageDetector {
+    pub fn new(config: &AppConfig) -> Self {
+        let detector = Self::from_base_units(
+            config.trading.min_profit_usdc.parse::<u64>().unwrap(),
+            config.trading.gas_estimate_usdc.parse::<u64>().unwrap(),
+        );
+        
+        let thresholds = detector.thresholds();
+        debug!(min_profit = thresholds.min_profit, gas_cost = thresholds.gas_cost, "Arbitrage detector initialized");
+        
+        detector
+    }
+    
+    /// Builds a detector from USDC base-unit (6 decimal) amounts.
+    pub fn from_base_units(min_profit_usdc: u64, gas_estimate_usdc: u64) -> Self {
+        Self {
+            thresholds: RwLock::new(Self::to_thresholds(min_profit_usdc, gas_estimate_usdc)),
+        }
+    }
+    
+    pub fn set_thresholds(&self, min_profit_usdc: u64, gas_estimate_usdc: u64) {
+        *self.thresholds.write().unwrap() = Self::to_thresholds(min_profit_usdc, gas_estimate_usdc);
+    }
+    
+    fn to_thresholds(min_profit_usdc: u64, gas_estimate_usdc: u64) -> Thresholds {
+        Thresholds {
+            min_profit: min_profit_usdc as f64 / 1_000_000.0,
+            gas_cost: gas_estimate_usdc as f64 / 1_000_000.0,
+        }
+    }
+    
+    fn thresholds(&self) -> Thresholds {
+        *self.thresholds.read().unwrap()
+    }
+    
+    pub fn detect_opportunity(
+        &self,
+        quickswap_price: f64,
+        sushiswap_price: f64,
+    ) -> Option<ArbitrageOpportunity> {
+        self.evaluate(quickswap_price, sushiswap_price).ok()
+    }
+    
+    /// Like `detect_opportunity`, but says why a spread was not worth trading.
+    #[tracing::instrument(skip(self))]
+    pub fn evaluate(
+        &self,
+        quickswap_price: f64,
+        sushiswap_price: f64,
+    ) -> Result<ArbitrageOpportunity, Rejection> {
+        let Thresholds { min_profit, gas_cost } = self.thresholds();
+        let price_difference = (quickswap_price - sushiswap_price).abs();
+        let estimated_profit = price_difference - gas_cost;
+        
+        debug!(price_difference, gas_cost, estimated_profit, min_profit, "Arbitrage analysis");
+        
+        if estimated_profit > min_profit {
+            let (buy_exchange, sell_exchange, buy_price, sell_price) = 
+                if quickswap_price < sushiswap_price {
+                    ("QuickSwap".to_string(), "SushiSwap".to_string(), quickswap_price, sushiswap_price)
+                } else {
+                    ("SushiSwap".to_string(), "QuickSwap".to_string(), sushiswap_price, quickswap_price)
+                };
+            
+            let profit_percentage = (estimated_profit / buy_price) * 100.0;
+            
+            Ok(ArbitrageOpportunity {
+                id: Uuid::new_v4().to_string(),
+                timestamp: Utc::now(),
+                pair: MONITORED_PAIR.to_string(),
+                block_number: None,
+                buy_exchange,
+                sell_exchange,
+                buy_price,
+                sell_price,
+                estimated_profit,
+                price_difference,
+                gas_cost_estimate: gas_cost,
+                profit_percentage,
+            })
+        } else {
+            debug!("No profitable opportunity found (profit {} < threshold {})",
+                   estimated_profit, min_profit);
+            if estimated_profit <= 0.0 {
+                Err(Rejection::UnprofitableAfterGas)
+            } else {
+                Err(Rejection::BelowMinProfit)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rejection_reasons() {
+        let detector = ArbitrageDetector::from_base_units(1_000_000, 500_000);
+
+        assert_eq!(detector.evaluate(3000.0, 3000.3).unwrap_err(), Rejection::UnprofitableAfterGas);
+        assert_eq!(detector.evaluate(3000.0, 3001.2).unwrap_err(), Rejection::BelowMinProfit);
+
+        let opportunity = detector.evaluate(3002.0, 3000.0).unwrap();
+        assert_eq!(opportunity.buy_exchange, "SushiSwap");
+        assert!((opportunity.estimated_profit - 1.5).abs() < 1e-9);
+
+        detector.set_thresholds(2_000_000, 500_000);
+        assert_eq!(detector.evaluate(3002.0, 3000.0).unwrap_err(), Rejection::BelowMinProfit);
+    }
+}
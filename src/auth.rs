@@ -0,0 +1,272 @@
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::{header, Method};
+use actix_web::middleware::Next;
+use actix_web::{web, Error, HttpMessage, HttpRequest, HttpResponse};
+use tracing::warn;
+use std::sync::Arc;
+
+use crate::config::{ApiKeyConfig, ApiScope};
+
+/// Routes left open for load balancer and orchestrator probes, and the API
+/// documentation.
+const PUBLIC_PATHS: &[&str] = &["/healthz", "/readyz", "/api/openapi.json", "/api/docs"];
+
+/// Query parameter accepted in place of a header, for browser clients such
+/// as `EventSource` and `WebSocket` that cannot set one.
+const TOKEN_QUERY_PARAM: &str = "access_token";
+
+/// The API key a request was authenticated with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Principal {
+    pub name: String,
+    pub scope: ApiScope,
+}
+
+/// API keys from the `[api]` config section.
+pub struct ApiAuth {
+    keys: Vec<ApiKeyConfig>,
+}
+
+impl ApiAuth {
+    pub fn new(keys: Vec<ApiKeyConfig>) -> Self {
+        Self { keys }
+    }
+
+    /// Without keys, read endpoints stay open for local use.
+    pub fn is_enabled(&self) -> bool {
+        !self.keys.is_empty()
+    }
+
+    pub fn authenticate(&self, presented: &str) -> Option<Principal> {
+        self.keys.iter()
+            .find(|key| constant_time_eq(key.token.as_bytes(), presented.as_bytes()))
+            .map(|key| Principal { name: key.name.clone(), scope: key.scope })
+    }
+
+    fn authorize(&self, req: &ServiceRequest, required: ApiScope) -> Result<Option<Principal>, Rejection> {
+        let principal = presented_token(req).and_then(|token| self.authenticate(&token));
+
+        match principal {
+            Some(principal) if principal.scope >= required => Ok(Some(principal)),
+            Some(principal) => Err(Rejection::InsufficientScope(principal.name)),
+            None if !self.is_enabled() && required == ApiScope::Read => Ok(None),
+            None if !self.is_enabled() => Err(Rejection::AdminDisabled),
+            None => Err(Rejection::Unauthenticated),
+        }
+    }
+}
+
+/// Why a request was turned away.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Rejection {
+    Unauthenticated,
+    InsufficientScope(String),
+    /// No keys are configured, so nobody holds the admin scope
+    AdminDisabled,
+}
+
+impl Rejection {
+    fn into_response(self) -> HttpResponse {
+        match self {
+            Rejection::Unauthenticated => HttpResponse::Unauthorized()
+                .insert_header((header::WWW_AUTHENTICATE, "Bearer"))
+                .json(serde_json::json!({"success": false, "error": "Invalid or missing API key"})),
+            Rejection::InsufficientScope(name) => HttpResponse::Forbidden().json(serde_json::json!({
+                "success": false,
+                "error": format!("API key {} lacks the admin scope", name)
+            })),
+            Rejection::AdminDisabled => HttpResponse::Forbidden().json(serde_json::json!({
+                "success": false,
+                "error": "Control API is disabled; configure an admin key under [[api.keys]] to enable it"
+            })),
+        }
+    }
+}
+
+/// Compares without short-circuiting on the first differing byte.
+fn constant_time_eq(expected: &[u8], presented: &[u8]) -> bool {
+    expected.len() == presented.len()
+        && expected.iter().zip(presented).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+fn presented_token(req: &ServiceRequest) -> Option<String> {
+    let headers = req.headers();
+
+    if let Some(bearer) = headers.get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+    {
+        return Some(bearer.trim().to_string());
+    }
+
+    if let Some(key) = headers.get("X-API-Key").and_then(|value| value.to_str().ok()) {
+        return Some(key.trim().to_string());
+    }
+
+    web::Query::<std::collections::HashMap<String, String>>::from_query(req.query_string())
+        .ok()
+        .and_then(|query| query.get(TOKEN_QUERY_PARAM).cloned())
+}
+
+/// The scope a route needs, or `None` for routes anyone may call.
+pub fn required_scope(method: &Method, path: &str) -> Option<ApiScope> {
+    if *method == Method::OPTIONS || PUBLIC_PATHS.contains(&path) {
+        None
+    } else if path.starts_with("/api/control") {
+        Some(ApiScope::Admin)
+    } else {
+        Some(ApiScope::Read)
+    }
+}
+
+/// Middleware enforcing API key scopes on every route. Authenticated
+/// requests carry their `Principal` in the request extensions.
+pub async fn enforce(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    // The router matches the percent-decoded path, so scopes must too;
+    // `req.path()` is the raw URI and `/api/%63ontrol` would slip past.
+    let Some(required) = required_scope(req.method(), req.match_info().as_str()) else {
+        return next.call(req).await.map(ServiceResponse::map_into_left_body);
+    };
+
+    let Some(auth) = req.app_data::<web::Data<Arc<ApiAuth>>>().cloned() else {
+        return Err(actix_web::error::ErrorInternalServerError("API authentication is not configured"));
+    };
+
+    match auth.authorize(&req, required) {
+        Ok(principal) => {
+            if let Some(principal) = principal {
+                req.extensions_mut().insert(principal);
+            }
+            next.call(req).await.map(ServiceResponse::map_into_left_body)
+        },
+        Err(rejection) => {
+            warn!("API: Rejected {} {} from {:?}: {:?}", req.method(), req.path(), req.peer_addr(), rejection);
+            Ok(req.into_response(rejection.into_response()).map_into_right_body())
+        }
+    }
+}
+
+/// Who made a request, for the control audit trail.
+pub fn actor(req: &HttpRequest) -> String {
+    let name = req.extensions().get::<Principal>()
+        .map(|principal| principal.name.clone())
+        .unwrap_or_else(|| "anonymous".to_string());
+    let peer = req.peer_addr().map(|addr| addr.ip().to_string()).unwrap_or_else(|| "unknown".to_string());
+
+    format!("{}@{}", name, peer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{get, middleware::from_fn, post, test, App, HttpResponse, Responder};
+
+    #[get("/api/stats")]
+    async fn stats() -> impl Responder {
+        HttpResponse::Ok().finish()
+    }
+
+    #[get("/api/control/whoami")]
+    async fn whoami(req: HttpRequest) -> impl Responder {
+        HttpResponse::Ok().body(actor(&req))
+    }
+
+    #[post("/api/control/pause")]
+    async fn pause() -> impl Responder {
+        HttpResponse::Ok().finish()
+    }
+
+    #[get("/healthz")]
+    async fn health() -> impl Responder {
+        HttpResponse::Ok().finish()
+    }
+
+    fn key(name: &str, token: &str, scope: ApiScope) -> ApiKeyConfig {
+        ApiKeyConfig { name: name.to_string(), token: token.to_string(), scope }
+    }
+
+    #[actix_web::test]
+    async fn test_scopes_are_enforced() {
+        let auth = Arc::new(ApiAuth::new(vec![
+            key("dashboard", "read-token", ApiScope::Read),
+            key("operator", "admin-token", ApiScope::Admin),
+        ]));
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(auth))
+                .wrap(from_fn(enforce))
+                .service(stats)
+                .service(whoami)
+                .service(health)
+        ).await;
+
+        let get = |uri: &str, token: Option<&str>| {
+            let request = test::TestRequest::get().uri(uri);
+            match token {
+                Some(token) => request.insert_header((header::AUTHORIZATION, format!("Bearer {}", token))),
+                None => request,
+            }.to_request()
+        };
+
+        assert_eq!(test::call_service(&app, get("/healthz", None)).await.status(), 200);
+        assert_eq!(test::call_service(&app, get("/api/stats", None)).await.status(), 401);
+        assert_eq!(test::call_service(&app, get("/api/stats", Some("wrong"))).await.status(), 401);
+        assert_eq!(test::call_service(&app, get("/api/stats", Some("read-token"))).await.status(), 200);
+        assert_eq!(test::call_service(&app, get("/api/stats?access_token=read-token", None)).await.status(), 200);
+        assert_eq!(test::call_service(&app, get("/api/control/whoami", Some("read-token"))).await.status(), 403);
+
+        let with_key = test::TestRequest::get()
+            .uri("/api/control/whoami")
+            .insert_header(("X-API-Key", "admin-token"))
+            .peer_addr("10.0.0.7:5000".parse().unwrap())
+            .to_request();
+        let body = test::call_and_read_body(&app, with_key).await;
+        assert_eq!(body, "operator@10.0.0.7");
+    }
+
+    #[actix_web::test]
+    async fn test_no_keys_allows_reads_only() {
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(Arc::new(ApiAuth::new(Vec::new()))))
+                .wrap(from_fn(enforce))
+                .service(stats)
+                .service(whoami)
+        ).await;
+
+        let get = |uri: &str| test::TestRequest::get().uri(uri).to_request();
+        assert_eq!(test::call_service(&app, get("/api/stats")).await.status(), 200);
+        assert_eq!(test::call_service(&app, get("/api/control/whoami")).await.status(), 403);
+    }
+
+    #[actix_web::test]
+    async fn test_encoded_control_paths_need_admin() {
+        let keyed = test::init_service(
+            App::new()
+                .app_data(web::Data::new(Arc::new(ApiAuth::new(vec![key("dashboard", "read-token", ApiScope::Read)]))))
+                .wrap(from_fn(enforce))
+                .service(pause)
+        ).await;
+        let open = test::init_service(
+            App::new()
+                .app_data(web::Data::new(Arc::new(ApiAuth::new(Vec::new()))))
+                .wrap(from_fn(enforce))
+                .service(pause)
+        ).await;
+
+        for uri in ["/api/control/pause", "/api/%63ontrol/pause", "/api/%63%6Fntrol/%70ause", "/api/control%2Fpause"] {
+            let request = test::TestRequest::post()
+                .uri(uri)
+                .insert_header((header::AUTHORIZATION, "Bearer read-token"))
+                .to_request();
+            assert_eq!(test::call_service(&keyed, request).await.status(), 403, "{}", uri);
+
+            let request = test::TestRequest::post().uri(uri).to_request();
+            assert_eq!(test::call_service(&open, request).await.status(), 403, "{}", uri);
+        }
+    }
+}
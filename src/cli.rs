@@ -0,0 +1,599 @@
+//! Command-line interface. Every subcommand but `run` does one thing and
+//! exits, printing text or, with `--json`, a single JSON document on stdout;
+//! logs always go to stderr.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use clap::{Parser, Subcommand};
+use ethers::providers::Middleware;
+use ethers::types::Bytes;
+use futures_util::TryStreamExt;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+use crate::arbitrage::{ArbitrageDetector, ArbitrageOpportunity, MONITORED_PAIR};
+use crate::config::{AppConfig, ValidationErrors};
+use crate::db::{self, MigrationStatus};
+use crate::export::{self, ExportDataset, ExportFormat, ExportRequest};
+use crate::price_fetcher::PriceFetcher;
+use crate::relay::{RelayStats, RelaySubmitter, Submission};
+use crate::rpc;
+use crate::snapshots::{PriceSnapshot, SnapshotRecorder};
+use crate::verify::{self, ContractCheck, VerificationReport};
+
+#[derive(Parser)]
+#[command(name = "polygon-arbitrage-bot")]
+#[command(about = "A Polygon arbitrage opportunity detection bot")]
+pub struct Cli {
+    #[arg(short, long, global = true, default_value = "config/config.toml")]
+    pub config: String,
+
+    /// Overlay e.g. config.prod.toml from next to the config file
+    #[arg(short, long, global = true, env = "ARB_PROFILE")]
+    pub profile: Option<String>,
+
+    /// Print the effective config, with secrets redacted, and exit
+    #[arg(long)]
+    pub print_config: bool,
+
+    #[arg(short, long, global = true)]
+    pub debug: bool,
+
+    /// Print command output as JSON
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    /// Defaults to `run`
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Monitor the venues and serve the HTTP API
+    Run {
+        /// Monitor without serving the HTTP API
+        #[arg(long, conflicts_with = "api_only")]
+        no_api: bool,
+        /// Serve the HTTP API from the database without monitoring
+        #[arg(long)]
+        api_only: bool,
+    },
+    /// Monitor the venues with a live dashboard in the terminal
+    Tui {
+        /// Monitor without serving the HTTP API
+        #[arg(long)]
+        no_api: bool,
+        /// Where logs go while the dashboard has the terminal
+        #[arg(long, default_value = "arbitrage-bot.log")]
+        log_file: PathBuf,
+    },
+    /// Quote every venue once and print the prices side by side
+    Quote,
+    /// Validate the config and verify the configured contracts on-chain
+    Check,
+    /// Send signed transactions through the private relays and wait for them
+    /// to be mined
+    Submit {
+        /// Signed raw transactions as 0x-prefixed hex, in execution order
+        #[arg(required = true)]
+        txs: Vec<Bytes>,
+        /// Block the transactions should land in; defaults to the next one
+        #[arg(long)]
+        target_block: Option<u64>,
+    },
+    /// Inspect and maintain the database
+    Db {
+        #[command(subcommand)]
+        action: DbAction,
+    },
+    /// Write opportunities or price snapshots for a time range to a file
+    Export {
+        #[arg(value_enum)]
+        dataset: ExportDataset,
+        #[arg(short, long, value_enum, default_value_t = ExportFormat::Csv)]
+        format: ExportFormat,
+        /// Start of the range (RFC 3339); defaults to the earliest record
+        #[arg(long)]
+        from: Option<DateTime<Utc>>,
+        /// End of the range (RFC 3339); defaults to now
+        #[arg(long)]
+        to: Option<DateTime<Utc>>,
+        /// Output file; writes to stdout when omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DbAction {
+    /// Count stored opportunities, predictions and lifecycles
+    Stats,
+    /// Apply the snapshot downsampling and retention policies now
+    Prune {
+        /// Delete every price snapshot taken before this time (RFC 3339)
+        /// instead of applying the configured policies
+        #[arg(long)]
+        before: Option<DateTime<Utc>>,
+    },
+    /// Inspect or apply schema migrations
+    Migrate {
+        #[command(subcommand)]
+        action: MigrateAction,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum MigrateAction {
+    /// Apply all pending migrations
+    Apply,
+    /// List applied and pending migrations
+    Status,
+}
+
+/// Which halves of the bot `run` starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunMode {
+    Full,
+    NoApi,
+    ApiOnly,
+}
+
+impl RunMode {
+    pub fn from_flags(no_api: bool, api_only: bool) -> Self {
+        match (no_api, api_only) {
+            (true, _) => RunMode::NoApi,
+            (_, true) => RunMode::ApiOnly,
+            _ => RunMode::Full,
+        }
+    }
+
+    pub fn monitors(self) -> bool {
+        self != RunMode::ApiOnly
+    }
+
+    pub fn serves_api(self) -> bool {
+        self != RunMode::NoApi
+    }
+}
+
+/// Prints `value` as JSON with `--json`, otherwise as `render` formats it.
+fn emit<T: Serialize>(json: bool, value: &T, render: impl FnOnce(&T) -> String) -> Result<()> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(value)?);
+    } else {
+        print!("{}", render(value));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+pub struct QuoteTable {
+    pub block_number: u64,
+    pub trade_amount: String,
+    pub pairs: Vec<PairQuotes>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PairQuotes {
+    pub pair: String,
+    pub quotes: Vec<PriceSnapshot>,
+    pub failures: Vec<VenueFailure>,
+    /// Price difference between the venues, when all of them quoted
+    pub spread: Option<f64>,
+    /// What the detector would report for this spread
+    pub opportunity: Option<ArbitrageOpportunity>,
+    /// Why the detector would not report it
+    pub rejection: Option<&'static str>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VenueFailure {
+    pub venue: String,
+    pub error: String,
+}
+
+/// Takes one quote from every venue at the configured trade amount. Fails
+/// after printing if any venue could not quote.
+pub async fn quote(config: &AppConfig, json: bool) -> Result<()> {
+    let fetcher = PriceFetcher::new(config).await?;
+    let detector = ArbitrageDetector::new(config);
+
+    let (quickswap, sushiswap, block) = tokio::join!(
+        fetcher.get_quickswap_quote(),
+        fetcher.get_sushiswap_quote(),
+        fetcher.get_block_number()
+    );
+    let block_number = block?;
+
+    let (check_id, quoted_at) = (uuid::Uuid::new_v4().to_string(), Utc::now());
+    let mut pair = PairQuotes {
+        pair: MONITORED_PAIR.to_string(),
+        quotes: Vec::new(),
+        failures: Vec::new(),
+        spread: None,
+        opportunity: None,
+        rejection: None,
+    };
+    for (venue, result) in [("QuickSwap", &quickswap), ("SushiSwap", &sushiswap)] {
+        match result {
+            Ok(quote) => pair.quotes.push(PriceSnapshot::from_quote(&check_id, quoted_at, block_number, MONITORED_PAIR, quote)),
+            Err(e) => pair.failures.push(VenueFailure { venue: venue.to_string(), error: format!("{:#}", e) }),
+        }
+    }
+    if let (Ok(quickswap), Ok(sushiswap)) = (&quickswap, &sushiswap) {
+        pair.spread = Some((quickswap.price - sushiswap.price).abs());
+        match detector.evaluate(quickswap.price, sushiswap.price) {
+            Ok(opportunity) => pair.opportunity = Some(opportunity),
+            Err(rejection) => pair.rejection = Some(rejection.as_str()),
+        }
+    }
+
+    let failed = pair.failures.len();
+    let table = QuoteTable {
+        block_number,
+        trade_amount: format!("{} WETH", ethers::utils::format_ether(fetcher.trade_amount())),
+        pairs: vec![pair],
+    };
+    emit(json, &table, render_quotes)?;
+
+    if failed > 0 {
+        anyhow::bail!("{} venue quote{} failed", failed, if failed == 1 { "" } else { "s" });
+    }
+    Ok(())
+}
+
+fn render_quotes(table: &QuoteTable) -> String {
+    let mut out = format!("Quotes for {} at block {}\n", table.trade_amount, table.block_number);
+    for pair in &table.pairs {
+        let _ = writeln!(out, "\n{}", pair.pair);
+        for quote in &pair.quotes {
+            let _ = writeln!(out, "  {:<10} {:>14.6}  {:>6.0} ms", quote.venue, quote.price, quote.latency_ms);
+        }
+        for failure in &pair.failures {
+            let _ = writeln!(out, "  {:<10} failed: {}", failure.venue, failure.error);
+        }
+        match (&pair.spread, &pair.opportunity, &pair.rejection) {
+            (Some(spread), Some(opportunity), _) => {
+                let _ = writeln!(out, "  spread {:.6}: buy on {}, sell on {} for an estimated {:.6} profit",
+                                 spread, opportunity.buy_exchange, opportunity.sell_exchange, opportunity.estimated_profit);
+            },
+            (Some(spread), None, Some(rejection)) => {
+                let _ = writeln!(out, "  spread {:.6}: {}", spread, rejection);
+            },
+            _ => {},
+        }
+    }
+    out
+}
+
+#[derive(Debug, Serialize)]
+pub struct CheckReport {
+    pub ok: bool,
+    pub config_errors: Vec<ConfigError>,
+    /// Chain ID the RPC reported, if it was reached
+    pub chain_id: Option<u64>,
+    pub contracts: Vec<ContractCheck>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConfigError {
+    pub path: String,
+    pub message: String,
+}
+
+/// Confirms the RPC serves the configured chain and verifies the contracts.
+pub async fn check(config: &AppConfig, json: bool) -> Result<()> {
+    let provider = Arc::new(rpc::http_provider(&config.network.rpc_url)?);
+    let chain_id = provider.get_chainid().await
+        .with_context(|| "Failed to get chain ID from provider")?
+        .as_u64();
+
+    let mut report = CheckReport { ok: true, config_errors: Vec::new(), chain_id: Some(chain_id), contracts: Vec::new() };
+    if chain_id == config.network.chain_id {
+        report.contracts = verify::verify_contracts(provider, config).await?.checks;
+    } else {
+        report.config_errors.push(ConfigError {
+            path: "network.chain_id".to_string(),
+            message: format!("is {}, but the RPC serves chain {}", config.network.chain_id, chain_id),
+        });
+    }
+    finish_check(report, json)
+}
+
+/// Reports a config that failed to load for `check`. Without `--json` the
+/// error already says everything.
+pub fn check_unloadable(error: anyhow::Error, json: bool) -> Result<()> {
+    if !json {
+        return Err(error);
+    }
+
+    let config_errors = match error.downcast_ref::<ValidationErrors>() {
+        Some(errors) => errors.iter()
+            .map(|(path, message)| ConfigError { path: path.clone(), message: message.clone() })
+            .collect(),
+        None => vec![ConfigError { path: String::new(), message: format!("{:#}", error) }],
+    };
+    finish_check(CheckReport { ok: false, config_errors, chain_id: None, contracts: Vec::new() }, json)
+}
+
+fn finish_check(mut report: CheckReport, json: bool) -> Result<()> {
+    report.ok = report.config_errors.is_empty() && report.contracts.iter().all(|check| check.ok);
+    emit(json, &report, render_check)?;
+
+    if !report.ok {
+        let failed = report.config_errors.len() + report.contracts.iter().filter(|check| !check.ok).count();
+        anyhow::bail!("Check failed with {} problem{}", failed, if failed == 1 { "" } else { "s" });
+    }
+    Ok(())
+}
+
+fn render_check(report: &CheckReport) -> String {
+    let mut out = String::new();
+    for error in &report.config_errors {
+        let _ = writeln!(out, "  FAIL  {}: {}", error.path, error.message);
+    }
+    out.push_str(&VerificationReport { checks: report.contracts.clone() }.to_string());
+    if report.ok {
+        let _ = writeln!(out, "All {} checks passed on chain {}", report.contracts.len(), report.chain_id.unwrap_or_default());
+    }
+    out
+}
+
+/// How often `submit` polls for receipts; about one Polygon block
+const INCLUSION_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Serialize)]
+pub struct SubmitReport {
+    #[serde(flatten)]
+    pub submission: Submission,
+    /// Counters per relay, plus `public` for broadcasts
+    pub stats: HashMap<String, RelayStats>,
+}
+
+/// Submits signed transactions and polls until they are mined or the last
+/// block the relays were asked to include them in has passed.
+pub async fn submit(config: &AppConfig, txs: Vec<Bytes>, target_block: Option<u64>, json: bool) -> Result<()> {
+    let provider = Arc::new(rpc::http_provider(&config.network.rpc_url)?);
+    let submitter = RelaySubmitter::new(&config.relay, Some(provider.clone()))?;
+    let target_block = match target_block {
+        Some(block) => block,
+        None => provider.get_block_number().await
+            .with_context(|| "Failed to fetch current block number")?.as_u64() + 1,
+    };
+    let last_block = target_block + config.relay.max_block_offset;
+
+    let mut submission = submitter.submit(&txs, target_block).await?;
+    let mut poll = tokio::time::interval(INCLUSION_POLL_INTERVAL);
+    loop {
+        poll.tick().await;
+        if submitter.check_inclusion(&mut submission).await? {
+            break;
+        }
+        let block = provider.get_block_number().await
+            .with_context(|| "Failed to fetch current block number")?.as_u64();
+        if block > last_block {
+            break;
+        }
+    }
+
+    let included = submission.included;
+    emit(json, &SubmitReport { submission, stats: submitter.stats() }, render_submit)?;
+
+    if !included {
+        anyhow::bail!("Not included by block {}", last_block);
+    }
+    Ok(())
+}
+
+fn render_submit(report: &SubmitReport) -> String {
+    let submission = &report.submission;
+    let mut out = format!("Submitted {} transaction{} for block {} via {}
+",
+                          submission.tx_hashes.len(), if submission.tx_hashes.len() == 1 { "" } else { "s" },
+                          submission.target_block, submission.accepted_by.join(", "));
+    for hash in &submission.tx_hashes {
+        let _ = writeln!(out, "  {:?}", hash);
+    }
+    let _ = writeln!(out, "{}", if submission.included { "Included" } else { "Not included" });
+
+    let mut routes: Vec<_> = report.stats.iter().collect();
+    routes.sort_by_key(|(route, _)| route.as_str());
+    for (route, stats) in routes {
+        let _ = writeln!(out, "  {:<16} {} submitted, {} accepted, {} failed, {} included",
+                         route, stats.submitted, stats.accepted, stats.failed, stats.included);
+    }
+    out
+}
+
+#[derive(Debug, Serialize)]
+pub struct DbStats {
+    pub schema_version: i64,
+    pub opportunities: OpportunityCounts,
+    pub predictions: PredictionCounts,
+    pub lifecycles: LifecycleCounts,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OpportunityCounts {
+    pub total: i64,
+    pub average_profit: f64,
+    pub best_profit: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PredictionCounts {
+    pub total: i64,
+    pub confirmed: i64,
+    pub refuted: i64,
+    pub expired: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LifecycleCounts {
+    pub total: i64,
+    pub open: i64,
+    pub average_duration_seconds: f64,
+    pub average_observations: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PruneResult {
+    pub downsampled: u64,
+    pub pruned: u64,
+}
+
+pub async fn db(config: &AppConfig, action: DbAction, json: bool) -> Result<()> {
+    let database = db::connect(&config.database.url).await?;
+
+    match action {
+        DbAction::Stats => {
+            let (total, average_profit, best_profit) = database.get_stats().await?;
+            let (predictions, confirmed, refuted, expired) = database.get_prediction_stats().await?;
+            let (lifecycles, open, average_duration_seconds, average_observations) = database.get_lifecycle_stats().await?;
+            let stats = DbStats {
+                schema_version: database.schema_version().await?,
+                opportunities: OpportunityCounts { total, average_profit, best_profit },
+                predictions: PredictionCounts { total: predictions, confirmed, refuted, expired },
+                lifecycles: LifecycleCounts { total: lifecycles, open, average_duration_seconds, average_observations },
+            };
+            emit(json, &stats, |stats| format!(
+                "Schema version  {}\nOpportunities   {} (average profit {:.2}, best {:.2})\nPredictions     {} ({} confirmed, {} refuted, {} expired)\nLifecycles      {} ({} open, {:.0}s and {:.1} observations on average)\n",
+                stats.schema_version,
+                stats.opportunities.total, stats.opportunities.average_profit, stats.opportunities.best_profit,
+                stats.predictions.total, stats.predictions.confirmed, stats.predictions.refuted, stats.predictions.expired,
+                stats.lifecycles.total, stats.lifecycles.open, stats.lifecycles.average_duration_seconds, stats.lifecycles.average_observations,
+            ))
+        },
+        DbAction::Prune { before } => {
+            let result = match before {
+                Some(before) => PruneResult { downsampled: 0, pruned: database.prune_price_snapshots(before).await? },
+                None => {
+                    let recorder = SnapshotRecorder::new(database, config.snapshots.clone());
+                    let (downsampled, pruned) = recorder.apply_retention(Utc::now()).await?;
+                    PruneResult { downsampled, pruned }
+                },
+            };
+            emit(json, &result, |result| format!("Downsampled {} and pruned {} price snapshots\n", result.downsampled, result.pruned))
+        },
+        DbAction::Migrate { action: MigrateAction::Apply } => {
+            let applied = database.migrate().await?;
+            emit(json, &applied, |applied| if applied.is_empty() {
+                "Database schema is up to date\n".to_string()
+            } else {
+                format!("Applied migrations: {:?}\n", applied)
+            })
+        },
+        DbAction::Migrate { action: MigrateAction::Status } => {
+            emit(json, &database.migration_status().await?, |migrations: &Vec<MigrationStatus>| {
+                migrations.iter()
+                    .map(|migration| format!("{:>4}  {:<32} {}\n",
+                                             migration.version,
+                                             migration.description,
+                                             migration.applied_at.as_deref().unwrap_or("pending")))
+                    .collect()
+            })
+        },
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExportSummary {
+    pub dataset: ExportDataset,
+    pub format: ExportFormat,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub bytes: usize,
+    pub output: Option<PathBuf>,
+}
+
+/// Writes the export to `output` or stdout. The summary goes to stdout when
+/// writing to a file and to stderr otherwise, so the data can be piped.
+pub async fn export(config: &AppConfig, request: ExportRequest, output: Option<PathBuf>, json: bool) -> Result<()> {
+    let database = db::connect(&config.database.url).await?;
+
+    let mut writer: Box<dyn AsyncWrite + Unpin> = match &output {
+        Some(path) => Box::new(tokio::fs::File::create(path).await
+            .with_context(|| format!("Failed to create {}", path.display()))?),
+        None => Box::new(tokio::io::stdout()),
+    };
+
+    let mut chunks = std::pin::pin!(export::export_stream(database, request.clone()));
+    let mut bytes = 0usize;
+    while let Some(chunk) = chunks.try_next().await? {
+        writer.write_all(&chunk).await?;
+        bytes += chunk.len();
+    }
+    writer.flush().await?;
+
+    let summary = ExportSummary {
+        dataset: request.dataset,
+        format: request.format,
+        from: request.from,
+        to: request.to,
+        bytes,
+        output,
+    };
+    if summary.output.is_some() {
+        return emit(json, &summary, render_export);
+    }
+    if json {
+        eprintln!("{}", serde_json::to_string(&summary)?);
+    } else {
+        eprint!("{}", render_export(&summary));
+    }
+    Ok(())
+}
+
+fn render_export(summary: &ExportSummary) -> String {
+    format!("Exported {} from {} to {} as {} ({} bytes){}\n",
+            summary.dataset.as_str(), summary.from, summary.to, summary.format.extension(), summary.bytes,
+            summary.output.as_ref().map(|path| format!(" to {}", path.display())).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_parses_subcommands() {
+        Cli::command().debug_assert();
+
+        let cli = Cli::try_parse_from(["bot", "run", "--api-only", "--config", "other.toml"]).unwrap();
+        assert_eq!(cli.config, "other.toml");
+        assert!(matches!(cli.command, Some(Command::Run { no_api: false, api_only: true })));
+        assert!(Cli::try_parse_from(["bot", "run", "--no-api", "--api-only"]).is_err());
+
+        let cli = Cli::try_parse_from(["bot", "submit", "0x02f870", "--target-block", "100"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Submit { ref txs, target_block: Some(100) }) if txs.len() == 1));
+        assert!(Cli::try_parse_from(["bot", "submit"]).is_err());
+        assert!(Cli::try_parse_from(["bot", "submit", "not-hex"]).is_err());
+
+        let cli = Cli::try_parse_from(["bot", "--json", "db", "migrate", "status"]).unwrap();
+        assert!(cli.json);
+        assert!(matches!(cli.command, Some(Command::Db { action: DbAction::Migrate { action: MigrateAction::Status } })));
+    }
+
+    #[tokio::test]
+    async fn test_check_reports_every_config_error_as_json() {
+        let error = AppConfig::load("config/missing.toml", None).await.unwrap_err();
+        assert!(check_unloadable(error, false).unwrap_err().to_string().contains("missing.toml"));
+
+        let path = std::env::temp_dir().join(format!("arb-config-{}.toml", uuid::Uuid::new_v4()));
+        let content = std::fs::read_to_string("config/config.toml").unwrap()
+            .replace("chain_id = 137", "chain_id = 0")
+            .replace("min_profit_usdc = \"5 USDC\"", "min_profit_usdc = \"lots\"");
+        std::fs::write(&path, content).unwrap();
+        let error = AppConfig::load(path.to_str().unwrap(), None).await.unwrap_err();
+
+        let errors: Vec<String> = error.downcast_ref::<ValidationErrors>().unwrap().iter().map(|(path, _)| path.clone()).collect();
+        assert_eq!(errors, vec!["trading.min_profit_usdc", "network.chain_id"]);
+        assert!(check_unloadable(error, true).unwrap_err().to_string().contains("2 problems"));
+    }
+}
@@ -0,0 +1,496 @@
+mod layers;
+mod validate;
+
+pub use layers::{profile_path, redact_url};
+pub use validate::{parse_amount, ValidationErrors};
+
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, Context};
+use std::path::Path;
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AppConfig {
+    pub network: NetworkConfig,
+    pub dex: DexConfig,
+    pub tokens: TokenConfig,
+    pub trading: TradingConfig,
+    pub monitoring: MonitoringConfig,
+    pub database: DatabaseConfig,
+    #[serde(default)]
+    pub relay: RelayConfig,
+    #[serde(default)]
+    pub mempool: MempoolConfig,
+    #[serde(default)]
+    pub snapshots: SnapshotConfig,
+    #[serde(default)]
+    pub api: ApiConfig,
+    #[serde(default)]
+    pub logging: LoggingConfig,
+    #[serde(default)]
+    pub alerts: AlertConfig,
+    #[serde(default)]
+    pub telemetry: TelemetryConfig,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct NetworkConfig {
+    pub rpc_url: String,
+    pub chain_id: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct DexConfig {
+    pub quickswap_router: String,
+    pub sushiswap_router: String,
+    /// Defaults to the canonical Polygon deployment, so configs written
+    /// before the factories were needed keep loading
+    #[serde(default = "default_quickswap_factory")]
+    pub quickswap_factory: String,
+    #[serde(default = "default_sushiswap_factory")]
+    pub sushiswap_factory: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TokenConfig {
+    pub weth: String,
+    pub usdc: String,
+}
+
+/// Decimals of the monitored tokens, used to read amounts like "1.5 WETH"
+pub const WETH_DECIMALS: u32 = 18;
+pub const USDC_DECIMALS: u32 = 6;
+
+/// Amounts are either base units of the token in the field name or a decimal
+/// amount with its symbol, e.g. "1.5 WETH"; loading converts them to base
+/// units.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TradingConfig {
+    pub trade_amount_wei: String,
+    pub min_profit_usdc: String,
+    pub gas_estimate_usdc: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct MonitoringConfig {
+    pub check_interval_seconds: u64,
+    /// Health checks fail once no check has run for this long; defaults to
+    /// three check intervals
+    #[serde(default)]
+    pub stall_after_seconds: Option<u64>,
+}
+
+impl MonitoringConfig {
+    pub fn stall_after_seconds(&self) -> u64 {
+        self.stall_after_seconds.unwrap_or(self.check_interval_seconds * 3)
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct DatabaseConfig {
+    pub url: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct RelayConfig {
+    #[serde(default)]
+    pub endpoints: Vec<RelayEndpointConfig>,
+    #[serde(default = "default_public_fallback")]
+    pub public_fallback: bool,
+    #[serde(default = "default_max_block_offset")]
+    pub max_block_offset: u64,
+    /// How long each relay gets to accept a submission
+    #[serde(default = "default_relay_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+impl Default for RelayConfig {
+    fn default() -> Self {
+        Self {
+            endpoints: Vec::new(),
+            public_fallback: default_public_fallback(),
+            max_block_offset: default_max_block_offset(),
+            timeout_ms: default_relay_timeout_ms(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct RelayEndpointConfig {
+    pub name: String,
+    pub url: String,
+    #[serde(default)]
+    pub method: RelayMethod,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RelayMethod {
+    /// `eth_sendPrivateTransaction` for a single signed transaction; several
+    /// are sent as one `eth_sendBundle` so they land together or not at all
+    #[default]
+    PrivateTransaction,
+    /// `eth_sendBundle`, all signed transactions in a single bundle
+    Bundle,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct MempoolConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub ws_url: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct SnapshotConfig {
+    pub enabled: bool,
+    /// Snapshots buffered before a batched insert
+    pub batch_size: usize,
+    /// Upper bound on how long buffered snapshots wait before being written
+    pub flush_interval_seconds: u64,
+    /// Snapshots older than this are deleted; 0 keeps them forever
+    pub retention_days: u64,
+    /// Snapshots older than this are thinned to one per bucket; 0 disables
+    pub downsample_after_hours: u64,
+    pub downsample_bucket_seconds: u64,
+}
+
+impl Default for SnapshotConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            batch_size: 50,
+            flush_interval_seconds: 30,
+            retention_days: 30,
+            downsample_after_hours: 24,
+            downsample_bucket_seconds: 300,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct ApiConfig {
+    pub bind: String,
+    pub port: u16,
+    /// Origins allowed to call the API from a browser; "*" allows any
+    pub allowed_origins: Vec<String>,
+    /// PEM certificate chain and private key; both enable HTTPS
+    pub tls_cert: Option<String>,
+    pub tls_key: Option<String>,
+    /// With no keys, read endpoints are open and control endpoints disabled
+    pub keys: Vec<ApiKeyConfig>,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            bind: "127.0.0.1".to_string(),
+            port: 8081,
+            allowed_origins: vec![
+                "http://localhost:3000".to_string(),
+                "http://127.0.0.1:3000".to_string(),
+            ],
+            tls_cert: None,
+            tls_key: None,
+            keys: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ApiKeyConfig {
+    /// Recorded as the actor in the control audit trail
+    pub name: String,
+    pub token: String,
+    #[serde(default)]
+    pub scope: ApiScope,
+}
+
+/// What an API key may do; admin implies read.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiScope {
+    #[default]
+    Read,
+    Admin,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct LoggingConfig {
+    /// Opportunities queued for the sinks; further ones are dropped until
+    /// the writer catches up
+    pub buffer_size: usize,
+    /// Upper bound on how long queued opportunities wait before being written
+    pub flush_interval_ms: u64,
+    pub sinks: Vec<SinkConfig>,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            buffer_size: 1024,
+            flush_interval_ms: 1000,
+            sinks: vec![SinkConfig::File(FileSinkConfig::default())],
+        }
+    }
+}
+
+/// Where logged opportunities are written, one JSON object per entry.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SinkConfig {
+    File(FileSinkConfig),
+    Stdout,
+    Syslog(SyslogSinkConfig),
+    Kafka(KafkaSinkConfig),
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct FileSinkConfig {
+    pub path: String,
+    /// Rotate once the file would grow past this size; 0 disables
+    pub max_size_mb: u64,
+    pub rotate: RotationPeriod,
+    /// Gzip rotated files
+    pub compress: bool,
+    /// Rotated files kept next to the active one; 0 keeps them all
+    pub max_files: usize,
+}
+
+impl Default for FileSinkConfig {
+    fn default() -> Self {
+        Self {
+            path: "arbitrage_opportunities.jsonl".to_string(),
+            max_size_mb: 0,
+            rotate: RotationPeriod::Never,
+            compress: true,
+            max_files: 0,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RotationPeriod {
+    #[default]
+    Never,
+    Hourly,
+    Daily,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct SyslogSinkConfig {
+    /// `host:port` of a collector accepting RFC 5424 messages over UDP
+    pub address: String,
+    #[serde(default = "default_syslog_facility")]
+    pub facility: String,
+    #[serde(default = "default_syslog_app_name")]
+    pub app_name: String,
+}
+
+/// Publishes through the REST interface of a Kafka-compatible broker
+/// (Confluent REST Proxy, Redpanda's HTTP proxy).
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct KafkaSinkConfig {
+    pub url: String,
+    pub topic: String,
+    #[serde(default = "default_kafka_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct TelemetryConfig {
+    /// Filter used when `RUST_LOG` is unset, e.g. "info,sqlx=warn"
+    pub level: String,
+    pub format: LogFormat,
+    /// OTLP/HTTP collector, e.g. "http://localhost:4318"; spans are only
+    /// exported when set
+    pub otlp_endpoint: Option<String>,
+    pub service_name: String,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            level: "info".to_string(),
+            format: LogFormat::Text,
+            otlp_endpoint: None,
+            service_name: "polygon-arbitrage-bot".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LogFormat {
+    #[default]
+    Text,
+    /// One JSON object per event, including the enclosing spans
+    Json,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct AlertConfig {
+    /// Consecutive failed checks before an RPC failure alert; 0 disables
+    pub rpc_failure_threshold: u64,
+    /// The same alert is sent to a channel at most once per window
+    pub dedup_window_seconds: u64,
+    /// `{{field}}` placeholders are filled from the opportunity
+    pub opportunity_template: String,
+    pub operational_template: String,
+    pub channels: Vec<AlertChannelConfig>,
+}
+
+impl Default for AlertConfig {
+    fn default() -> Self {
+        Self {
+            rpc_failure_threshold: 3,
+            dedup_window_seconds: 300,
+            opportunity_template: "{{pair}}: buy on {{buy_exchange}} at {{buy_price}}, sell on {{sell_exchange}} \
+                at {{sell_price}} for an estimated {{profit}} USDC ({{profit_percentage}}%) at block {{block_number}}"
+                .to_string(),
+            operational_template: "{{event}}: {{message}}".to_string(),
+            channels: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AlertChannelConfig {
+    #[serde(flatten)]
+    pub kind: AlertChannelKind,
+    /// Opportunities estimated below this many USDC are not sent here
+    #[serde(default)]
+    pub min_profit: f64,
+    /// 0 removes the limit
+    #[serde(default = "default_alerts_per_minute")]
+    pub max_per_minute: usize,
+    /// Replaces `opportunity_template` for this channel
+    pub template: Option<String>,
+    /// Whether RPC failure and recovery alerts are sent here
+    #[serde(default = "default_operational_alerts")]
+    pub operational: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AlertChannelKind {
+    /// JSON POST with the rendered text and the alert fields
+    Webhook { url: String },
+    Telegram {
+        bot_token: String,
+        chat_id: String,
+        #[serde(default = "default_telegram_api_url")]
+        api_url: String,
+    },
+    Discord { webhook_url: String },
+    Email(EmailChannelConfig),
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct EmailChannelConfig {
+    pub smtp_host: String,
+    #[serde(default = "default_smtp_port")]
+    pub smtp_port: u16,
+    #[serde(default)]
+    pub tls: SmtpTls,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub from: String,
+    pub to: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpTls {
+    #[default]
+    Starttls,
+    /// TLS from the first byte, usually on port 465
+    Tls,
+    /// Plain text, only for local relays
+    None,
+}
+
+fn default_alerts_per_minute() -> usize {
+    10
+}
+
+fn default_operational_alerts() -> bool {
+    true
+}
+
+fn default_telegram_api_url() -> String {
+    "https://api.telegram.org".to_string()
+}
+
+fn default_smtp_port() -> u16 {
+    587
+}
+
+fn default_syslog_facility() -> String {
+    "local0".to_string()
+}
+
+fn default_syslog_app_name() -> String {
+    "arbitrage-bot".to_string()
+}
+
+fn default_kafka_timeout_ms() -> u64 {
+    5000
+}
+
+fn default_quickswap_factory() -> String {
+    "0x5757371414417b8C6CAad45bAeF941aBc7d3Ab32".to_string()
+}
+
+fn default_sushiswap_factory() -> String {
+    "0xc35DADB65012eC5796536bD9864eD8773aBc74C4".to_string()
+}
+
+fn default_public_fallback() -> bool {
+    true
+}
+
+fn default_max_block_offset() -> u64 {
+    3
+}
+
+fn default_relay_timeout_ms() -> u64 {
+    2000
+}
+
+impl AppConfig {
+    /// Loads `config_path`, then `profile`'s file next to it, then `ARB_`
+    /// environment overrides, each taking precedence over the one before.
+    pub async fn load(config_path: &str, profile: Option<&str>) -> Result<Self> {
+        let config_path = Path::new(config_path);
+        let mut layered = layers::read_toml(config_path)?;
+        if let Some(profile) = profile {
+            let profile_path = layers::profile_path(config_path, profile);
+            let overlay = layers::read_toml(&profile_path)
+                .with_context(|| format!("Failed to load config profile {}", profile))?;
+            layers::merge(&mut layered, overlay);
+        }
+        layers::apply_env(&mut layered, std::env::vars())?;
+        
+        let mut config: AppConfig = layered.try_into()
+            .with_context(|| "Failed to parse config file")?;
+        validate::check(&mut config)?;
+        
+        Ok(config)
+    }
+    
+    /// The effective config as TOML with tokens, passwords and URL
+    /// credentials hidden.
+    pub fn to_redacted_toml(&self) -> Result<String> {
+        let mut value = toml::Value::try_from(self)?;
+        layers::redact(&mut value);
+        Ok(toml::to_string_pretty(&value)?)
+    }
+}
@@ -0,0 +1,333 @@
+//! Checks a parsed config and converts amounts to base units, collecting
+//! every problem so they can all be fixed in one edit.
+
+use ethers::types::Address;
+use ethers::utils::to_checksum;
+use std::fmt;
+
+use super::{AlertChannelKind, AppConfig, SinkConfig, USDC_DECIMALS, WETH_DECIMALS};
+
+/// Every problem found in a config, each with the path of the field it
+/// concerns.
+#[derive(Debug)]
+pub struct ValidationErrors(Vec<(String, String)>);
+
+impl ValidationErrors {
+    /// (field path, problem) pairs
+    pub fn iter(&self) -> impl Iterator<Item = &(String, String)> {
+        self.0.iter()
+    }
+}
+
+impl fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid configuration ({} error{}):", self.0.len(), if self.0.len() == 1 { "" } else { "s" })?;
+        for (path, message) in &self.0 {
+            write!(f, "\n  {}: {}", path, message)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationErrors {}
+
+#[derive(Default)]
+struct Validator {
+    errors: Vec<(String, String)>,
+}
+
+impl Validator {
+    fn error(&mut self, path: impl Into<String>, message: impl fmt::Display) {
+        self.errors.push((path.into(), message.to_string()));
+    }
+
+    fn check(&mut self, ok: bool, path: impl Into<String>, message: impl fmt::Display) {
+        if !ok {
+            self.error(path, message);
+        }
+    }
+
+    fn address(&mut self, path: &str, value: &str) -> Option<Address> {
+        match parse_address(value) {
+            Ok(address) => Some(address),
+            Err(message) => {
+                self.error(path, message);
+                None
+            },
+        }
+    }
+
+    fn http_url(&mut self, path: impl Into<String>, url: &str) {
+        self.check(url.starts_with("http://") || url.starts_with("https://"), path, format!("{:?} is not an http(s) URL", url));
+    }
+
+    fn finish(self) -> anyhow::Result<()> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationErrors(self.errors).into())
+        }
+    }
+}
+
+/// A 20-byte hex address whose letter case matches its EIP-55 checksum.
+pub fn parse_address(value: &str) -> Result<Address, String> {
+    let hex = value.strip_prefix("0x").ok_or_else(|| format!("{:?} does not start with 0x", value))?;
+    if hex.len() != 40 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("{:?} is not 20 bytes of hex", value));
+    }
+
+    let address: Address = value.parse().map_err(|e| format!("{:?} is not an address: {}", value, e))?;
+    let checksummed = to_checksum(&address, None);
+    if checksummed != value {
+        return Err(format!("{} fails its EIP-55 checksum, expected {}", value, checksummed));
+    }
+    Ok(address)
+}
+
+/// Parses either raw base units (`"5000000"`) or a decimal amount of
+/// `symbol` (`"5 USDC"`, `"1.5 WETH"`), returning base units.
+pub fn parse_amount(value: &str, symbol: &str, decimals: u32) -> Result<u128, String> {
+    let value = value.trim();
+    let number_end = value.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(value.len());
+    let (number, unit) = value.split_at(number_end);
+    let unit = unit.trim();
+
+    if unit.is_empty() {
+        return number.parse().map_err(|_| format!("{:?} is neither base units nor an amount like \"1.5 {}\"", value, symbol));
+    }
+    if !unit.eq_ignore_ascii_case(symbol) {
+        return Err(format!("{:?} must be an amount of {}", value, symbol));
+    }
+
+    let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+    if whole.is_empty() && fraction.is_empty() || fraction.contains('.') {
+        return Err(format!("{:?} is not a decimal number", number));
+    }
+    if fraction.len() > decimals as usize {
+        return Err(format!("{} has at most {} decimal places", symbol, decimals));
+    }
+
+    let scale = 10u128.pow(decimals);
+    let whole: u128 = if whole.is_empty() { 0 } else { whole.parse().map_err(|_| format!("{:?} is too large", value))? };
+    let fraction: u128 = format!("{:0<width$}", fraction, width = decimals as usize).parse().unwrap_or(0);
+    whole.checked_mul(scale)
+        .and_then(|base| base.checked_add(fraction))
+        .ok_or_else(|| format!("{:?} is too large", value))
+}
+
+/// Rewrites the `[trading]` amounts in base units, so the rest of the bot
+/// only ever sees those, then checks every field.
+pub(super) fn check(config: &mut AppConfig) -> anyhow::Result<()> {
+    let mut v = Validator::default();
+
+    // Runtime settings hold WETH amounts as u128 and USDC amounts as u64
+    let amounts = [
+        ("trading.trade_amount_wei", &mut config.trading.trade_amount_wei, "WETH", WETH_DECIMALS, u128::MAX),
+        ("trading.min_profit_usdc", &mut config.trading.min_profit_usdc, "USDC", USDC_DECIMALS, u64::MAX.into()),
+        ("trading.gas_estimate_usdc", &mut config.trading.gas_estimate_usdc, "USDC", USDC_DECIMALS, u64::MAX.into()),
+    ];
+    let mut base_units = Vec::with_capacity(amounts.len());
+    for (path, value, symbol, decimals, max) in amounts {
+        match parse_amount(value, symbol, decimals).and_then(|amount| {
+            if amount <= max { Ok(amount) } else { Err(format!("{:?} is too large", value)) }
+        }) {
+            Ok(amount) => {
+                *value = amount.to_string();
+                base_units.push(Some(amount));
+            },
+            Err(message) => {
+                v.error(path, message);
+                base_units.push(None);
+            },
+        }
+    }
+    if let [Some(trade_amount), _, _] = base_units[..] {
+        v.check(trade_amount > 0, "trading.trade_amount_wei", "must be positive");
+    }
+
+    let config = &*config;
+    let quickswap_router = v.address("dex.quickswap_router", &config.dex.quickswap_router);
+    let sushiswap_router = v.address("dex.sushiswap_router", &config.dex.sushiswap_router);
+    let quickswap_factory = v.address("dex.quickswap_factory", &config.dex.quickswap_factory);
+    let sushiswap_factory = v.address("dex.sushiswap_factory", &config.dex.sushiswap_factory);
+    let weth = v.address("tokens.weth", &config.tokens.weth);
+    let usdc = v.address("tokens.usdc", &config.tokens.usdc);
+
+    if quickswap_router.is_some() && quickswap_router == sushiswap_router {
+        v.error("dex.sushiswap_router", "is the same as dex.quickswap_router");
+    }
+    if quickswap_factory.is_some() && quickswap_factory == sushiswap_factory {
+        v.error("dex.sushiswap_factory", "is the same as dex.quickswap_factory");
+    }
+    if weth.is_some() && weth == usdc {
+        v.error("tokens.usdc", "is the same as tokens.weth");
+    }
+
+    v.check(config.network.chain_id > 0, "network.chain_id", "must be positive");
+    v.check(!config.network.rpc_url.is_empty(), "network.rpc_url", "is required");
+
+    v.check(config.monitoring.check_interval_seconds > 0, "monitoring.check_interval_seconds", "must be at least 1");
+    if let Some(stall_after) = config.monitoring.stall_after_seconds {
+        v.check(stall_after > config.monitoring.check_interval_seconds, "monitoring.stall_after_seconds",
+                "must be longer than monitoring.check_interval_seconds");
+    }
+
+    v.check(!config.mempool.enabled || config.mempool.ws_url.starts_with("ws"), "mempool.ws_url",
+            "must be a ws:// or wss:// URL when the mempool watcher is enabled");
+
+    v.check(config.snapshots.batch_size > 0, "snapshots.batch_size", "must be at least 1");
+    v.check(config.snapshots.downsample_after_hours == 0 || config.snapshots.downsample_bucket_seconds > 0,
+            "snapshots.downsample_bucket_seconds", "must be positive when downsampling is enabled");
+    v.check(config.snapshots.retention_days == 0 || config.snapshots.downsample_after_hours == 0
+            || config.snapshots.downsample_after_hours < config.snapshots.retention_days * 24,
+            "snapshots.downsample_after_hours", "must be shorter than snapshots.retention_days");
+
+    v.check(config.api.tls_cert.is_some() == config.api.tls_key.is_some(), "api.tls_key",
+            "api.tls_cert and api.tls_key must be set together");
+    for (index, key) in config.api.keys.iter().enumerate() {
+        let path = format!("api.keys[{}]", index);
+        v.check(!key.name.is_empty(), format!("{}.name", path), "is required");
+        v.check(!key.token.is_empty(), format!("{}.token", path), "is required");
+        if config.api.keys[..index].iter().any(|other| other.name == key.name) {
+            v.error(format!("{}.name", path), format!("{:?} is used by another key", key.name));
+        }
+        if !key.token.is_empty() && config.api.keys[..index].iter().any(|other| other.token == key.token) {
+            v.error(format!("{}.token", path), "is used by another key");
+        }
+    }
+
+    v.check(config.logging.buffer_size > 0, "logging.buffer_size", "must be at least 1");
+    for (index, sink) in config.logging.sinks.iter().enumerate() {
+        let path = format!("logging.sinks[{}]", index);
+        match sink {
+            SinkConfig::File(file) => v.check(!file.path.is_empty(), format!("{}.path", path), "is required"),
+            SinkConfig::Syslog(syslog) => v.check(crate::logger::syslog_facility(&syslog.facility).is_some(),
+                                                  format!("{}.facility", path), format!("unknown syslog facility {:?}", syslog.facility)),
+            SinkConfig::Kafka(kafka) => {
+                v.http_url(format!("{}.url", path), &kafka.url);
+                v.check(!kafka.topic.is_empty(), format!("{}.topic", path), "is required");
+            },
+            SinkConfig::Stdout => {},
+        }
+    }
+
+    let templates = [
+        ("alerts.opportunity_template".to_string(), &config.alerts.opportunity_template, crate::notifier::OPPORTUNITY_FIELDS),
+        ("alerts.operational_template".to_string(), &config.alerts.operational_template, crate::notifier::OPERATIONAL_FIELDS),
+    ];
+    let channel_templates = config.alerts.channels.iter().enumerate().filter_map(|(index, channel)| {
+        channel.template.as_ref().map(|template| (format!("alerts.channels[{}].template", index), template, crate::notifier::OPPORTUNITY_FIELDS))
+    });
+    for (path, template, fields) in templates.into_iter().chain(channel_templates) {
+        if let Err(e) = crate::notifier::check_template(template, fields) {
+            v.error(path, format!("{:#}", e));
+        }
+    }
+    for (index, channel) in config.alerts.channels.iter().enumerate() {
+        let path = format!("alerts.channels[{}]", index);
+        v.check(channel.min_profit >= 0.0, format!("{}.min_profit", path), "must not be negative");
+        match &channel.kind {
+            AlertChannelKind::Webhook { url } => v.http_url(format!("{}.url", path), url),
+            AlertChannelKind::Discord { webhook_url } => v.http_url(format!("{}.webhook_url", path), webhook_url),
+            AlertChannelKind::Telegram { bot_token, chat_id, api_url } => {
+                v.check(!bot_token.is_empty(), format!("{}.bot_token", path), "is required");
+                v.check(!chat_id.is_empty(), format!("{}.chat_id", path), "is required");
+                v.http_url(format!("{}.api_url", path), api_url);
+            },
+            AlertChannelKind::Email(email) => {
+                v.check(!email.smtp_host.is_empty(), format!("{}.smtp_host", path), "is required");
+                v.check(!email.to.is_empty(), format!("{}.to", path), "needs at least one recipient");
+                v.check(email.username.is_some() == email.password.is_some(), format!("{}.password", path),
+                        "username and password must be set together");
+            },
+        }
+    }
+
+    if let Some(endpoint) = &config.telemetry.otlp_endpoint {
+        v.http_url("telemetry.otlp_endpoint", endpoint);
+    }
+
+    for (index, relay) in config.relay.endpoints.iter().enumerate() {
+        let path = format!("relay.endpoints[{}]", index);
+        v.http_url(format!("{}.url", path), &relay.url);
+        if config.relay.endpoints[..index].iter().any(|other| other.name == relay.name) {
+            v.error(format!("{}.name", path), format!("{:?} is used by another relay", relay.name));
+        }
+    }
+
+    v.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_amount() {
+        assert_eq!(parse_amount("1.5 WETH", "WETH", 18), Ok(1_500_000_000_000_000_000));
+        assert_eq!(parse_amount("5 usdc", "USDC", 6), Ok(5_000_000));
+        assert_eq!(parse_amount("0.000001USDC", "USDC", 6), Ok(1));
+        assert_eq!(parse_amount("5000000", "USDC", 6), Ok(5_000_000));
+
+        assert!(parse_amount("5 WETH", "USDC", 6).is_err());
+        assert!(parse_amount("0.0000001 USDC", "USDC", 6).is_err());
+        assert!(parse_amount("1.2.3 USDC", "USDC", 6).is_err());
+        assert!(parse_amount("-1 USDC", "USDC", 6).is_err());
+        assert!(parse_amount(". USDC", "USDC", 6).is_err());
+    }
+
+    #[test]
+    fn test_parse_address() {
+        assert!(parse_address("0x7ceB23fD6bC0adD59E62ac25578270cFf1b9f619").is_ok());
+
+        let error = parse_address("0x7ceb23fd6bc0add59e62ac25578270cff1b9f619").unwrap_err();
+        assert!(error.contains("expected 0x7ceB23fD6bC0adD59E62ac25578270cFf1b9f619"));
+        assert!(parse_address("0x7ceB23fD6bC0adD59E62ac25578270cFf1b9f6").is_err());
+        assert!(parse_address("7ceB23fD6bC0adD59E62ac25578270cFf1b9f619").is_err());
+    }
+
+    #[test]
+    fn test_reports_every_error() {
+        let mut config: AppConfig = toml::from_str(&std::fs::read_to_string("config/config.toml").unwrap()).unwrap();
+        config.trading.trade_amount_wei = "1.5 WETH".to_string();
+        config.trading.min_profit_usdc = "5 WETH".to_string();
+        config.dex.sushiswap_router = config.dex.quickswap_router.clone();
+        config.tokens.usdc = config.tokens.usdc.to_lowercase();
+        config.monitoring.stall_after_seconds = Some(config.monitoring.check_interval_seconds);
+
+        let error = check(&mut config).unwrap_err();
+        let errors = error.downcast_ref::<ValidationErrors>().unwrap();
+        assert_eq!(errors.0.iter().map(|(path, _)| path.as_str()).collect::<Vec<_>>(), vec![
+            "trading.min_profit_usdc",
+            "tokens.usdc",
+            "dex.sushiswap_router",
+            "monitoring.stall_after_seconds",
+        ]);
+        assert!(error.to_string().starts_with("Invalid configuration (4 errors):\n  trading.min_profit_usdc: "));
+        assert_eq!(config.trading.trade_amount_wei, "1500000000000000000");
+    }
+
+    #[test]
+    fn test_accepts_trade_amounts_beyond_u64() {
+        let mut config: AppConfig = toml::from_str(&std::fs::read_to_string("config/config.toml").unwrap()).unwrap();
+        config.trading.trade_amount_wei = "250 WETH".to_string();
+
+        check(&mut config).unwrap();
+        assert_eq!(config.trading.trade_amount_wei, "250000000000000000000");
+    }
+
+    #[test]
+    fn test_factories_default_to_polygon() {
+        let content = std::fs::read_to_string("config/config.toml").unwrap();
+        let content: String = content.lines()
+            .filter(|line| !line.contains("_factory ="))
+            .map(|line| format!("{}\n", line))
+            .collect();
+        let mut config: AppConfig = toml::from_str(&content).unwrap();
+
+        check(&mut config).unwrap();
+        assert_eq!(config.dex.quickswap_factory, "0x5757371414417b8C6CAad45bAeF941aBc7d3Ab32");
+        assert_eq!(config.dex.sushiswap_factory, "0xc35DADB65012eC5796536bD9864eD8773aBc74C4");
+    }
+}
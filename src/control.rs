@@ -0,0 +1,254 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::{watch, Notify};
+use utoipa::ToSchema;
+use tracing::info;
+
+use crate::config::{self, AppConfig, USDC_DECIMALS, WETH_DECIMALS};
+use crate::db::Store;
+
+/// One audited change made through the control API.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct ControlAction {
+    pub timestamp: DateTime<Utc>,
+    pub actor: String,
+    pub action: String,
+    pub previous_value: Option<String>,
+    pub new_value: Option<String>,
+}
+
+/// Trading and monitoring parameters that can change without a restart,
+/// in the same base units as the `[trading]` config section.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct RuntimeSettings {
+    pub min_profit_usdc: u64,
+    pub gas_estimate_usdc: u64,
+    pub trade_amount_wei: u128,
+    pub check_interval_seconds: u64,
+}
+
+impl RuntimeSettings {
+    pub fn from_config(config: &AppConfig) -> Result<Self> {
+        Ok(Self {
+            min_profit_usdc: config.trading.min_profit_usdc.parse()
+                .with_context(|| "Invalid min_profit_usdc format")?,
+            gas_estimate_usdc: config.trading.gas_estimate_usdc.parse()
+                .with_context(|| "Invalid gas_estimate_usdc format")?,
+            trade_amount_wei: config.trading.trade_amount_wei.parse()
+                .with_context(|| "Invalid trade_amount_wei format")?,
+            check_interval_seconds: config.monitoring.check_interval_seconds,
+        })
+    }
+}
+
+/// Partial update accepted by `POST /api/control/settings`. Amounts are
+/// strings like in the config file.
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SettingsUpdate {
+    pub min_profit_usdc: Option<String>,
+    pub gas_estimate_usdc: Option<String>,
+    pub trade_amount_wei: Option<String>,
+    pub check_interval_seconds: Option<u64>,
+}
+
+/// Runtime control shared between the API and the monitoring loop. Every
+/// change is written to the audit table before it takes effect.
+pub struct BotControl {
+    store: Arc<dyn Store>,
+    paused: AtomicBool,
+    check_requested: Notify,
+    settings: watch::Sender<RuntimeSettings>,
+}
+
+impl BotControl {
+    pub fn new(store: Arc<dyn Store>, settings: RuntimeSettings) -> Self {
+        Self {
+            store,
+            paused: AtomicBool::new(false),
+            check_requested: Notify::new(),
+            settings: watch::Sender::new(settings),
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    /// Pauses scheduled checks, returning false if already paused.
+    pub async fn pause(&self, actor: &str) -> Result<bool> {
+        self.set_paused(actor, true).await
+    }
+
+    /// Resumes scheduled checks, returning false if not paused.
+    pub async fn resume(&self, actor: &str) -> Result<bool> {
+        self.set_paused(actor, false).await
+    }
+
+    async fn set_paused(&self, actor: &str, paused: bool) -> Result<bool> {
+        if self.is_paused() == paused {
+            return Ok(false);
+        }
+
+        self.audit(actor, if paused { "pause" } else { "resume" }, None, None).await?;
+        self.paused.store(paused, Ordering::SeqCst);
+        info!("Monitoring {} by {}", if paused { "paused" } else { "resumed" }, actor);
+
+        Ok(true)
+    }
+
+    /// Asks the monitoring loop to run a check now, even while paused.
+    pub async fn request_check(&self, actor: &str) -> Result<()> {
+        self.audit(actor, "trigger_check", None, None).await?;
+        self.check_requested.notify_one();
+        Ok(())
+    }
+
+    pub async fn check_requested(&self) {
+        self.check_requested.notified().await
+    }
+
+    pub fn settings(&self) -> RuntimeSettings {
+        self.settings.borrow().clone()
+    }
+
+    pub fn subscribe_settings(&self) -> watch::Receiver<RuntimeSettings> {
+        self.settings.subscribe()
+    }
+
+    /// Validates every field of the update before applying any of them.
+    pub async fn update_settings(&self, actor: &str, update: SettingsUpdate) -> Result<RuntimeSettings> {
+        let current = self.settings();
+        let mut next = current.clone();
+
+        if let Some(value) = &update.min_profit_usdc {
+            next.min_profit_usdc = parse_amount(value, "USDC", USDC_DECIMALS)
+                .with_context(|| "Invalid min_profit_usdc")?;
+        }
+        if let Some(value) = &update.gas_estimate_usdc {
+            next.gas_estimate_usdc = parse_amount(value, "USDC", USDC_DECIMALS)
+                .with_context(|| "Invalid gas_estimate_usdc")?;
+        }
+        if let Some(value) = &update.trade_amount_wei {
+            next.trade_amount_wei = parse_amount(value, "WETH", WETH_DECIMALS)
+                .with_context(|| "Invalid trade_amount_wei")?;
+            if next.trade_amount_wei == 0 {
+                anyhow::bail!("trade_amount_wei must be positive");
+            }
+        }
+        if let Some(value) = update.check_interval_seconds {
+            if value == 0 {
+                anyhow::bail!("check_interval_seconds must be at least 1");
+            }
+            next.check_interval_seconds = value;
+        }
+
+        let changes: [(&str, u128, u128); 4] = [
+            ("set_min_profit_usdc", current.min_profit_usdc.into(), next.min_profit_usdc.into()),
+            ("set_gas_estimate_usdc", current.gas_estimate_usdc.into(), next.gas_estimate_usdc.into()),
+            ("set_trade_amount_wei", current.trade_amount_wei, next.trade_amount_wei),
+            ("set_check_interval_seconds", current.check_interval_seconds.into(), next.check_interval_seconds.into()),
+        ];
+        for (action, previous, new) in changes {
+            if previous != new {
+                self.audit(actor, action, Some(previous.to_string()), Some(new.to_string())).await?;
+                info!("{} {} -> {} by {}", action, previous, new, actor);
+            }
+        }
+
+        self.settings.send_if_modified(|settings| {
+            let modified = *settings != next;
+            *settings = next.clone();
+            modified
+        });
+
+        Ok(next)
+    }
+
+    async fn audit(&self, actor: &str, action: &str, previous: Option<String>, new: Option<String>) -> Result<()> {
+        self.store.record_control_action(&ControlAction {
+            timestamp: Utc::now(),
+            actor: actor.to_string(),
+            action: action.to_string(),
+            previous_value: previous,
+            new_value: new,
+        }).await
+    }
+}
+
+fn parse_amount<T: TryFrom<u128>>(value: &str, symbol: &str, decimals: u32) -> Result<T> {
+    let amount = config::parse_amount(value, symbol, decimals).map_err(anyhow::Error::msg)?;
+    T::try_from(amount).map_err(|_| anyhow::anyhow!("{:?} is too large", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+
+    fn settings() -> RuntimeSettings {
+        RuntimeSettings {
+            min_profit_usdc: 1_000_000,
+            gas_estimate_usdc: 500_000,
+            trade_amount_wei: 1_000_000_000_000_000_000,
+            check_interval_seconds: 10,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_changes_are_audited() {
+        let store = db::open(&db::tests::temp_sqlite_url()).await.unwrap();
+        let control = BotControl::new(store.clone(), settings());
+        let mut receiver = control.subscribe_settings();
+
+        assert!(control.pause("admin").await.unwrap());
+        assert!(!control.pause("admin").await.unwrap());
+        assert!(control.is_paused());
+
+        let updated = control.update_settings("admin", SettingsUpdate {
+            min_profit_usdc: Some("2.5 USDC".to_string()),
+            check_interval_seconds: Some(5),
+            ..Default::default()
+        }).await.unwrap();
+        assert_eq!(updated.min_profit_usdc, 2_500_000);
+        assert!(receiver.has_changed().unwrap());
+        assert_eq!(receiver.borrow_and_update().check_interval_seconds, 5);
+
+        let actions: Vec<String> = store.get_recent_control_actions(10).await.unwrap()
+            .into_iter().map(|a| a.action).collect();
+        assert_eq!(actions.len(), 3);
+        assert!(actions.contains(&"pause".to_string()));
+        assert!(actions.contains(&"set_check_interval_seconds".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_trade_amount_beyond_u64() {
+        let store = db::open(&db::tests::temp_sqlite_url()).await.unwrap();
+        let control = BotControl::new(store, settings());
+
+        let updated = control.update_settings("admin", SettingsUpdate {
+            trade_amount_wei: Some("25 WETH".to_string()),
+            ..Default::default()
+        }).await.unwrap();
+        assert_eq!(updated.trade_amount_wei, 25_000_000_000_000_000_000);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_update_changes_nothing() {
+        let store = db::open(&db::tests::temp_sqlite_url()).await.unwrap();
+        let control = BotControl::new(store.clone(), settings());
+
+        let result = control.update_settings("admin", SettingsUpdate {
+            min_profit_usdc: Some("3000000".to_string()),
+            check_interval_seconds: Some(0),
+            ..Default::default()
+        }).await;
+
+        assert!(result.is_err());
+        assert_eq!(control.settings(), settings());
+        assert!(store.get_recent_control_actions(10).await.unwrap().is_empty());
+    }
+}
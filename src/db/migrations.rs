@@ -0,0 +1,108 @@
+use serde::Serialize;
+
+pub struct Migration {
+    pub version: i64,
+    pub description: &'static str,
+    pub sql: &'static str,
+}
+
+/// Every SQLite schema change, in order. Applied migrations must never be
+/// edited; add a new one instead.
+pub const SQLITE_MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "initial",
+        sql: include_str!("../../migrations/sqlite/0001_initial.sql"),
+    },
+    Migration {
+        version: 2,
+        description: "predicted opportunities",
+        sql: include_str!("../../migrations/sqlite/0002_predicted_opportunities.sql"),
+    },
+    Migration {
+        version: 3,
+        description: "opportunity lifecycles",
+        sql: include_str!("../../migrations/sqlite/0003_opportunity_lifecycles.sql"),
+    },
+    Migration {
+        version: 4,
+        description: "opportunity pair and block",
+        sql: include_str!("../../migrations/sqlite/0004_opportunity_pair_and_block.sql"),
+    },
+    Migration {
+        version: 5,
+        description: "price snapshots",
+        sql: include_str!("../../migrations/sqlite/0005_price_snapshots.sql"),
+    },
+    Migration {
+        version: 6,
+        description: "opportunity query indexes",
+        sql: include_str!("../../migrations/sqlite/0006_opportunity_query_indexes.sql"),
+    },
+    Migration {
+        version: 7,
+        description: "control audit",
+        sql: include_str!("../../migrations/sqlite/0007_control_audit.sql"),
+    },
+];
+
+/// PostgreSQL counterparts of `SQLITE_MIGRATIONS`, kept at the same versions.
+pub const POSTGRES_MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "initial",
+        sql: include_str!("../../migrations/postgres/0001_initial.sql"),
+    },
+    Migration {
+        version: 2,
+        description: "predicted opportunities",
+        sql: include_str!("../../migrations/postgres/0002_predicted_opportunities.sql"),
+    },
+    Migration {
+        version: 3,
+        description: "opportunity lifecycles",
+        sql: include_str!("../../migrations/postgres/0003_opportunity_lifecycles.sql"),
+    },
+    Migration {
+        version: 4,
+        description: "opportunity pair and block",
+        sql: include_str!("../../migrations/postgres/0004_opportunity_pair_and_block.sql"),
+    },
+    Migration {
+        version: 5,
+        description: "price snapshots",
+        sql: include_str!("../../migrations/postgres/0005_price_snapshots.sql"),
+    },
+    Migration {
+        version: 6,
+        description: "opportunity query indexes",
+        sql: include_str!("../../migrations/postgres/0006_opportunity_query_indexes.sql"),
+    },
+    Migration {
+        version: 7,
+        description: "control audit",
+        sql: include_str!("../../migrations/postgres/0007_control_audit.sql"),
+    },
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    pub applied_at: Option<String>,
+}
+
+/// Lists every known migration alongside the time it was applied, if it was.
+pub fn status(migrations: &[Migration], applied: &[(i64, String)]) -> Vec<MigrationStatus> {
+    migrations.iter().map(|migration| MigrationStatus {
+        version: migration.version,
+        description: migration.description.to_string(),
+        applied_at: applied.iter()
+            .find(|(version, _)| *version == migration.version)
+            .map(|(_, applied_at)| applied_at.clone()),
+    }).collect()
+}
+
+pub fn latest_version(migrations: &[Migration]) -> i64 {
+    migrations.last().map(|m| m.version).unwrap_or(0)
+}
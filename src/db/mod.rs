@@ -0,0 +1,630 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use ethers::utils::hex;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::ToSchema;
+use tracing::info;
+
+use crate::arbitrage::{ArbitrageOpportunity, PredictedOpportunity};
+use crate::control::ControlAction;
+use crate::lifecycle::OpportunityLifecycle;
+use crate::snapshots::PriceSnapshot;
+
+pub mod migrations;
+mod postgres;
+mod sqlite;
+
+pub use migrations::MigrationStatus;
+pub use postgres::PostgresStore;
+pub use sqlite::SqliteStore;
+
+/// Persistence for everything the bot records. SQLite is the default; a
+/// `postgres://` URL selects PostgreSQL (optionally with TimescaleDB).
+#[async_trait]
+pub trait Store: Send + Sync {
+    /// Applies pending schema migrations, returning the versions applied.
+    async fn migrate(&self) -> Result<Vec<i64>>;
+    async fn migration_status(&self) -> Result<Vec<MigrationStatus>>;
+    async fn schema_version(&self) -> Result<i64>;
+
+    async fn store_opportunity(&self, opportunity: &ArbitrageOpportunity) -> Result<()>;
+    /// Returns one page of opportunities matching the query, along with the
+    /// total number of matches and a cursor for the next page.
+    async fn query_opportunities(&self, query: &OpportunityQuery) -> Result<OpportunityPage>;
+    /// Returns up to `query.limit` opportunities after the query's cursor
+    /// without counting matches, for walking large ranges.
+    async fn scan_opportunities(&self, query: &OpportunityQuery) -> Result<Vec<ArbitrageOpportunity>>;
+    /// Returns (total, average profit, best profit).
+    async fn get_stats(&self) -> Result<(i64, f64, f64)>;
+
+    async fn store_prediction(&self, prediction: &PredictedOpportunity) -> Result<()>;
+    /// Records whether a prediction held, or with `None` that it expired
+    /// without being scored.
+    async fn resolve_prediction(&self, id: &str, confirmed: Option<bool>) -> Result<()>;
+    async fn get_recent_predictions(&self, limit: i64) -> Result<Vec<PredictedOpportunity>>;
+    /// Returns (total, confirmed, refuted, expired) prediction counts;
+    /// unresolved predictions count towards the total only.
+    async fn get_prediction_stats(&self) -> Result<(i64, i64, i64, i64)>;
+
+    async fn upsert_lifecycle(&self, lifecycle: &OpportunityLifecycle) -> Result<()>;
+    async fn get_open_lifecycles(&self) -> Result<Vec<OpportunityLifecycle>>;
+    async fn get_recent_lifecycles(&self, limit: i64) -> Result<Vec<OpportunityLifecycle>>;
+    /// Returns (total, open, average duration in seconds, average observations).
+    async fn get_lifecycle_stats(&self) -> Result<(i64, i64, f64, f64)>;
+
+    /// Aggregates opportunities between `from` and `to` into per-direction
+    /// buckets of the given width, oldest first.
+    async fn get_profit_buckets(&self, interval: BucketInterval, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<ProfitBucket>>;
+    /// Ranks trade directions by how many opportunities they produced
+    /// between `from` and `to`.
+    async fn get_venue_leaderboard(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<VenueStanding>>;
+
+    async fn record_control_action(&self, action: &ControlAction) -> Result<()>;
+    async fn get_recent_control_actions(&self, limit: i64) -> Result<Vec<ControlAction>>;
+
+    /// Inserts a batch of snapshots in a single transaction.
+    async fn store_price_snapshots(&self, snapshots: &[PriceSnapshot]) -> Result<()>;
+    async fn get_recent_price_snapshots(&self, limit: i64) -> Result<Vec<PriceSnapshot>>;
+    /// Returns up to `limit` snapshots taken between `from` and `to` whose
+    /// row id is above `after_id`, in id order and paired with their ids.
+    async fn scan_price_snapshots(&self, from: DateTime<Utc>, to: DateTime<Utc>, after_id: i64, limit: i64) -> Result<Vec<(i64, PriceSnapshot)>>;
+    /// Deletes snapshots older than `before`, returning how many were removed.
+    async fn prune_price_snapshots(&self, before: DateTime<Utc>) -> Result<u64>;
+    /// Keeps only the earliest snapshot per pair, venue and `bucket_seconds`
+    /// bucket among those older than `before`, returning how many were removed.
+    async fn downsample_price_snapshots(&self, before: DateTime<Utc>, bucket_seconds: i64) -> Result<u64>;
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OpportunitySort {
+    #[default]
+    Timestamp,
+    Profit,
+}
+
+impl OpportunitySort {
+    fn as_str(&self) -> &'static str {
+        match self {
+            OpportunitySort::Timestamp => "timestamp",
+            OpportunitySort::Profit => "profit",
+        }
+    }
+
+    pub(crate) fn column(&self) -> &'static str {
+        match self {
+            OpportunitySort::Timestamp => "timestamp",
+            OpportunitySort::Profit => "estimated_profit",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+/// Position after the last row of a page: the sort key and id of that row.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OpportunityCursor {
+    Timestamp(DateTime<Utc>, String),
+    Profit(f64, String),
+}
+
+impl OpportunityCursor {
+    fn after(opportunity: &ArbitrageOpportunity, sort: OpportunitySort) -> Self {
+        match sort {
+            OpportunitySort::Timestamp => OpportunityCursor::Timestamp(opportunity.timestamp, opportunity.id.clone()),
+            OpportunitySort::Profit => OpportunityCursor::Profit(opportunity.estimated_profit, opportunity.id.clone()),
+        }
+    }
+
+    fn sort(&self) -> OpportunitySort {
+        match self {
+            OpportunityCursor::Timestamp(..) => OpportunitySort::Timestamp,
+            OpportunityCursor::Profit(..) => OpportunitySort::Profit,
+        }
+    }
+
+    pub(crate) fn id(&self) -> &str {
+        match self {
+            OpportunityCursor::Timestamp(_, id) | OpportunityCursor::Profit(_, id) => id,
+        }
+    }
+
+    /// Opaque token handed to API clients.
+    pub fn encode(&self) -> String {
+        let raw = match self {
+            OpportunityCursor::Timestamp(timestamp, id) => format!("{}|{}|{}", self.sort().as_str(), timestamp.to_rfc3339(), id),
+            OpportunityCursor::Profit(profit, id) => format!("{}|{}|{}", self.sort().as_str(), profit, id),
+        };
+        hex::encode(raw)
+    }
+
+    /// Parses a token produced by `encode`, which must have been issued for `sort`.
+    pub fn decode(token: &str, sort: OpportunitySort) -> Result<Self> {
+        let bytes = hex::decode(token).with_context(|| "Malformed cursor")?;
+        let raw = String::from_utf8(bytes).with_context(|| "Malformed cursor")?;
+        let mut parts = raw.splitn(3, '|');
+
+        let (Some(field), Some(value), Some(id)) = (parts.next(), parts.next(), parts.next()) else {
+            anyhow::bail!("Malformed cursor");
+        };
+        if field != sort.as_str() {
+            anyhow::bail!("Cursor was issued for sort={}, not sort={}", field, sort.as_str());
+        }
+
+        Ok(match sort {
+            OpportunitySort::Timestamp => OpportunityCursor::Timestamp(
+                value.parse().with_context(|| "Malformed cursor timestamp")?,
+                id.to_string(),
+            ),
+            OpportunitySort::Profit => OpportunityCursor::Profit(
+                value.parse().with_context(|| "Malformed cursor profit")?,
+                id.to_string(),
+            ),
+        })
+    }
+}
+
+/// Filters, ordering and position for `Store::query_opportunities`.
+#[derive(Debug, Clone)]
+pub struct OpportunityQuery {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub pair: Option<String>,
+    pub buy_exchange: Option<String>,
+    pub sell_exchange: Option<String>,
+    pub min_profit: Option<f64>,
+    pub sort: OpportunitySort,
+    pub order: SortOrder,
+    pub cursor: Option<OpportunityCursor>,
+    pub limit: i64,
+}
+
+impl Default for OpportunityQuery {
+    fn default() -> Self {
+        Self {
+            from: None,
+            to: None,
+            pair: None,
+            buy_exchange: None,
+            sell_exchange: None,
+            min_profit: None,
+            sort: OpportunitySort::default(),
+            order: SortOrder::default(),
+            cursor: None,
+            limit: 50,
+        }
+    }
+}
+
+pub struct OpportunityPage {
+    pub opportunities: Vec<ArbitrageOpportunity>,
+    /// Matches across all pages, ignoring the cursor
+    pub total: i64,
+    pub next_cursor: Option<String>,
+}
+
+impl OpportunityPage {
+    /// Builds a page from up to `limit + 1` fetched rows; the extra row only
+    /// signals that another page exists.
+    pub(crate) fn from_rows(mut rows: Vec<ArbitrageOpportunity>, total: i64, query: &OpportunityQuery) -> Self {
+        let has_more = rows.len() as i64 > query.limit;
+        rows.truncate(query.limit.max(0) as usize);
+
+        let next_cursor = if has_more {
+            rows.last().map(|last| OpportunityCursor::after(last, query.sort).encode())
+        } else {
+            None
+        };
+
+        Self { opportunities: rows, total, next_cursor }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BucketInterval {
+    #[default]
+    Hour,
+    Day,
+}
+
+/// Opportunity statistics for one trade direction within one time bucket.
+/// Percentiles use the nearest-rank method on both backends.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct ProfitBucket {
+    pub bucket_start: DateTime<Utc>,
+    pub pair: String,
+    pub buy_exchange: String,
+    pub sell_exchange: String,
+    pub count: i64,
+    pub total_profit: f64,
+    pub median_profit: f64,
+    pub p95_profit: f64,
+    pub average_spread: f64,
+    pub max_spread: f64,
+    /// Mean lifetime of the opportunities first seen in this bucket, if any
+    /// lifecycle was tracked for them
+    pub mean_duration_seconds: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct VenueStanding {
+    pub pair: String,
+    pub buy_exchange: String,
+    pub sell_exchange: String,
+    pub wins: i64,
+    /// Fraction of all opportunities for the pair won by this direction
+    pub win_share: f64,
+    pub total_profit: f64,
+    pub average_profit: f64,
+    pub best_profit: f64,
+}
+
+/// Connects to the store selected by the URL scheme without touching the schema.
+pub async fn connect(database_url: &str) -> Result<Arc<dyn Store>> {
+    if database_url.starts_with("postgres://") || database_url.starts_with("postgresql://") {
+        Ok(Arc::new(PostgresStore::connect(database_url).await?))
+    } else if database_url.starts_with("sqlite:") {
+        Ok(Arc::new(SqliteStore::connect(database_url).await?))
+    } else {
+        anyhow::bail!("Unsupported database URL scheme: {}", database_url)
+    }
+}
+
+/// Connects and brings the schema up to date.
+pub async fn open(database_url: &str) -> Result<Arc<dyn Store>> {
+    let store = connect(database_url).await?;
+
+    let applied = store.migrate().await?;
+    if !applied.is_empty() {
+        info!("Applied {} database migration(s)", applied.len());
+    }
+
+    info!("Database initialized successfully");
+
+    Ok(store)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::arbitrage::MONITORED_PAIR;
+    use chrono::Utc;
+    use sqlx::{Connection, Executor, PgConnection};
+    use uuid::Uuid;
+
+    pub fn temp_sqlite_url() -> String {
+        let path = std::env::temp_dir().join(format!("arb-test-{}.db", Uuid::new_v4()));
+        format!("sqlite:{}", path.display())
+    }
+
+    /// Creates a throwaway database on the server named by `TEST_POSTGRES_URL`.
+    pub async fn temp_postgres_url() -> String {
+        let admin_url = std::env::var("TEST_POSTGRES_URL")
+            .expect("TEST_POSTGRES_URL must name a PostgreSQL server to run the PostgreSQL suite");
+        let name = format!("arb_test_{}", Uuid::new_v4().simple());
+
+        let mut admin = PgConnection::connect(&admin_url).await
+            .expect("TEST_POSTGRES_URL is not reachable");
+        admin.execute(format!("CREATE DATABASE {}", name).as_str()).await.unwrap();
+
+        let (base, _) = admin_url.rsplit_once('/').expect("TEST_POSTGRES_URL needs a database path");
+        format!("{}/{}", base, name)
+    }
+
+    pub fn opportunity(profit: f64) -> ArbitrageOpportunity {
+        ArbitrageOpportunity {
+            id: Uuid::new_v4().to_string(),
+            timestamp: Utc::now(),
+            pair: MONITORED_PAIR.to_string(),
+            block_number: Some(100),
+            buy_exchange: "QuickSwap".to_string(),
+            sell_exchange: "SushiSwap".to_string(),
+            buy_price: 3000.0,
+            sell_price: 3000.0 + profit + 1.0,
+            estimated_profit: profit,
+            price_difference: profit + 1.0,
+            gas_cost_estimate: 1.0,
+            profit_percentage: profit / 3000.0 * 100.0,
+        }
+    }
+
+    async fn check_opportunities(store: &dyn Store) {
+        store.store_opportunity(&opportunity(6.0)).await.unwrap();
+        store.store_opportunity(&opportunity(10.0)).await.unwrap();
+
+        let recent = store.query_opportunities(&OpportunityQuery::default()).await.unwrap().opportunities;
+        assert_eq!(recent.len(), 2);
+        assert!(recent.iter().all(|o| o.pair == MONITORED_PAIR && o.block_number == Some(100)));
+
+        let (total, avg, max) = store.get_stats().await.unwrap();
+        assert_eq!(total, 2);
+        assert_eq!(avg, 8.0);
+        assert_eq!(max, 10.0);
+    }
+
+    async fn check_opportunity_query(store: &dyn Store) {
+        let start = Utc::now() - chrono::Duration::hours(1);
+        for (minute, profit) in [(0, 3.0), (1, 9.0), (2, 1.0), (3, 9.0), (4, 5.0)] {
+            let mut opp = opportunity(profit);
+            opp.timestamp = start + chrono::Duration::minutes(minute);
+            if minute == 4 {
+                opp.buy_exchange = "SushiSwap".to_string();
+                opp.sell_exchange = "QuickSwap".to_string();
+            }
+            store.store_opportunity(&opp).await.unwrap();
+        }
+
+        // Walk every page of the highest-profit-first ordering
+        let mut query = OpportunityQuery { sort: OpportunitySort::Profit, limit: 2, ..Default::default() };
+        let mut profits = Vec::new();
+        loop {
+            let page = store.query_opportunities(&query).await.unwrap();
+            assert_eq!(page.total, 5);
+            profits.extend(page.opportunities.iter().map(|o| o.estimated_profit));
+            match page.next_cursor {
+                Some(token) => query.cursor = Some(OpportunityCursor::decode(&token, query.sort).unwrap()),
+                None => break,
+            }
+        }
+        assert_eq!(profits, vec![9.0, 9.0, 5.0, 3.0, 1.0]);
+
+        let page = store.query_opportunities(&OpportunityQuery {
+            from: Some(start + chrono::Duration::minutes(1)),
+            to: Some(start + chrono::Duration::minutes(3)),
+            min_profit: Some(2.0),
+            order: SortOrder::Asc,
+            ..Default::default()
+        }).await.unwrap();
+        assert_eq!(page.total, 2);
+        assert!(page.next_cursor.is_none());
+        assert!(page.opportunities[0].timestamp < page.opportunities[1].timestamp);
+
+        let page = store.query_opportunities(&OpportunityQuery {
+            buy_exchange: Some("SushiSwap".to_string()),
+            pair: Some(MONITORED_PAIR.to_string()),
+            ..Default::default()
+        }).await.unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.opportunities[0].estimated_profit, 5.0);
+    }
+
+    async fn check_predictions(store: &dyn Store) {
+        for resolution in [Some(Some(true)), Some(Some(false)), Some(None), None] {
+            let prediction = PredictedOpportunity {
+                opportunity: opportunity(6.0),
+                trigger_tx: format!("0x{}", "ab".repeat(32)),
+                trigger_venue: "QuickSwap".to_string(),
+                target_block: 101,
+                confirmed: None,
+                expired: false,
+            };
+            store.store_prediction(&prediction).await.unwrap();
+            if let Some(confirmed) = resolution {
+                store.resolve_prediction(&prediction.opportunity.id, confirmed).await.unwrap();
+            }
+        }
+
+        let recent = store.get_recent_predictions(10).await.unwrap();
+        assert_eq!(recent.len(), 4);
+        assert!(recent.iter().all(|p| p.opportunity.block_number == Some(101)));
+        assert_eq!(recent.iter().filter(|p| p.expired).count(), 1);
+        assert!(recent.iter().filter(|p| p.expired).all(|p| p.confirmed.is_none()));
+        assert_eq!(store.get_prediction_stats().await.unwrap(), (4, 1, 1, 1));
+    }
+
+    async fn check_lifecycles(store: &dyn Store) {
+        let now = Utc::now();
+        let mut lifecycle = OpportunityLifecycle {
+            id: Uuid::new_v4().to_string(),
+            pair: MONITORED_PAIR.to_string(),
+            buy_exchange: "QuickSwap".to_string(),
+            sell_exchange: "SushiSwap".to_string(),
+            first_seen_block: 100,
+            last_seen_block: 100,
+            first_seen_at: now,
+            last_seen_at: now,
+            peak_profit: 6.0,
+            peak_profit_percentage: 0.2,
+            observations: 1,
+            closed_at: None,
+        };
+        store.upsert_lifecycle(&lifecycle).await.unwrap();
+        assert_eq!(store.get_open_lifecycles().await.unwrap().len(), 1);
+
+        lifecycle.last_seen_block = 110;
+        lifecycle.last_seen_at = now + chrono::Duration::seconds(20);
+        lifecycle.observations = 3;
+        lifecycle.closed_at = Some(lifecycle.last_seen_at);
+        store.upsert_lifecycle(&lifecycle).await.unwrap();
+
+        assert!(store.get_open_lifecycles().await.unwrap().is_empty());
+        let recent = store.get_recent_lifecycles(10).await.unwrap();
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].last_seen_block, 110);
+        assert!(!recent[0].is_open());
+
+        let (total, open, avg_duration, avg_observations) = store.get_lifecycle_stats().await.unwrap();
+        assert_eq!((total, open), (1, 0));
+        assert!((avg_duration - 20.0).abs() < 0.01);
+        assert_eq!(avg_observations, 3.0);
+    }
+
+    async fn check_price_snapshots(store: &dyn Store) {
+        let now = Utc::now();
+        // Aligned to a day so both old snapshots share one downsampling bucket
+        let old = DateTime::from_timestamp(now.timestamp() - now.timestamp() % 86_400 - 3 * 86_400, 0).unwrap();
+        let snapshot = |timestamp, venue: &str| PriceSnapshot {
+            check_id: Uuid::new_v4().to_string(),
+            timestamp,
+            block_number: 100,
+            pair: MONITORED_PAIR.to_string(),
+            venue: venue.to_string(),
+            amount_in: "1000000000000000000".to_string(),
+            amount_out: "3000123456".to_string(),
+            price: 3000.123456,
+            latency_ms: 42.0,
+        };
+
+        store.store_price_snapshots(&[
+            snapshot(old, "QuickSwap"),
+            snapshot(old + chrono::Duration::seconds(1), "QuickSwap"),
+            snapshot(now, "SushiSwap"),
+        ]).await.unwrap();
+
+        let recent = store.get_recent_price_snapshots(10).await.unwrap();
+        assert_eq!(recent.len(), 3);
+        assert_eq!(recent[0].venue, "SushiSwap");
+        assert_eq!(recent[0].amount_out, "3000123456");
+
+        assert_eq!(store.downsample_price_snapshots(now - chrono::Duration::days(1), 86_400).await.unwrap(), 1);
+        assert_eq!(store.prune_price_snapshots(now - chrono::Duration::days(1)).await.unwrap(), 1);
+        assert_eq!(store.get_recent_price_snapshots(10).await.unwrap().len(), 1);
+    }
+
+    async fn check_analytics(store: &dyn Store) {
+        let now = Utc::now();
+        let yesterday = DateTime::from_timestamp(now.timestamp() - now.timestamp() % 86_400 - 86_400, 0).unwrap();
+        let first_hour = yesterday + chrono::Duration::hours(1);
+
+        for (minute, profit) in [(0, 4.0), (5, 1.0), (10, 10.0), (15, 2.0), (20, 3.0)] {
+            let mut opp = opportunity(profit);
+            opp.timestamp = first_hour + chrono::Duration::minutes(minute);
+            store.store_opportunity(&opp).await.unwrap();
+        }
+        let mut reversed = opportunity(5.0);
+        reversed.timestamp = first_hour + chrono::Duration::hours(1);
+        reversed.buy_exchange = "SushiSwap".to_string();
+        reversed.sell_exchange = "QuickSwap".to_string();
+        store.store_opportunity(&reversed).await.unwrap();
+
+        store.upsert_lifecycle(&OpportunityLifecycle {
+            id: Uuid::new_v4().to_string(),
+            pair: MONITORED_PAIR.to_string(),
+            buy_exchange: "QuickSwap".to_string(),
+            sell_exchange: "SushiSwap".to_string(),
+            first_seen_block: 100,
+            last_seen_block: 130,
+            first_seen_at: first_hour,
+            last_seen_at: first_hour + chrono::Duration::seconds(60),
+            peak_profit: 10.0,
+            peak_profit_percentage: 0.3,
+            observations: 5,
+            closed_at: Some(first_hour + chrono::Duration::seconds(60)),
+        }).await.unwrap();
+
+        let hourly = store.get_profit_buckets(BucketInterval::Hour, yesterday, now).await.unwrap();
+        assert_eq!(hourly.len(), 2);
+        assert_eq!(hourly[0].bucket_start, first_hour);
+        assert_eq!(hourly[0].count, 5);
+        assert_eq!(hourly[0].total_profit, 20.0);
+        assert_eq!(hourly[0].median_profit, 3.0);
+        assert_eq!(hourly[0].p95_profit, 10.0);
+        assert_eq!(hourly[0].max_spread, 11.0);
+        assert!((hourly[0].mean_duration_seconds.unwrap() - 60.0).abs() < 0.01);
+        assert_eq!(hourly[1].buy_exchange, "SushiSwap");
+        assert_eq!(hourly[1].mean_duration_seconds, None);
+
+        let daily = store.get_profit_buckets(BucketInterval::Day, yesterday, now).await.unwrap();
+        assert_eq!(daily.len(), 2);
+        assert!(daily.iter().all(|b| b.bucket_start == yesterday));
+
+        let leaderboard = store.get_venue_leaderboard(yesterday, now).await.unwrap();
+        assert_eq!(leaderboard.len(), 2);
+        assert_eq!(leaderboard[0].buy_exchange, "QuickSwap");
+        assert_eq!(leaderboard[0].wins, 5);
+        assert!((leaderboard[0].win_share - 5.0 / 6.0).abs() < 1e-9);
+        assert_eq!(leaderboard[0].best_profit, 10.0);
+        assert_eq!(leaderboard[1].wins, 1);
+    }
+
+    async fn check_control_audit(store: &dyn Store) {
+        let now = Utc::now();
+        for (offset, action, previous, new) in [
+            (0, "pause", None, None),
+            (1, "set_min_profit_usdc", Some("1000000"), Some("2500000")),
+        ] {
+            store.record_control_action(&ControlAction {
+                timestamp: now + chrono::Duration::seconds(offset),
+                actor: "admin".to_string(),
+                action: action.to_string(),
+                previous_value: previous.map(str::to_string),
+                new_value: new.map(str::to_string),
+            }).await.unwrap();
+        }
+
+        let recent = store.get_recent_control_actions(10).await.unwrap();
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].action, "set_min_profit_usdc");
+        assert_eq!(recent[0].new_value.as_deref(), Some("2500000"));
+        assert_eq!(recent[1].previous_value, None);
+    }
+
+    async fn check_migrations(store: &dyn Store) {
+        assert!(store.migrate().await.unwrap().is_empty());
+
+        let status = store.migration_status().await.unwrap();
+        assert!(status.iter().all(|m| m.applied_at.is_some()));
+        assert_eq!(store.schema_version().await.unwrap(), status.last().unwrap().version);
+    }
+
+    /// Runs each check against a fresh SQLite store and a fresh PostgreSQL
+    /// database. The PostgreSQL suite is ignored by default; run it with
+    /// `TEST_POSTGRES_URL` set and `cargo test -- --include-ignored`.
+    macro_rules! store_suite {
+        ($($check:ident),* $(,)?) => {
+            mod sqlite_suite {
+                $(
+                    #[tokio::test]
+                    async fn $check() {
+                        let store = super::open(&super::temp_sqlite_url()).await.unwrap();
+                        super::$check(&*store).await;
+                    }
+                )*
+            }
+
+            mod postgres_suite {
+                $(
+                    #[tokio::test]
+                    #[ignore = "needs a PostgreSQL server in TEST_POSTGRES_URL"]
+                    async fn $check() {
+                        let store = super::open(&super::temp_postgres_url().await).await.unwrap();
+                        super::$check(&*store).await;
+                    }
+                )*
+            }
+        };
+    }
+
+    store_suite!(
+        check_opportunities,
+        check_opportunity_query,
+        check_predictions,
+        check_lifecycles,
+        check_price_snapshots,
+        check_analytics,
+        check_control_audit,
+        check_migrations,
+    );
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let cursor = OpportunityCursor::Profit(12.345678, "abc".to_string());
+        assert_eq!(OpportunityCursor::decode(&cursor.encode(), OpportunitySort::Profit).unwrap(), cursor);
+        assert!(OpportunityCursor::decode(&cursor.encode(), OpportunitySort::Timestamp).is_err());
+        assert!(OpportunityCursor::decode("not-hex", OpportunitySort::Profit).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rejects_unknown_scheme() {
+        assert!(connect("mysql://localhost/arb").await.is_err());
+    }
+}
@@ -0,0 +1,715 @@
+use anyhow::{Result, Context};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{Connection, Executor, PgPool, Postgres, QueryBuilder, Row, postgres::{PgConnectOptions, PgPoolOptions, PgRow}};
+use std::str::FromStr;
+use tracing::{info, debug};
+
+use crate::arbitrage::{ArbitrageOpportunity, PredictedOpportunity};
+use crate::config::redact_url;
+use crate::control::ControlAction;
+use crate::lifecycle::OpportunityLifecycle;
+use crate::snapshots::PriceSnapshot;
+use super::migrations::{self, MigrationStatus, POSTGRES_MIGRATIONS};
+use super::{BucketInterval, OpportunityCursor, ProfitBucket, VenueStanding, OpportunityPage, OpportunityQuery, SortOrder, Store};
+
+/// Key for the advisory lock serialising migrations across instances.
+const MIGRATION_LOCK_KEY: i64 = 0x6172_6269_7472_6167;
+
+#[derive(Clone)]
+pub struct PostgresStore {
+    pool: PgPool,
+}
+
+impl PostgresStore {
+    /// Connects without touching the schema; call `migrate` to bring it up to date.
+    pub async fn connect(database_url: &str) -> Result<Self> {
+        info!("Initializing PostgreSQL connection: {}", redact_url(database_url));
+
+        let options = PgConnectOptions::from_str(database_url)
+            .with_context(|| "Invalid PostgreSQL connection URL")?;
+
+        let pool = PgPoolOptions::new()
+            .max_connections(10)
+            .connect_with(options)
+            .await
+            .with_context(|| format!("Failed to connect to PostgreSQL: {}", redact_url(database_url)))?;
+
+        Ok(Self { pool })
+    }
+
+    async fn ensure_version_table(&self) -> Result<()> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS schema_version (
+                version BIGINT PRIMARY KEY,
+                description TEXT NOT NULL,
+                applied_at TIMESTAMPTZ NOT NULL DEFAULT now()
+            )
+            "#
+        )
+        .execute(&self.pool)
+        .await
+        .with_context(|| "Failed to create schema_version table")?;
+
+        Ok(())
+    }
+
+    fn row_to_opportunity(row: &PgRow) -> ArbitrageOpportunity {
+        ArbitrageOpportunity {
+            id: row.get("id"),
+            timestamp: row.get("timestamp"),
+            pair: row.get("pair"),
+            block_number: row.get::<Option<i64>, _>("block_number").map(|b| b as u64),
+            buy_exchange: row.get("buy_exchange"),
+            sell_exchange: row.get("sell_exchange"),
+            buy_price: row.get("buy_price"),
+            sell_price: row.get("sell_price"),
+            estimated_profit: row.get("estimated_profit"),
+            price_difference: row.get("price_difference"),
+            gas_cost_estimate: row.get("gas_cost_estimate"),
+            profit_percentage: row.get("profit_percentage"),
+        }
+    }
+
+    /// Appends the WHERE clause for `query`, optionally restricted to rows
+    /// after its cursor.
+    fn push_opportunity_filters(builder: &mut QueryBuilder<'_, Postgres>, query: &OpportunityQuery, after_cursor: bool) {
+        builder.push(" WHERE 1 = 1");
+        if let Some(from) = query.from {
+            builder.push(" AND timestamp >= ").push_bind(from);
+        }
+        if let Some(to) = query.to {
+            builder.push(" AND timestamp <= ").push_bind(to);
+        }
+        if let Some(pair) = &query.pair {
+            builder.push(" AND pair = ").push_bind(pair.clone());
+        }
+        if let Some(buy_exchange) = &query.buy_exchange {
+            builder.push(" AND buy_exchange = ").push_bind(buy_exchange.clone());
+        }
+        if let Some(sell_exchange) = &query.sell_exchange {
+            builder.push(" AND sell_exchange = ").push_bind(sell_exchange.clone());
+        }
+        if let Some(min_profit) = query.min_profit {
+            builder.push(" AND estimated_profit >= ").push_bind(min_profit);
+        }
+
+        if let (true, Some(cursor)) = (after_cursor, &query.cursor) {
+            let column = query.sort.column();
+            let op = match query.order {
+                SortOrder::Asc => ">",
+                SortOrder::Desc => "<",
+            };
+            builder.push(format!(" AND ({} {} ", column, op));
+            match cursor {
+                OpportunityCursor::Timestamp(timestamp, _) => builder.push_bind(*timestamp),
+                OpportunityCursor::Profit(profit, _) => builder.push_bind(*profit),
+            };
+            builder.push(format!(" OR ({} = ", column));
+            match cursor {
+                OpportunityCursor::Timestamp(timestamp, _) => builder.push_bind(*timestamp),
+                OpportunityCursor::Profit(profit, _) => builder.push_bind(*profit),
+            };
+            builder.push(format!(" AND id {} ", op))
+                .push_bind(cursor.id().to_string())
+                .push("))");
+        }
+    }
+
+    async fn select_opportunities(&self, query: &OpportunityQuery, limit: i64) -> Result<Vec<ArbitrageOpportunity>> {
+        let direction = match query.order {
+            SortOrder::Asc => "ASC",
+            SortOrder::Desc => "DESC",
+        };
+        let mut select = QueryBuilder::<Postgres>::new("SELECT * FROM arbitrage_opportunities");
+        Self::push_opportunity_filters(&mut select, query, true);
+        select.push(format!(" ORDER BY {} {}, id {} LIMIT ", query.sort.column(), direction, direction))
+            .push_bind(limit);
+
+        let rows = select.build()
+            .fetch_all(&self.pool)
+            .await
+            .with_context(|| "Failed to query opportunities")?;
+        Ok(rows.iter().map(Self::row_to_opportunity).collect())
+    }
+
+    fn row_to_snapshot(row: &PgRow) -> PriceSnapshot {
+        PriceSnapshot {
+            check_id: row.get("check_id"),
+            timestamp: row.get("timestamp"),
+            block_number: row.get::<i64, _>("block_number") as u64,
+            pair: row.get("pair"),
+            venue: row.get("venue"),
+            amount_in: row.get("amount_in"),
+            amount_out: row.get("amount_out"),
+            price: row.get("price"),
+            latency_ms: row.get("latency_ms"),
+        }
+    }
+
+    fn row_to_lifecycle(row: &PgRow) -> OpportunityLifecycle {
+        OpportunityLifecycle {
+            id: row.get("id"),
+            pair: row.get("pair"),
+            buy_exchange: row.get("buy_exchange"),
+            sell_exchange: row.get("sell_exchange"),
+            first_seen_block: row.get::<i64, _>("first_seen_block") as u64,
+            last_seen_block: row.get::<i64, _>("last_seen_block") as u64,
+            first_seen_at: row.get("first_seen_at"),
+            last_seen_at: row.get("last_seen_at"),
+            peak_profit: row.get("peak_profit"),
+            peak_profit_percentage: row.get("peak_profit_percentage"),
+            observations: row.get::<i64, _>("observations") as u64,
+            closed_at: row.get("closed_at"),
+        }
+    }
+}
+
+#[async_trait]
+impl Store for PostgresStore {
+    async fn migrate(&self) -> Result<Vec<i64>> {
+        self.ensure_version_table().await?;
+
+        // Several instances may start at once; only one may migrate at a time
+        let mut conn = self.pool.acquire().await?;
+        sqlx::query("SELECT pg_advisory_lock($1)")
+            .bind(MIGRATION_LOCK_KEY)
+            .execute(&mut *conn)
+            .await
+            .with_context(|| "Failed to acquire migration lock")?;
+
+        let result = async {
+            let current = sqlx::query_as::<_, (Option<i64>,)>("SELECT MAX(version) FROM schema_version")
+                .fetch_one(&mut *conn)
+                .await?
+                .0
+                .unwrap_or(0);
+            let mut applied = Vec::new();
+
+            for migration in POSTGRES_MIGRATIONS.iter().filter(|m| m.version > current) {
+                info!("Applying migration {}: {}", migration.version, migration.description);
+
+                let mut tx = conn.begin().await?;
+
+                tx.execute(migration.sql).await
+                    .with_context(|| format!("Migration {} ({}) failed", migration.version, migration.description))?;
+
+                sqlx::query("INSERT INTO schema_version (version, description) VALUES ($1, $2)")
+                    .bind(migration.version)
+                    .bind(migration.description)
+                    .execute(&mut *tx)
+                    .await
+                    .with_context(|| format!("Failed to record migration {}", migration.version))?;
+
+                tx.commit().await?;
+                applied.push(migration.version);
+            }
+
+            anyhow::Ok(applied)
+        }.await;
+
+        sqlx::query("SELECT pg_advisory_unlock($1)")
+            .bind(MIGRATION_LOCK_KEY)
+            .execute(&mut *conn)
+            .await
+            .with_context(|| "Failed to release migration lock")?;
+
+        debug!("Database schema at version {}", migrations::latest_version(POSTGRES_MIGRATIONS));
+
+        result
+    }
+
+    async fn migration_status(&self) -> Result<Vec<MigrationStatus>> {
+        self.ensure_version_table().await?;
+
+        let applied: Vec<(i64, String)> = sqlx::query_as::<_, (i64, DateTime<Utc>)>(
+            "SELECT version, applied_at FROM schema_version"
+        )
+        .fetch_all(&self.pool)
+        .await
+        .with_context(|| "Failed to read applied migrations")?
+        .into_iter()
+        .map(|(version, applied_at)| (version, applied_at.to_rfc3339()))
+        .collect();
+
+        Ok(migrations::status(POSTGRES_MIGRATIONS, &applied))
+    }
+
+    async fn schema_version(&self) -> Result<i64> {
+        self.ensure_version_table().await?;
+
+        let version = sqlx::query_as::<_, (Option<i64>,)>("SELECT MAX(version) FROM schema_version")
+            .fetch_one(&self.pool)
+            .await
+            .with_context(|| "Failed to read schema version")?;
+
+        Ok(version.0.unwrap_or(0))
+    }
+
+    async fn store_opportunity(&self, opportunity: &ArbitrageOpportunity) -> Result<()> {
+        info!("Storing opportunity with ID: {}", opportunity.id);
+
+        sqlx::query(
+            r#"
+            INSERT INTO arbitrage_opportunities
+            (id, timestamp, pair, block_number, buy_exchange, sell_exchange, buy_price, sell_price,
+             estimated_profit, price_difference, gas_cost_estimate, profit_percentage)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            "#
+        )
+        .bind(&opportunity.id)
+        .bind(opportunity.timestamp)
+        .bind(&opportunity.pair)
+        .bind(opportunity.block_number.map(|b| b as i64))
+        .bind(&opportunity.buy_exchange)
+        .bind(&opportunity.sell_exchange)
+        .bind(opportunity.buy_price)
+        .bind(opportunity.sell_price)
+        .bind(opportunity.estimated_profit)
+        .bind(opportunity.price_difference)
+        .bind(opportunity.gas_cost_estimate)
+        .bind(opportunity.profit_percentage)
+        .execute(&self.pool)
+        .await
+        .with_context(|| "Failed to store arbitrage opportunity")?;
+
+        Ok(())
+    }
+
+    async fn query_opportunities(&self, query: &OpportunityQuery) -> Result<OpportunityPage> {
+        let mut count = QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM arbitrage_opportunities");
+        Self::push_opportunity_filters(&mut count, query, false);
+        let total: i64 = count.build_query_scalar()
+            .fetch_one(&self.pool)
+            .await
+            .with_context(|| "Failed to count opportunities")?;
+
+        let opportunities = self.select_opportunities(query, query.limit + 1).await?;
+
+        Ok(OpportunityPage::from_rows(opportunities, total, query))
+    }
+
+    async fn scan_opportunities(&self, query: &OpportunityQuery) -> Result<Vec<ArbitrageOpportunity>> {
+        self.select_opportunities(query, query.limit).await
+    }
+
+    async fn get_stats(&self) -> Result<(i64, f64, f64)> {
+        let row = sqlx::query_as::<_, (i64, Option<f64>, Option<f64>)>(
+            r#"
+            SELECT
+                COUNT(*) as total,
+                AVG(estimated_profit) as avg_profit,
+                MAX(estimated_profit) as max_profit
+            FROM arbitrage_opportunities
+            "#
+        )
+        .fetch_one(&self.pool)
+        .await
+        .with_context(|| "Failed to fetch stats")?;
+
+        Ok((row.0, row.1.unwrap_or(0.0), row.2.unwrap_or(0.0)))
+    }
+
+    async fn store_prediction(&self, prediction: &PredictedOpportunity) -> Result<()> {
+        let opportunity = &prediction.opportunity;
+        debug!("Storing prediction with ID: {}", opportunity.id);
+
+        sqlx::query(
+            r#"
+            INSERT INTO predicted_opportunities
+            (id, timestamp, pair, trigger_tx, trigger_venue, target_block, buy_exchange, sell_exchange,
+             buy_price, sell_price, estimated_profit, price_difference, gas_cost_estimate, profit_percentage)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+            "#
+        )
+        .bind(&opportunity.id)
+        .bind(opportunity.timestamp)
+        .bind(&opportunity.pair)
+        .bind(&prediction.trigger_tx)
+        .bind(&prediction.trigger_venue)
+        .bind(prediction.target_block as i64)
+        .bind(&opportunity.buy_exchange)
+        .bind(&opportunity.sell_exchange)
+        .bind(opportunity.buy_price)
+        .bind(opportunity.sell_price)
+        .bind(opportunity.estimated_profit)
+        .bind(opportunity.price_difference)
+        .bind(opportunity.gas_cost_estimate)
+        .bind(opportunity.profit_percentage)
+        .execute(&self.pool)
+        .await
+        .with_context(|| "Failed to store predicted opportunity")?;
+
+        Ok(())
+    }
+
+    async fn resolve_prediction(&self, id: &str, confirmed: Option<bool>) -> Result<()> {
+        sqlx::query(
+            "UPDATE predicted_opportunities SET confirmed = $1, resolved_at = now() WHERE id = $2"
+        )
+        .bind(confirmed)
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .with_context(|| format!("Failed to resolve prediction {}", id))?;
+
+        Ok(())
+    }
+
+    async fn get_recent_predictions(&self, limit: i64) -> Result<Vec<PredictedOpportunity>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT *, target_block AS block_number FROM predicted_opportunities
+            ORDER BY created_at DESC, timestamp DESC LIMIT $1
+            "#
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .with_context(|| "Failed to fetch recent predictions")?;
+
+        Ok(rows.iter().map(|row| PredictedOpportunity {
+            opportunity: Self::row_to_opportunity(row),
+            trigger_tx: row.get("trigger_tx"),
+            trigger_venue: row.get("trigger_venue"),
+            target_block: row.get::<i64, _>("target_block") as u64,
+            confirmed: row.get("confirmed"),
+            expired: row.get::<Option<DateTime<Utc>>, _>("resolved_at").is_some()
+                && row.get::<Option<bool>, _>("confirmed").is_none(),
+        }).collect())
+    }
+
+    async fn get_prediction_stats(&self) -> Result<(i64, i64, i64, i64)> {
+        let row = sqlx::query_as::<_, (i64, i64, i64, i64)>(
+            r#"
+            SELECT
+                COUNT(*) as total,
+                COUNT(*) FILTER (WHERE confirmed) as confirmed,
+                COUNT(*) FILTER (WHERE NOT confirmed) as refuted,
+                COUNT(*) FILTER (WHERE confirmed IS NULL AND resolved_at IS NOT NULL) as expired
+            FROM predicted_opportunities
+            "#
+        )
+        .fetch_one(&self.pool)
+        .await
+        .with_context(|| "Failed to fetch prediction stats")?;
+
+        Ok(row)
+    }
+
+    async fn upsert_lifecycle(&self, lifecycle: &OpportunityLifecycle) -> Result<()> {
+        debug!("Storing lifecycle with ID: {}", lifecycle.id);
+
+        sqlx::query(
+            r#"
+            INSERT INTO opportunity_lifecycles
+            (id, pair, buy_exchange, sell_exchange, first_seen_block, last_seen_block,
+             first_seen_at, last_seen_at, peak_profit, peak_profit_percentage, observations, closed_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            ON CONFLICT (id) DO UPDATE SET
+                last_seen_block = excluded.last_seen_block,
+                last_seen_at = excluded.last_seen_at,
+                peak_profit = excluded.peak_profit,
+                peak_profit_percentage = excluded.peak_profit_percentage,
+                observations = excluded.observations,
+                closed_at = excluded.closed_at
+            "#
+        )
+        .bind(&lifecycle.id)
+        .bind(&lifecycle.pair)
+        .bind(&lifecycle.buy_exchange)
+        .bind(&lifecycle.sell_exchange)
+        .bind(lifecycle.first_seen_block as i64)
+        .bind(lifecycle.last_seen_block as i64)
+        .bind(lifecycle.first_seen_at)
+        .bind(lifecycle.last_seen_at)
+        .bind(lifecycle.peak_profit)
+        .bind(lifecycle.peak_profit_percentage)
+        .bind(lifecycle.observations as i64)
+        .bind(lifecycle.closed_at)
+        .execute(&self.pool)
+        .await
+        .with_context(|| "Failed to store opportunity lifecycle")?;
+
+        Ok(())
+    }
+
+    async fn get_open_lifecycles(&self) -> Result<Vec<OpportunityLifecycle>> {
+        let rows = sqlx::query(
+            "SELECT * FROM opportunity_lifecycles WHERE closed_at IS NULL"
+        )
+        .fetch_all(&self.pool)
+        .await
+        .with_context(|| "Failed to fetch open lifecycles")?;
+
+        Ok(rows.iter().map(Self::row_to_lifecycle).collect())
+    }
+
+    async fn get_recent_lifecycles(&self, limit: i64) -> Result<Vec<OpportunityLifecycle>> {
+        let rows = sqlx::query(
+            "SELECT * FROM opportunity_lifecycles ORDER BY last_seen_at DESC LIMIT $1"
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .with_context(|| "Failed to fetch recent lifecycles")?;
+
+        Ok(rows.iter().map(Self::row_to_lifecycle).collect())
+    }
+
+    async fn get_lifecycle_stats(&self) -> Result<(i64, i64, f64, f64)> {
+        let row = sqlx::query_as::<_, (i64, i64, Option<f64>, Option<f64>)>(
+            r#"
+            SELECT
+                COUNT(*) as total,
+                COUNT(*) FILTER (WHERE closed_at IS NULL) as open,
+                AVG(EXTRACT(EPOCH FROM (last_seen_at - first_seen_at)))::DOUBLE PRECISION as avg_duration,
+                AVG(observations)::DOUBLE PRECISION as avg_observations
+            FROM opportunity_lifecycles
+            "#
+        )
+        .fetch_one(&self.pool)
+        .await
+        .with_context(|| "Failed to fetch lifecycle stats")?;
+
+        Ok((row.0, row.1, row.2.unwrap_or(0.0), row.3.unwrap_or(0.0)))
+    }
+
+    async fn get_profit_buckets(&self, interval: BucketInterval, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<ProfitBucket>> {
+        let unit = match interval {
+            BucketInterval::Hour => "hour",
+            BucketInterval::Day => "day",
+        };
+
+        let rows = sqlx::query(
+            r#"
+            WITH profits AS (
+                SELECT
+                    date_trunc($1, timestamp AT TIME ZONE 'UTC') AS bucket,
+                    pair, buy_exchange, sell_exchange,
+                    COUNT(*) AS count,
+                    SUM(estimated_profit) AS total_profit,
+                    percentile_disc(0.5) WITHIN GROUP (ORDER BY estimated_profit) AS median_profit,
+                    percentile_disc(0.95) WITHIN GROUP (ORDER BY estimated_profit) AS p95_profit,
+                    AVG(price_difference) AS average_spread,
+                    MAX(price_difference) AS max_spread
+                FROM arbitrage_opportunities
+                WHERE timestamp >= $2 AND timestamp < $3
+                GROUP BY 1, 2, 3, 4
+            ),
+            durations AS (
+                SELECT
+                    date_trunc($1, first_seen_at AT TIME ZONE 'UTC') AS bucket,
+                    pair, buy_exchange, sell_exchange,
+                    AVG(EXTRACT(EPOCH FROM (last_seen_at - first_seen_at)))::DOUBLE PRECISION AS mean_duration_seconds
+                FROM opportunity_lifecycles
+                WHERE first_seen_at >= $2 AND first_seen_at < $3
+                GROUP BY 1, 2, 3, 4
+            )
+            SELECT
+                profits.bucket AT TIME ZONE 'UTC' AS bucket_start,
+                profits.pair, profits.buy_exchange, profits.sell_exchange,
+                profits.count, profits.total_profit, profits.median_profit, profits.p95_profit,
+                profits.average_spread, profits.max_spread,
+                durations.mean_duration_seconds
+            FROM profits
+            LEFT JOIN durations USING (bucket, pair, buy_exchange, sell_exchange)
+            ORDER BY bucket_start, pair, buy_exchange, sell_exchange
+            "#
+        )
+        .bind(unit)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await
+        .with_context(|| "Failed to fetch profit buckets")?;
+
+        Ok(rows.iter().map(|row| ProfitBucket {
+            bucket_start: row.get("bucket_start"),
+            pair: row.get("pair"),
+            buy_exchange: row.get("buy_exchange"),
+            sell_exchange: row.get("sell_exchange"),
+            count: row.get("count"),
+            total_profit: row.get("total_profit"),
+            median_profit: row.get("median_profit"),
+            p95_profit: row.get("p95_profit"),
+            average_spread: row.get("average_spread"),
+            max_spread: row.get("max_spread"),
+            mean_duration_seconds: row.get("mean_duration_seconds"),
+        }).collect())
+    }
+
+    async fn get_venue_leaderboard(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<VenueStanding>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                pair, buy_exchange, sell_exchange,
+                COUNT(*) AS wins,
+                (COUNT(*)::DOUBLE PRECISION / SUM(COUNT(*)) OVER (PARTITION BY pair)::DOUBLE PRECISION) AS win_share,
+                SUM(estimated_profit) AS total_profit,
+                AVG(estimated_profit) AS average_profit,
+                MAX(estimated_profit) AS best_profit
+            FROM arbitrage_opportunities
+            WHERE timestamp >= $1 AND timestamp < $2
+            GROUP BY pair, buy_exchange, sell_exchange
+            ORDER BY wins DESC, total_profit DESC
+            "#
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await
+        .with_context(|| "Failed to fetch venue leaderboard")?;
+
+        Ok(rows.iter().map(|row| VenueStanding {
+            pair: row.get("pair"),
+            buy_exchange: row.get("buy_exchange"),
+            sell_exchange: row.get("sell_exchange"),
+            wins: row.get("wins"),
+            win_share: row.get("win_share"),
+            total_profit: row.get("total_profit"),
+            average_profit: row.get("average_profit"),
+            best_profit: row.get("best_profit"),
+        }).collect())
+    }
+
+    async fn record_control_action(&self, action: &ControlAction) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO control_audit (timestamp, actor, action, previous_value, new_value)
+            VALUES ($1, $2, $3, $4, $5)
+            "#
+        )
+        .bind(action.timestamp)
+        .bind(&action.actor)
+        .bind(&action.action)
+        .bind(&action.previous_value)
+        .bind(&action.new_value)
+        .execute(&self.pool)
+        .await
+        .with_context(|| "Failed to record control action")?;
+
+        Ok(())
+    }
+
+    async fn get_recent_control_actions(&self, limit: i64) -> Result<Vec<ControlAction>> {
+        let rows = sqlx::query(
+            "SELECT * FROM control_audit ORDER BY timestamp DESC, id DESC LIMIT $1"
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .with_context(|| "Failed to fetch control audit")?;
+
+        Ok(rows.iter().map(|row| ControlAction {
+            timestamp: row.get("timestamp"),
+            actor: row.get("actor"),
+            action: row.get("action"),
+            previous_value: row.get("previous_value"),
+            new_value: row.get("new_value"),
+        }).collect())
+    }
+
+    async fn store_price_snapshots(&self, snapshots: &[PriceSnapshot]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        for chunk in snapshots.chunks(1000) {
+            let mut query = QueryBuilder::<Postgres>::new(
+                "INSERT INTO price_snapshots (check_id, timestamp, block_number, pair, venue, amount_in, amount_out, price, latency_ms) "
+            );
+            query.push_values(chunk, |mut row, snapshot| {
+                row.push_bind(&snapshot.check_id)
+                    .push_bind(snapshot.timestamp)
+                    .push_bind(snapshot.block_number as i64)
+                    .push_bind(&snapshot.pair)
+                    .push_bind(&snapshot.venue)
+                    .push_bind(&snapshot.amount_in)
+                    .push_unseparated("::NUMERIC")
+                    .push_bind(&snapshot.amount_out)
+                    .push_unseparated("::NUMERIC")
+                    .push_bind(snapshot.price)
+                    .push_bind(snapshot.latency_ms);
+            });
+            query.build()
+                .execute(&mut *tx)
+                .await
+                .with_context(|| "Failed to store price snapshots")?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn get_recent_price_snapshots(&self, limit: i64) -> Result<Vec<PriceSnapshot>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT check_id, timestamp, block_number, pair, venue,
+                   amount_in::TEXT AS amount_in, amount_out::TEXT AS amount_out, price, latency_ms
+            FROM price_snapshots
+            ORDER BY timestamp DESC, id DESC
+            LIMIT $1
+            "#
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .with_context(|| "Failed to fetch price snapshots")?;
+
+        Ok(rows.iter().map(Self::row_to_snapshot).collect())
+    }
+
+    async fn scan_price_snapshots(&self, from: DateTime<Utc>, to: DateTime<Utc>, after_id: i64, limit: i64) -> Result<Vec<(i64, PriceSnapshot)>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, check_id, timestamp, block_number, pair, venue,
+                   amount_in::TEXT AS amount_in, amount_out::TEXT AS amount_out, price, latency_ms
+            FROM price_snapshots
+            WHERE timestamp >= $1 AND timestamp <= $2 AND id > $3
+            ORDER BY id
+            LIMIT $4
+            "#
+        )
+        .bind(from)
+        .bind(to)
+        .bind(after_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .with_context(|| "Failed to scan price snapshots")?;
+
+        Ok(rows.iter().map(|row| (row.get("id"), Self::row_to_snapshot(row))).collect())
+    }
+
+    async fn prune_price_snapshots(&self, before: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM price_snapshots WHERE timestamp < $1")
+            .bind(before)
+            .execute(&self.pool)
+            .await
+            .with_context(|| "Failed to prune price snapshots")?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn downsample_price_snapshots(&self, before: DateTime<Utc>, bucket_seconds: i64) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM price_snapshots
+            WHERE timestamp < $1
+              AND id NOT IN (
+                SELECT MIN(id) FROM price_snapshots
+                WHERE timestamp < $1
+                GROUP BY pair, venue, FLOOR(EXTRACT(EPOCH FROM timestamp) / $2)
+              )
+            "#
+        )
+        .bind(before)
+        .bind(bucket_seconds as f64)
+        .execute(&self.pool)
+        .await
+        .with_context(|| "Failed to downsample price snapshots")?;
+
+        Ok(result.rows_affected())
+    }
+}
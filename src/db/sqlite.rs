@@ -0,0 +1,787 @@
+use anyhow::{Result, Context};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{Executor, QueryBuilder, Sqlite, SqlitePool, Row, sqlite::SqliteConnectOptions};
+use std::str::FromStr;
+use tracing::{info, debug, error};
+
+use crate::arbitrage::{ArbitrageOpportunity, PredictedOpportunity};
+use crate::config::redact_url;
+use crate::control::ControlAction;
+use crate::lifecycle::OpportunityLifecycle;
+use crate::snapshots::PriceSnapshot;
+use super::migrations::{self, MigrationStatus, SQLITE_MIGRATIONS};
+use super::{BucketInterval, OpportunityCursor, ProfitBucket, VenueStanding, OpportunityPage, OpportunityQuery, SortOrder, Store};
+
+#[derive(Clone)]
+pub struct SqliteStore {
+    pool: SqlitePool,
+}
+
+impl SqliteStore {
+    async fn ensure_version_table(&self) -> Result<()> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS schema_version (
+                version INTEGER PRIMARY KEY,
+                description TEXT NOT NULL,
+                applied_at TEXT NOT NULL
+            )
+            "#
+        )
+        .execute(&self.pool)
+        .await
+        .with_context(|| "Failed to create schema_version table")?;
+        
+        Ok(())
+    }
+    
+    /// Connects without touching the schema; call `migrate` to bring it up to date.
+    pub async fn connect(database_url: &str) -> Result<Self> {
+        info!("Initializing database connection: {}", redact_url(database_url));
+        
+        let options = SqliteConnectOptions::from_str(database_url)?
+            .create_if_missing(true);
+        
+        let pool = SqlitePool::connect_with(options).await
+            .with_context(|| format!("Failed to connect to database: {}", redact_url(database_url)))?;
+        
+        Ok(Self { pool })
+    }
+
+    fn row_to_opportunity(row: &sqlx::sqlite::SqliteRow) -> Result<ArbitrageOpportunity> {
+        let timestamp_str: String = row.get("timestamp");
+        let timestamp = timestamp_str.parse()
+            .with_context(|| format!("Failed to parse timestamp: {}", timestamp_str))?;
+        
+        Ok(ArbitrageOpportunity {
+            id: row.get("id"),
+            timestamp,
+            pair: row.get("pair"),
+            block_number: row.get::<Option<i64>, _>("block_number").map(|b| b as u64),
+            buy_exchange: row.get("buy_exchange"),
+            sell_exchange: row.get("sell_exchange"),
+            buy_price: row.get("buy_price"),
+            sell_price: row.get("sell_price"),
+            estimated_profit: row.get("estimated_profit"),
+            price_difference: row.get("price_difference"),
+            gas_cost_estimate: row.get("gas_cost_estimate"),
+            profit_percentage: row.get("profit_percentage"),
+        })
+    }
+
+    /// Appends the WHERE clause for `query`, optionally restricted to rows
+    /// after its cursor.
+    fn push_opportunity_filters(builder: &mut QueryBuilder<'_, Sqlite>, query: &OpportunityQuery, after_cursor: bool) {
+        builder.push(" WHERE 1 = 1");
+        if let Some(from) = query.from {
+            builder.push(" AND timestamp >= ").push_bind(from.to_rfc3339());
+        }
+        if let Some(to) = query.to {
+            builder.push(" AND timestamp <= ").push_bind(to.to_rfc3339());
+        }
+        if let Some(pair) = &query.pair {
+            builder.push(" AND pair = ").push_bind(pair.clone());
+        }
+        if let Some(buy_exchange) = &query.buy_exchange {
+            builder.push(" AND buy_exchange = ").push_bind(buy_exchange.clone());
+        }
+        if let Some(sell_exchange) = &query.sell_exchange {
+            builder.push(" AND sell_exchange = ").push_bind(sell_exchange.clone());
+        }
+        if let Some(min_profit) = query.min_profit {
+            builder.push(" AND estimated_profit >= ").push_bind(min_profit);
+        }
+
+        if let (true, Some(cursor)) = (after_cursor, &query.cursor) {
+            let column = query.sort.column();
+            let op = match query.order {
+                SortOrder::Asc => ">",
+                SortOrder::Desc => "<",
+            };
+            builder.push(format!(" AND ({} {} ", column, op));
+            match cursor {
+                OpportunityCursor::Timestamp(timestamp, _) => builder.push_bind(timestamp.to_rfc3339()),
+                OpportunityCursor::Profit(profit, _) => builder.push_bind(*profit),
+            };
+            builder.push(format!(" OR ({} = ", column));
+            match cursor {
+                OpportunityCursor::Timestamp(timestamp, _) => builder.push_bind(timestamp.to_rfc3339()),
+                OpportunityCursor::Profit(profit, _) => builder.push_bind(*profit),
+            };
+            builder.push(format!(" AND id {} ", op))
+                .push_bind(cursor.id().to_string())
+                .push("))");
+        }
+    }
+
+    async fn select_opportunities(&self, query: &OpportunityQuery, limit: i64) -> Result<Vec<ArbitrageOpportunity>> {
+        let direction = match query.order {
+            SortOrder::Asc => "ASC",
+            SortOrder::Desc => "DESC",
+        };
+        let mut select = QueryBuilder::<Sqlite>::new("SELECT * FROM arbitrage_opportunities");
+        Self::push_opportunity_filters(&mut select, query, true);
+        select.push(format!(" ORDER BY {} {}, id {} LIMIT ", query.sort.column(), direction, direction))
+            .push_bind(limit);
+
+        let rows = select.build()
+            .fetch_all(&self.pool)
+            .await
+            .with_context(|| "Failed to query opportunities")?;
+        rows.iter().map(Self::row_to_opportunity).collect()
+    }
+
+    fn row_to_snapshot(row: &sqlx::sqlite::SqliteRow) -> Result<PriceSnapshot> {
+        let timestamp_str: String = row.get("timestamp");
+        let timestamp = timestamp_str.parse()
+            .with_context(|| format!("Failed to parse timestamp: {}", timestamp_str))?;
+        
+        Ok(PriceSnapshot {
+            check_id: row.get("check_id"),
+            timestamp,
+            block_number: row.get::<i64, _>("block_number") as u64,
+            pair: row.get("pair"),
+            venue: row.get("venue"),
+            amount_in: row.get("amount_in"),
+            amount_out: row.get("amount_out"),
+            price: row.get("price"),
+            latency_ms: row.get("latency_ms"),
+        })
+    }
+
+    fn row_to_lifecycle(row: &sqlx::sqlite::SqliteRow) -> Result<OpportunityLifecycle> {
+        let parse_time = |column: &str| -> Result<chrono::DateTime<chrono::Utc>> {
+            let value: String = row.get(column);
+            value.parse()
+                .with_context(|| format!("Failed to parse {}: {}", column, value))
+        };
+        let closed_at: Option<String> = row.get("closed_at");
+        
+        Ok(OpportunityLifecycle {
+            id: row.get("id"),
+            pair: row.get("pair"),
+            buy_exchange: row.get("buy_exchange"),
+            sell_exchange: row.get("sell_exchange"),
+            first_seen_block: row.get::<i64, _>("first_seen_block") as u64,
+            last_seen_block: row.get::<i64, _>("last_seen_block") as u64,
+            first_seen_at: parse_time("first_seen_at")?,
+            last_seen_at: parse_time("last_seen_at")?,
+            peak_profit: row.get("peak_profit"),
+            peak_profit_percentage: row.get("peak_profit_percentage"),
+            observations: row.get::<i64, _>("observations") as u64,
+            closed_at: match closed_at {
+                Some(value) => Some(value.parse()
+                    .with_context(|| format!("Failed to parse closed_at: {}", value))?),
+                None => None,
+            },
+        })
+    }
+}
+
+#[async_trait]
+impl Store for SqliteStore {
+    async fn migrate(&self) -> Result<Vec<i64>> {
+        let current = self.schema_version().await?;
+        let mut applied = Vec::new();
+        
+        for migration in SQLITE_MIGRATIONS.iter().filter(|m| m.version > current) {
+            info!("Applying migration {}: {}", migration.version, migration.description);
+            
+            let mut tx = self.pool.begin().await?;
+            
+            tx.execute(migration.sql).await
+                .with_context(|| format!("Migration {} ({}) failed", migration.version, migration.description))?;
+            
+            sqlx::query("INSERT INTO schema_version (version, description, applied_at) VALUES (?, ?, ?)")
+                .bind(migration.version)
+                .bind(migration.description)
+                .bind(chrono::Utc::now().to_rfc3339())
+                .execute(&mut *tx)
+                .await
+                .with_context(|| format!("Failed to record migration {}", migration.version))?;
+            
+            tx.commit().await?;
+            applied.push(migration.version);
+        }
+        
+        debug!("Database schema at version {}", migrations::latest_version(SQLITE_MIGRATIONS));
+        
+        Ok(applied)
+    }
+    
+    async fn migration_status(&self) -> Result<Vec<MigrationStatus>> {
+        self.ensure_version_table().await?;
+        
+        let applied = sqlx::query_as::<_, (i64, String)>("SELECT version, applied_at FROM schema_version")
+            .fetch_all(&self.pool)
+            .await
+            .with_context(|| "Failed to read applied migrations")?;
+        
+        Ok(migrations::status(SQLITE_MIGRATIONS, &applied))
+    }
+    
+    async fn schema_version(&self) -> Result<i64> {
+        self.ensure_version_table().await?;
+        
+        let version = sqlx::query_as::<_, (Option<i64>,)>("SELECT MAX(version) FROM schema_version")
+            .fetch_one(&self.pool)
+            .await
+            .with_context(|| "Failed to read schema version")?;
+        
+        Ok(version.0.unwrap_or(0))
+    }
+    
+    async fn store_opportunity(&self, opportunity: &ArbitrageOpportunity) -> Result<()> {
+        info!("Storing opportunity with ID: {}", opportunity.id);
+        
+        sqlx::query(
+            r#"
+            INSERT INTO arbitrage_opportunities 
+            (id, timestamp, pair, block_number, buy_exchange, sell_exchange, buy_price, sell_price, 
+             estimated_profit, price_difference, gas_cost_estimate, profit_percentage)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#
+        )
+        .bind(&opportunity.id)
+        .bind(opportunity.timestamp.to_rfc3339())
+        .bind(&opportunity.pair)
+        .bind(opportunity.block_number.map(|b| b as i64))
+        .bind(&opportunity.buy_exchange)
+        .bind(&opportunity.sell_exchange)
+        .bind(opportunity.buy_price)
+        .bind(opportunity.sell_price)
+        .bind(opportunity.estimated_profit)
+        .bind(opportunity.price_difference)
+        .bind(opportunity.gas_cost_estimate)
+        .bind(opportunity.profit_percentage)
+        .execute(&self.pool)
+        .await
+        .with_context(|| "Failed to store arbitrage opportunity")?;
+        
+        info!("Opportunity stored in database with ID: {}", opportunity.id);
+        
+        Ok(())
+    }
+
+    async fn query_opportunities(&self, query: &OpportunityQuery) -> Result<OpportunityPage> {
+        let mut count = QueryBuilder::<Sqlite>::new("SELECT COUNT(*) FROM arbitrage_opportunities");
+        Self::push_opportunity_filters(&mut count, query, false);
+        let total: i64 = count.build_query_scalar()
+            .fetch_one(&self.pool)
+            .await
+            .with_context(|| "Failed to count opportunities")?;
+
+        let opportunities = self.select_opportunities(query, query.limit + 1).await?;
+
+        Ok(OpportunityPage::from_rows(opportunities, total, query))
+    }
+
+    async fn scan_opportunities(&self, query: &OpportunityQuery) -> Result<Vec<ArbitrageOpportunity>> {
+        self.select_opportunities(query, query.limit).await
+    }
+
+    async fn get_stats(&self) -> Result<(i64, f64, f64)> {
+        info!("Querying database stats");
+        
+        let row = sqlx::query_as::<_, (i64, Option<f64>, Option<f64>)>(
+            r#"
+            SELECT 
+                COUNT(*) as total,
+                AVG(estimated_profit) as avg_profit,
+                MAX(estimated_profit) as max_profit
+            FROM arbitrage_opportunities
+            "#
+        )
+        .fetch_one(&self.pool)
+        .await
+        .with_context(|| "Failed to fetch stats")?;
+
+        let stats = (row.0, row.1.unwrap_or(0.0), row.2.unwrap_or(0.0));
+        info!("Stats: total={}, avg={:.2}, max={:.2}", stats.0, stats.1, stats.2);
+        
+        Ok(stats)
+    }
+
+    async fn store_prediction(&self, prediction: &PredictedOpportunity) -> Result<()> {
+        let opportunity = &prediction.opportunity;
+        debug!("Storing prediction with ID: {}", opportunity.id);
+        
+        sqlx::query(
+            r#"
+            INSERT INTO predicted_opportunities 
+            (id, timestamp, pair, trigger_tx, trigger_venue, target_block, buy_exchange, sell_exchange,
+             buy_price, sell_price, estimated_profit, price_difference, gas_cost_estimate, profit_percentage)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#
+        )
+        .bind(&opportunity.id)
+        .bind(opportunity.timestamp.to_rfc3339())
+        .bind(&opportunity.pair)
+        .bind(&prediction.trigger_tx)
+        .bind(&prediction.trigger_venue)
+        .bind(prediction.target_block as i64)
+        .bind(&opportunity.buy_exchange)
+        .bind(&opportunity.sell_exchange)
+        .bind(opportunity.buy_price)
+        .bind(opportunity.sell_price)
+        .bind(opportunity.estimated_profit)
+        .bind(opportunity.price_difference)
+        .bind(opportunity.gas_cost_estimate)
+        .bind(opportunity.profit_percentage)
+        .execute(&self.pool)
+        .await
+        .with_context(|| "Failed to store predicted opportunity")?;
+        
+        Ok(())
+    }
+
+    async fn resolve_prediction(&self, id: &str, confirmed: Option<bool>) -> Result<()> {
+        sqlx::query(
+            "UPDATE predicted_opportunities SET confirmed = ?, resolved_at = ? WHERE id = ?"
+        )
+        .bind(confirmed)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .with_context(|| format!("Failed to resolve prediction {}", id))?;
+        
+        Ok(())
+    }
+
+    async fn get_recent_predictions(&self, limit: i64) -> Result<Vec<PredictedOpportunity>> {
+        let rows = sqlx::query(
+            "SELECT *, target_block AS block_number FROM predicted_opportunities ORDER BY created_at DESC LIMIT ?1"
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .with_context(|| "Failed to fetch recent predictions")?;
+        
+        let mut predictions = Vec::new();
+        
+        for row in rows {
+            match Self::row_to_opportunity(&row) {
+                Ok(opportunity) => predictions.push(PredictedOpportunity {
+                    opportunity,
+                    trigger_tx: row.get("trigger_tx"),
+                    trigger_venue: row.get("trigger_venue"),
+                    target_block: row.get::<i64, _>("target_block") as u64,
+                    confirmed: row.get("confirmed"),
+                    expired: row.get::<Option<String>, _>("resolved_at").is_some()
+                        && row.get::<Option<bool>, _>("confirmed").is_none(),
+                }),
+                Err(e) => {
+                    error!("Failed to parse prediction row: {}", e);
+                    continue;
+                }
+            }
+        }
+        
+        Ok(predictions)
+    }
+
+    async fn get_prediction_stats(&self) -> Result<(i64, i64, i64, i64)> {
+        let row = sqlx::query_as::<_, (i64, Option<i64>, Option<i64>, Option<i64>)>(
+            r#"
+            SELECT 
+                COUNT(*) as total,
+                SUM(CASE WHEN confirmed = 1 THEN 1 ELSE 0 END) as confirmed,
+                SUM(CASE WHEN confirmed = 0 THEN 1 ELSE 0 END) as refuted,
+                SUM(CASE WHEN confirmed IS NULL AND resolved_at IS NOT NULL THEN 1 ELSE 0 END) as expired
+            FROM predicted_opportunities
+            "#
+        )
+        .fetch_one(&self.pool)
+        .await
+        .with_context(|| "Failed to fetch prediction stats")?;
+        
+        Ok((row.0, row.1.unwrap_or(0), row.2.unwrap_or(0), row.3.unwrap_or(0)))
+    }
+
+    async fn upsert_lifecycle(&self, lifecycle: &OpportunityLifecycle) -> Result<()> {
+        debug!("Storing lifecycle with ID: {}", lifecycle.id);
+        
+        sqlx::query(
+            r#"
+            INSERT INTO opportunity_lifecycles 
+            (id, pair, buy_exchange, sell_exchange, first_seen_block, last_seen_block,
+             first_seen_at, last_seen_at, peak_profit, peak_profit_percentage, observations, closed_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(id) DO UPDATE SET
+                last_seen_block = excluded.last_seen_block,
+                last_seen_at = excluded.last_seen_at,
+                peak_profit = excluded.peak_profit,
+                peak_profit_percentage = excluded.peak_profit_percentage,
+                observations = excluded.observations,
+                closed_at = excluded.closed_at
+            "#
+        )
+        .bind(&lifecycle.id)
+        .bind(&lifecycle.pair)
+        .bind(&lifecycle.buy_exchange)
+        .bind(&lifecycle.sell_exchange)
+        .bind(lifecycle.first_seen_block as i64)
+        .bind(lifecycle.last_seen_block as i64)
+        .bind(lifecycle.first_seen_at.to_rfc3339())
+        .bind(lifecycle.last_seen_at.to_rfc3339())
+        .bind(lifecycle.peak_profit)
+        .bind(lifecycle.peak_profit_percentage)
+        .bind(lifecycle.observations as i64)
+        .bind(lifecycle.closed_at.map(|t| t.to_rfc3339()))
+        .execute(&self.pool)
+        .await
+        .with_context(|| "Failed to store opportunity lifecycle")?;
+        
+        Ok(())
+    }
+
+    async fn get_open_lifecycles(&self) -> Result<Vec<OpportunityLifecycle>> {
+        let rows = sqlx::query(
+            "SELECT * FROM opportunity_lifecycles WHERE closed_at IS NULL"
+        )
+        .fetch_all(&self.pool)
+        .await
+        .with_context(|| "Failed to fetch open lifecycles")?;
+        
+        rows.iter().map(Self::row_to_lifecycle).collect()
+    }
+
+    async fn get_recent_lifecycles(&self, limit: i64) -> Result<Vec<OpportunityLifecycle>> {
+        let rows = sqlx::query(
+            "SELECT * FROM opportunity_lifecycles ORDER BY last_seen_at DESC LIMIT ?1"
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .with_context(|| "Failed to fetch recent lifecycles")?;
+        
+        let mut lifecycles = Vec::new();
+        
+        for row in rows {
+            match Self::row_to_lifecycle(&row) {
+                Ok(lifecycle) => lifecycles.push(lifecycle),
+                Err(e) => {
+                    error!("Failed to parse lifecycle row: {}", e);
+                    continue;
+                }
+            }
+        }
+        
+        Ok(lifecycles)
+    }
+
+    async fn get_lifecycle_stats(&self) -> Result<(i64, i64, f64, f64)> {
+        let row = sqlx::query_as::<_, (i64, Option<i64>, Option<f64>, Option<f64>)>(
+            r#"
+            SELECT 
+                COUNT(*) as total,
+                SUM(CASE WHEN closed_at IS NULL THEN 1 ELSE 0 END) as open,
+                AVG((julianday(last_seen_at) - julianday(first_seen_at)) * 86400.0) as avg_duration,
+                AVG(observations) as avg_observations
+            FROM opportunity_lifecycles
+            "#
+        )
+        .fetch_one(&self.pool)
+        .await
+        .with_context(|| "Failed to fetch lifecycle stats")?;
+        
+        Ok((row.0, row.1.unwrap_or(0), row.2.unwrap_or(0.0), row.3.unwrap_or(0.0)))
+    }
+    
+    async fn get_profit_buckets(&self, interval: BucketInterval, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<ProfitBucket>> {
+        let bucket_format = match interval {
+            BucketInterval::Hour => "%Y-%m-%dT%H:00:00Z",
+            BucketInterval::Day => "%Y-%m-%dT00:00:00Z",
+        };
+        
+        // SQLite has no percentile aggregate, so rank rows within each bucket
+        // and pick the nearest-rank median and p95 rows
+        let rows = sqlx::query(
+            r#"
+            WITH ranked AS (
+                SELECT
+                    strftime(?1, timestamp) AS bucket_start,
+                    pair, buy_exchange, sell_exchange, estimated_profit, price_difference,
+                    ROW_NUMBER() OVER bucket AS position,
+                    COUNT(*) OVER bucket_all AS bucket_size
+                FROM arbitrage_opportunities
+                WHERE timestamp >= ?2 AND timestamp < ?3
+                WINDOW bucket_all AS (PARTITION BY strftime(?1, timestamp), pair, buy_exchange, sell_exchange),
+                       bucket AS (bucket_all ORDER BY estimated_profit)
+            ),
+            profits AS (
+                SELECT
+                    bucket_start, pair, buy_exchange, sell_exchange,
+                    COUNT(*) AS count,
+                    SUM(estimated_profit) AS total_profit,
+                    MAX(CASE WHEN position = (bucket_size + 1) / 2 THEN estimated_profit END) AS median_profit,
+                    MAX(CASE WHEN position = (95 * bucket_size + 99) / 100 THEN estimated_profit END) AS p95_profit,
+                    AVG(price_difference) AS average_spread,
+                    MAX(price_difference) AS max_spread
+                FROM ranked
+                GROUP BY bucket_start, pair, buy_exchange, sell_exchange
+            ),
+            durations AS (
+                SELECT
+                    strftime(?1, first_seen_at) AS bucket_start,
+                    pair, buy_exchange, sell_exchange,
+                    AVG((julianday(last_seen_at) - julianday(first_seen_at)) * 86400.0) AS mean_duration_seconds
+                FROM opportunity_lifecycles
+                WHERE first_seen_at >= ?2 AND first_seen_at < ?3
+                GROUP BY 1, 2, 3, 4
+            )
+            SELECT profits.*, durations.mean_duration_seconds
+            FROM profits
+            LEFT JOIN durations USING (bucket_start, pair, buy_exchange, sell_exchange)
+            ORDER BY bucket_start, pair, buy_exchange, sell_exchange
+            "#
+        )
+        .bind(bucket_format)
+        .bind(from.to_rfc3339())
+        .bind(to.to_rfc3339())
+        .fetch_all(&self.pool)
+        .await
+        .with_context(|| "Failed to fetch profit buckets")?;
+        
+        rows.iter().map(|row| {
+            let bucket_str: String = row.get("bucket_start");
+            Ok(ProfitBucket {
+                bucket_start: bucket_str.parse()
+                    .with_context(|| format!("Failed to parse bucket: {}", bucket_str))?,
+                pair: row.get("pair"),
+                buy_exchange: row.get("buy_exchange"),
+                sell_exchange: row.get("sell_exchange"),
+                count: row.get("count"),
+                total_profit: row.get("total_profit"),
+                median_profit: row.get("median_profit"),
+                p95_profit: row.get("p95_profit"),
+                average_spread: row.get("average_spread"),
+                max_spread: row.get("max_spread"),
+                mean_duration_seconds: row.get("mean_duration_seconds"),
+            })
+        }).collect()
+    }
+    
+    async fn get_venue_leaderboard(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<VenueStanding>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                pair, buy_exchange, sell_exchange,
+                COUNT(*) AS wins,
+                CAST(COUNT(*) AS REAL) / SUM(COUNT(*)) OVER (PARTITION BY pair) AS win_share,
+                SUM(estimated_profit) AS total_profit,
+                AVG(estimated_profit) AS average_profit,
+                MAX(estimated_profit) AS best_profit
+            FROM arbitrage_opportunities
+            WHERE timestamp >= ?1 AND timestamp < ?2
+            GROUP BY pair, buy_exchange, sell_exchange
+            ORDER BY wins DESC, total_profit DESC
+            "#
+        )
+        .bind(from.to_rfc3339())
+        .bind(to.to_rfc3339())
+        .fetch_all(&self.pool)
+        .await
+        .with_context(|| "Failed to fetch venue leaderboard")?;
+        
+        Ok(rows.iter().map(|row| VenueStanding {
+            pair: row.get("pair"),
+            buy_exchange: row.get("buy_exchange"),
+            sell_exchange: row.get("sell_exchange"),
+            wins: row.get("wins"),
+            win_share: row.get("win_share"),
+            total_profit: row.get("total_profit"),
+            average_profit: row.get("average_profit"),
+            best_profit: row.get("best_profit"),
+        }).collect())
+    }
+    
+    async fn record_control_action(&self, action: &ControlAction) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO control_audit (timestamp, actor, action, previous_value, new_value)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            "#
+        )
+        .bind(action.timestamp.to_rfc3339())
+        .bind(&action.actor)
+        .bind(&action.action)
+        .bind(&action.previous_value)
+        .bind(&action.new_value)
+        .execute(&self.pool)
+        .await
+        .with_context(|| "Failed to record control action")?;
+        
+        Ok(())
+    }
+    
+    async fn get_recent_control_actions(&self, limit: i64) -> Result<Vec<ControlAction>> {
+        let rows = sqlx::query(
+            "SELECT * FROM control_audit ORDER BY timestamp DESC, id DESC LIMIT ?1"
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .with_context(|| "Failed to fetch control audit")?;
+        
+        rows.iter().map(|row| {
+            let timestamp_str: String = row.get("timestamp");
+            Ok(ControlAction {
+                timestamp: timestamp_str.parse()
+                    .with_context(|| format!("Failed to parse timestamp: {}", timestamp_str))?,
+                actor: row.get("actor"),
+                action: row.get("action"),
+                previous_value: row.get("previous_value"),
+                new_value: row.get("new_value"),
+            })
+        }).collect()
+    }
+    
+    async fn store_price_snapshots(&self, snapshots: &[PriceSnapshot]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        
+        // Stay well below SQLite's bound parameter limit
+        for chunk in snapshots.chunks(100) {
+            let mut query = QueryBuilder::<Sqlite>::new(
+                "INSERT INTO price_snapshots (check_id, timestamp, block_number, pair, venue, amount_in, amount_out, price, latency_ms) "
+            );
+            query.push_values(chunk, |mut row, snapshot| {
+                row.push_bind(&snapshot.check_id)
+                    .push_bind(snapshot.timestamp.to_rfc3339())
+                    .push_bind(snapshot.block_number as i64)
+                    .push_bind(&snapshot.pair)
+                    .push_bind(&snapshot.venue)
+                    .push_bind(&snapshot.amount_in)
+                    .push_bind(&snapshot.amount_out)
+                    .push_bind(snapshot.price)
+                    .push_bind(snapshot.latency_ms);
+            });
+            query.build()
+                .execute(&mut *tx)
+                .await
+                .with_context(|| "Failed to store price snapshots")?;
+        }
+        
+        tx.commit().await?;
+        
+        Ok(())
+    }
+    
+    async fn get_recent_price_snapshots(&self, limit: i64) -> Result<Vec<PriceSnapshot>> {
+        let rows = sqlx::query(
+            "SELECT * FROM price_snapshots ORDER BY timestamp DESC, id DESC LIMIT ?1"
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .with_context(|| "Failed to fetch price snapshots")?;
+        
+        rows.iter().map(Self::row_to_snapshot).collect()
+    }
+    
+    async fn scan_price_snapshots(&self, from: DateTime<Utc>, to: DateTime<Utc>, after_id: i64, limit: i64) -> Result<Vec<(i64, PriceSnapshot)>> {
+        let rows = sqlx::query(
+            "SELECT * FROM price_snapshots WHERE timestamp >= ?1 AND timestamp <= ?2 AND id > ?3 ORDER BY id LIMIT ?4"
+        )
+        .bind(from.to_rfc3339())
+        .bind(to.to_rfc3339())
+        .bind(after_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .with_context(|| "Failed to scan price snapshots")?;
+        
+        rows.iter().map(|row| Ok((row.get("id"), Self::row_to_snapshot(row)?))).collect()
+    }
+    
+    async fn prune_price_snapshots(&self, before: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM price_snapshots WHERE timestamp < ?1")
+            .bind(before.to_rfc3339())
+            .execute(&self.pool)
+            .await
+            .with_context(|| "Failed to prune price snapshots")?;
+        
+        Ok(result.rows_affected())
+    }
+    
+    async fn downsample_price_snapshots(&self, before: DateTime<Utc>, bucket_seconds: i64) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM price_snapshots
+            WHERE timestamp < ?1
+              AND id NOT IN (
+                SELECT MIN(id) FROM price_snapshots
+                WHERE timestamp < ?1
+                GROUP BY pair, venue, CAST(strftime('%s', timestamp) AS INTEGER) / ?2
+              )
+            "#
+        )
+        .bind(before.to_rfc3339())
+        .bind(bucket_seconds)
+        .execute(&self.pool)
+        .await
+        .with_context(|| "Failed to downsample price snapshots")?;
+        
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arbitrage::MONITORED_PAIR;
+    use crate::db::tests::{opportunity, temp_sqlite_url};
+    use chrono::Utc;
+
+    #[tokio::test]
+    async fn test_upgrades_pre_migration_database() {
+        let url = temp_sqlite_url();
+
+        // Schema and data as written by releases without versioned migrations
+        let legacy = SqliteStore::connect(&url).await.unwrap();
+        sqlx::query(include_str!("../../migrations/sqlite/0001_initial.sql"))
+            .execute(&legacy.pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            r#"
+            INSERT INTO arbitrage_opportunities 
+            (id, timestamp, buy_exchange, sell_exchange, buy_price, sell_price, 
+             estimated_profit, price_difference, gas_cost_estimate, profit_percentage)
+            VALUES ('legacy', ?, 'QuickSwap', 'SushiSwap', 3000.0, 3010.0, 9.0, 10.0, 1.0, 0.3)
+            "#
+        )
+        .bind(Utc::now().to_rfc3339())
+        .execute(&legacy.pool)
+        .await
+        .unwrap();
+        legacy.pool.close().await;
+
+        let db = SqliteStore::connect(&url).await.unwrap();
+        assert_eq!(db.migrate().await.unwrap(), vec![1, 2, 3, 4, 5, 6, 7]);
+
+        let status = db.migration_status().await.unwrap();
+        assert!(status.iter().all(|m| m.applied_at.is_some()));
+        assert_eq!(db.schema_version().await.unwrap(), migrations::latest_version(SQLITE_MIGRATIONS));
+
+        let opportunities = db.query_opportunities(&OpportunityQuery::default()).await.unwrap().opportunities;
+        assert_eq!(opportunities.len(), 1);
+        assert_eq!(opportunities[0].id, "legacy");
+        assert_eq!(opportunities[0].pair, MONITORED_PAIR);
+        assert_eq!(opportunities[0].block_number, None);
+
+        db.store_opportunity(&opportunity(9.0)).await.unwrap();
+        assert_eq!(db.get_stats().await.unwrap().0, 2);
+    }
+
+    #[tokio::test]
+    async fn test_status_lists_pending_migrations() {
+        let db = SqliteStore::connect(&temp_sqlite_url()).await.unwrap();
+
+        let status = db.migration_status().await.unwrap();
+        assert_eq!(status.len(), SQLITE_MIGRATIONS.len());
+        assert!(status.iter().all(|m| m.applied_at.is_none()));
+    }
+}
@@ -0,0 +1,195 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::arbitrage::ArbitrageOpportunity;
+use crate::snapshots::PriceSnapshot;
+
+/// Something the bot did that live clients may want to hear about.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BotEvent {
+    Opportunity {
+        opportunity: ArbitrageOpportunity,
+    },
+    /// Every venue quote taken during one check
+    Quotes {
+        block_number: u64,
+        pair: String,
+        quotes: Vec<PriceSnapshot>,
+    },
+    /// Sent when checks start failing or recover
+    Status {
+        healthy: bool,
+        message: Option<String>,
+        at: DateTime<Utc>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    Opportunity,
+    Quotes,
+    Status,
+}
+
+impl BotEvent {
+    pub fn kind(&self) -> EventKind {
+        match self {
+            BotEvent::Opportunity { .. } => EventKind::Opportunity,
+            BotEvent::Quotes { .. } => EventKind::Quotes,
+            BotEvent::Status { .. } => EventKind::Status,
+        }
+    }
+}
+
+impl EventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventKind::Opportunity => "opportunity",
+            EventKind::Quotes => "quotes",
+            EventKind::Status => "status",
+        }
+    }
+}
+
+impl std::str::FromStr for EventKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "opportunity" => Ok(EventKind::Opportunity),
+            "quotes" => Ok(EventKind::Quotes),
+            "status" => Ok(EventKind::Status),
+            other => anyhow::bail!("Unknown event type: {}", other),
+        }
+    }
+}
+
+/// Subscription filter sent by stream clients. Every field is optional and
+/// an empty filter receives everything.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EventFilter {
+    /// Only these event types
+    pub types: Option<Vec<EventKind>>,
+    /// Only opportunities and quotes for this pair
+    pub pair: Option<String>,
+    /// Only opportunities buying or selling on this venue, and its quotes
+    pub venue: Option<String>,
+    /// Only opportunities at least this profitable
+    pub min_profit: Option<f64>,
+}
+
+impl EventFilter {
+    pub fn matches(&self, event: &BotEvent) -> bool {
+        if let Some(types) = &self.types {
+            if !types.contains(&event.kind()) {
+                return false;
+            }
+        }
+
+        match event {
+            BotEvent::Opportunity { opportunity } => {
+                self.pair.as_ref().is_none_or(|pair| *pair == opportunity.pair)
+                    && self.venue.as_ref().is_none_or(|venue| {
+                        *venue == opportunity.buy_exchange || *venue == opportunity.sell_exchange
+                    })
+                    && self.min_profit.is_none_or(|min| opportunity.estimated_profit >= min)
+            },
+            BotEvent::Quotes { pair: event_pair, quotes, .. } => {
+                self.pair.as_ref().is_none_or(|pair| pair == event_pair)
+                    && self.venue.as_ref().is_none_or(|venue| quotes.iter().any(|q| q.venue == *venue))
+            },
+            BotEvent::Status { .. } => true,
+        }
+    }
+
+    /// Narrows a quotes event down to the venue the client asked for.
+    pub fn apply(&self, event: BotEvent) -> BotEvent {
+        match (event, &self.venue) {
+            (BotEvent::Quotes { block_number, pair, quotes }, Some(venue)) => BotEvent::Quotes {
+                block_number,
+                pair,
+                quotes: quotes.into_iter().filter(|q| q.venue == *venue).collect(),
+            },
+            (event, _) => event,
+        }
+    }
+}
+
+/// Fan-out of bot events to any number of live subscribers. Slow
+/// subscribers miss events rather than holding up the monitoring loop.
+pub struct EventBus {
+    sender: broadcast::Sender<BotEvent>,
+}
+
+impl EventBus {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    pub fn publish(&self, event: BotEvent) {
+        // An error only means nobody is listening right now
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<BotEvent> {
+        self.sender.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arbitrage::MONITORED_PAIR;
+    use crate::db::tests::opportunity;
+
+    fn quotes() -> BotEvent {
+        let snapshot = |venue: &str| PriceSnapshot {
+            check_id: "check".to_string(),
+            timestamp: Utc::now(),
+            block_number: 100,
+            pair: MONITORED_PAIR.to_string(),
+            venue: venue.to_string(),
+            amount_in: "1".to_string(),
+            amount_out: "3000".to_string(),
+            price: 3000.0,
+            latency_ms: 10.0,
+        };
+        BotEvent::Quotes {
+            block_number: 100,
+            pair: MONITORED_PAIR.to_string(),
+            quotes: vec![snapshot("QuickSwap"), snapshot("SushiSwap")],
+        }
+    }
+
+    #[test]
+    fn test_filter_matches() {
+        let opportunity = BotEvent::Opportunity { opportunity: opportunity(5.0) };
+
+        assert!(EventFilter::default().matches(&opportunity));
+        assert!(EventFilter { min_profit: Some(5.0), ..Default::default() }.matches(&opportunity));
+        assert!(!EventFilter { min_profit: Some(6.0), ..Default::default() }.matches(&opportunity));
+        assert!(!EventFilter { types: Some(vec![EventKind::Quotes]), ..Default::default() }.matches(&opportunity));
+        assert!(!EventFilter { venue: Some("Uniswap".to_string()), ..Default::default() }.matches(&opportunity));
+
+        let filter = EventFilter { venue: Some("SushiSwap".to_string()), ..Default::default() };
+        assert!(filter.matches(&quotes()));
+        match filter.apply(quotes()) {
+            BotEvent::Quotes { quotes, .. } => assert_eq!(quotes.len(), 1),
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_event_json_is_tagged() {
+        let json = serde_json::to_value(BotEvent::Status { healthy: false, message: Some("rpc down".to_string()), at: Utc::now() }).unwrap();
+        assert_eq!(json["type"], "status");
+        assert_eq!(json["healthy"], false);
+
+        let filter: EventFilter = serde_json::from_str(r#"{"types": ["opportunity"], "min_profit": 2.5}"#).unwrap();
+        assert_eq!(filter.types, Some(vec![EventKind::Opportunity]));
+    }
+}
@@ -0,0 +1,439 @@
+use anyhow::{Context, Result};
+use arrow_array::{ArrayRef, Float64Array, RecordBatch, StringArray, TimestampMicrosecondArray, UInt64Array};
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use chrono::{DateTime, Utc};
+use futures_util::Stream;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use utoipa::ToSchema;
+
+use crate::arbitrage::ArbitrageOpportunity;
+use crate::db::{OpportunityCursor, OpportunityQuery, OpportunitySort, SortOrder, Store};
+use crate::snapshots::PriceSnapshot;
+
+/// Rows fetched per database round trip, and per Parquet row group.
+const DEFAULT_BATCH_SIZE: i64 = 5_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportDataset {
+    Opportunities,
+    Snapshots,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Jsonl,
+    Parquet,
+}
+
+impl ExportDataset {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExportDataset::Opportunities => "opportunities",
+            ExportDataset::Snapshots => "snapshots",
+        }
+    }
+}
+
+impl ExportFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv",
+            ExportFormat::Jsonl => "application/x-ndjson",
+            ExportFormat::Parquet => "application/vnd.apache.parquet",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Jsonl => "jsonl",
+            ExportFormat::Parquet => "parquet",
+        }
+    }
+}
+
+/// One dataset over an inclusive time range, in one format.
+#[derive(Debug, Clone)]
+pub struct ExportRequest {
+    pub dataset: ExportDataset,
+    pub format: ExportFormat,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub batch_size: i64,
+}
+
+impl ExportRequest {
+    pub fn new(dataset: ExportDataset, format: ExportFormat, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Self> {
+        if from > to {
+            anyhow::bail!("Export range starts after it ends: {} > {}", from, to);
+        }
+        Ok(Self { dataset, format, from, to, batch_size: DEFAULT_BATCH_SIZE })
+    }
+
+    pub fn file_name(&self) -> String {
+        format!("{}-{}-{}.{}",
+                self.dataset.as_str(),
+                self.from.format("%Y%m%dT%H%M%S"),
+                self.to.format("%Y%m%dT%H%M%S"),
+                self.format.extension())
+    }
+}
+
+enum Rows {
+    Opportunities(Vec<ArbitrageOpportunity>),
+    Snapshots(Vec<PriceSnapshot>),
+}
+
+/// Walks a dataset in batches with keyset pagination, so only one batch is
+/// held in memory at a time.
+struct RowSource {
+    store: Arc<dyn Store>,
+    request: ExportRequest,
+    opportunity_cursor: Option<OpportunityCursor>,
+    snapshot_cursor: i64,
+    exhausted: bool,
+}
+
+impl RowSource {
+    fn new(store: Arc<dyn Store>, request: ExportRequest) -> Self {
+        Self { store, request, opportunity_cursor: None, snapshot_cursor: 0, exhausted: false }
+    }
+
+    async fn next_batch(&mut self) -> Result<Option<Rows>> {
+        if self.exhausted {
+            return Ok(None);
+        }
+
+        let rows = match self.request.dataset {
+            ExportDataset::Opportunities => {
+                let batch = self.store.scan_opportunities(&OpportunityQuery {
+                    from: Some(self.request.from),
+                    to: Some(self.request.to),
+                    sort: OpportunitySort::Timestamp,
+                    order: SortOrder::Asc,
+                    cursor: self.opportunity_cursor.take(),
+                    limit: self.request.batch_size,
+                    ..Default::default()
+                }).await?;
+
+                self.opportunity_cursor = batch.last()
+                    .map(|last| OpportunityCursor::Timestamp(last.timestamp, last.id.clone()));
+                Rows::Opportunities(batch)
+            },
+            ExportDataset::Snapshots => {
+                let batch = self.store.scan_price_snapshots(
+                    self.request.from,
+                    self.request.to,
+                    self.snapshot_cursor,
+                    self.request.batch_size,
+                ).await?;
+
+                if let Some((id, _)) = batch.last() {
+                    self.snapshot_cursor = *id;
+                }
+                Rows::Snapshots(batch.into_iter().map(|(_, snapshot)| snapshot).collect())
+            },
+        };
+
+        let len = match &rows {
+            Rows::Opportunities(rows) => rows.len(),
+            Rows::Snapshots(rows) => rows.len(),
+        };
+        self.exhausted = (len as i64) < self.request.batch_size;
+
+        Ok((len > 0).then_some(rows))
+    }
+}
+
+/// In-memory sink the Parquet writer flushes row groups into, drained after
+/// every batch.
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuffer {
+    fn take(&self) -> Vec<u8> {
+        std::mem::take(&mut *self.0.lock().unwrap())
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Turns batches of rows into output bytes incrementally.
+enum Encoder {
+    Csv { header_written: bool },
+    Jsonl,
+    Parquet { writer: Box<ArrowWriter<SharedBuffer>>, buffer: SharedBuffer, schema: SchemaRef },
+}
+
+impl Encoder {
+    fn new(request: &ExportRequest) -> Result<Self> {
+        Ok(match request.format {
+            ExportFormat::Csv => Encoder::Csv { header_written: false },
+            ExportFormat::Jsonl => Encoder::Jsonl,
+            ExportFormat::Parquet => {
+                let schema = parquet_schema(request.dataset);
+                let buffer = SharedBuffer::default();
+                let properties = WriterProperties::builder()
+                    .set_compression(Compression::SNAPPY)
+                    .build();
+                let writer = ArrowWriter::try_new(buffer.clone(), schema.clone(), Some(properties))
+                    .with_context(|| "Failed to start Parquet writer")?;
+                Encoder::Parquet { writer: Box::new(writer), buffer, schema }
+            },
+        })
+    }
+
+    fn encode(&mut self, rows: &Rows) -> Result<Vec<u8>> {
+        match self {
+            Encoder::Csv { header_written } => {
+                let chunk = match rows {
+                    Rows::Opportunities(rows) => csv_rows(rows, !*header_written)?,
+                    Rows::Snapshots(rows) => csv_rows(rows, !*header_written)?,
+                };
+                *header_written = true;
+                Ok(chunk)
+            },
+            Encoder::Jsonl => match rows {
+                Rows::Opportunities(rows) => json_lines(rows),
+                Rows::Snapshots(rows) => json_lines(rows),
+            },
+            Encoder::Parquet { writer, buffer, schema } => {
+                let batch = record_batch(schema.clone(), rows)?;
+                writer.write(&batch).with_context(|| "Failed to write Parquet row group")?;
+                writer.flush().with_context(|| "Failed to flush Parquet row group")?;
+                Ok(buffer.take())
+            },
+        }
+    }
+
+    /// Bytes that close the output, such as the Parquet footer.
+    fn finish(self) -> Result<Vec<u8>> {
+        match self {
+            Encoder::Csv { .. } | Encoder::Jsonl => Ok(Vec::new()),
+            Encoder::Parquet { writer, buffer, .. } => {
+                writer.close().with_context(|| "Failed to finish Parquet file")?;
+                Ok(buffer.take())
+            },
+        }
+    }
+}
+
+fn csv_rows<T: Serialize>(rows: &[T], with_header: bool) -> Result<Vec<u8>> {
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(with_header)
+        .from_writer(Vec::new());
+    for row in rows {
+        writer.serialize(row).with_context(|| "Failed to write CSV row")?;
+    }
+    writer.into_inner().map_err(|e| anyhow::anyhow!("Failed to write CSV: {}", e))
+}
+
+fn json_lines<T: Serialize>(rows: &[T]) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    for row in rows {
+        serde_json::to_writer(&mut out, row)?;
+        out.push(b'\n');
+    }
+    Ok(out)
+}
+
+fn parquet_schema(dataset: ExportDataset) -> SchemaRef {
+    let timestamp = || Field::new("timestamp", DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())), false);
+    let text = |name: &str| Field::new(name, DataType::Utf8, false);
+    let number = |name: &str| Field::new(name, DataType::Float64, false);
+
+    Arc::new(Schema::new(match dataset {
+        ExportDataset::Opportunities => vec![
+            text("id"),
+            timestamp(),
+            text("pair"),
+            Field::new("block_number", DataType::UInt64, true),
+            text("buy_exchange"),
+            text("sell_exchange"),
+            number("buy_price"),
+            number("sell_price"),
+            number("estimated_profit"),
+            number("price_difference"),
+            number("gas_cost_estimate"),
+            number("profit_percentage"),
+        ],
+        ExportDataset::Snapshots => vec![
+            text("check_id"),
+            timestamp(),
+            Field::new("block_number", DataType::UInt64, false),
+            text("pair"),
+            text("venue"),
+            // Raw base units overflow 64-bit integers, so they stay decimal strings
+            text("amount_in"),
+            text("amount_out"),
+            number("price"),
+            number("latency_ms"),
+        ],
+    }))
+}
+
+fn record_batch(schema: SchemaRef, rows: &Rows) -> Result<RecordBatch> {
+    fn text<T>(rows: &[T], field: impl Fn(&T) -> &str) -> ArrayRef {
+        Arc::new(StringArray::from_iter_values(rows.iter().map(field)))
+    }
+    fn number<T>(rows: &[T], field: impl Fn(&T) -> f64) -> ArrayRef {
+        Arc::new(Float64Array::from_iter_values(rows.iter().map(field)))
+    }
+    fn timestamps<T>(rows: &[T], field: impl Fn(&T) -> DateTime<Utc>) -> ArrayRef {
+        Arc::new(TimestampMicrosecondArray::from_iter_values(rows.iter().map(|row| field(row).timestamp_micros()))
+            .with_timezone("UTC"))
+    }
+
+    let columns = match rows {
+        Rows::Opportunities(rows) => vec![
+            text(rows, |o| &o.id),
+            timestamps(rows, |o| o.timestamp),
+            text(rows, |o| &o.pair),
+            Arc::new(UInt64Array::from(rows.iter().map(|o| o.block_number).collect::<Vec<_>>())) as ArrayRef,
+            text(rows, |o| &o.buy_exchange),
+            text(rows, |o| &o.sell_exchange),
+            number(rows, |o| o.buy_price),
+            number(rows, |o| o.sell_price),
+            number(rows, |o| o.estimated_profit),
+            number(rows, |o| o.price_difference),
+            number(rows, |o| o.gas_cost_estimate),
+            number(rows, |o| o.profit_percentage),
+        ],
+        Rows::Snapshots(rows) => vec![
+            text(rows, |s| &s.check_id),
+            timestamps(rows, |s| s.timestamp),
+            Arc::new(UInt64Array::from_iter_values(rows.iter().map(|s| s.block_number))) as ArrayRef,
+            text(rows, |s| &s.pair),
+            text(rows, |s| &s.venue),
+            text(rows, |s| &s.amount_in),
+            text(rows, |s| &s.amount_out),
+            number(rows, |s| s.price),
+            number(rows, |s| s.latency_ms),
+        ],
+    };
+
+    RecordBatch::try_new(schema, columns).with_context(|| "Failed to build Arrow record batch")
+}
+
+/// Streams the export as chunks of output bytes, one per database batch,
+/// followed by any trailer the format needs.
+pub fn export_stream(store: Arc<dyn Store>, request: ExportRequest) -> impl Stream<Item = Result<Vec<u8>>> + 'static {
+    let state = Encoder::new(&request).map(|encoder| (RowSource::new(store, request), Some(encoder)));
+
+    futures_util::stream::try_unfold(state, |state| async move {
+        let (mut source, encoder) = state?;
+        let Some(mut encoder) = encoder else {
+            return Ok(None);
+        };
+
+        match source.next_batch().await? {
+            Some(rows) => {
+                let chunk = encoder.encode(&rows)?;
+                Ok(Some((chunk, Ok((source, Some(encoder))))))
+            },
+            None => Ok(Some((encoder.finish()?, Ok((source, None))))),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{self, tests::opportunity};
+    use futures_util::TryStreamExt;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    async fn seeded_store() -> (Arc<dyn Store>, DateTime<Utc>) {
+        let store = db::open(&db::tests::temp_sqlite_url()).await.unwrap();
+        let start = Utc::now() - chrono::Duration::hours(1);
+
+        for minute in 0..7 {
+            let mut opp = opportunity(minute as f64);
+            opp.timestamp = start + chrono::Duration::minutes(minute);
+            store.store_opportunity(&opp).await.unwrap();
+        }
+
+        let snapshots: Vec<PriceSnapshot> = (0..5).map(|minute| PriceSnapshot {
+            check_id: format!("check-{}", minute),
+            timestamp: start + chrono::Duration::minutes(minute),
+            block_number: 100 + minute as u64,
+            pair: "WETH/USDC".to_string(),
+            venue: "QuickSwap".to_string(),
+            amount_in: "1000000000000000000".to_string(),
+            amount_out: "3000123456".to_string(),
+            price: 3000.123456,
+            latency_ms: 12.0,
+        }).collect();
+        store.store_price_snapshots(&snapshots).await.unwrap();
+
+        (store, start)
+    }
+
+    async fn export(store: &Arc<dyn Store>, dataset: ExportDataset, format: ExportFormat, from: DateTime<Utc>) -> Vec<u8> {
+        let mut request = ExportRequest::new(dataset, format, from, Utc::now()).unwrap();
+        request.batch_size = 3;
+        let chunks: Vec<Vec<u8>> = export_stream(store.clone(), request).try_collect().await.unwrap();
+        chunks.concat()
+    }
+
+    #[tokio::test]
+    async fn test_csv_export_spans_batches() {
+        let (store, start) = seeded_store().await;
+
+        let csv = String::from_utf8(export(&store, ExportDataset::Opportunities, ExportFormat::Csv, start).await).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 8);
+        assert!(lines[0].starts_with("id,timestamp,pair,block_number"));
+        assert_eq!(lines.iter().filter(|line| line.starts_with("id,")).count(), 1);
+
+        // The range is inclusive and ordered oldest first
+        let later = String::from_utf8(export(&store, ExportDataset::Opportunities, ExportFormat::Csv, start + chrono::Duration::minutes(5)).await).unwrap();
+        assert_eq!(later.lines().count(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_jsonl_export() {
+        let (store, start) = seeded_store().await;
+
+        let jsonl = String::from_utf8(export(&store, ExportDataset::Snapshots, ExportFormat::Jsonl, start).await).unwrap();
+        let rows: Vec<serde_json::Value> = jsonl.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(rows.len(), 5);
+        assert_eq!(rows[0]["check_id"], "check-0");
+        assert_eq!(rows[4]["amount_out"], "3000123456");
+    }
+
+    #[tokio::test]
+    async fn test_parquet_export_reads_back() {
+        let (store, start) = seeded_store().await;
+
+        let bytes = export(&store, ExportDataset::Opportunities, ExportFormat::Parquet, start).await;
+        let reader = ParquetRecordBatchReaderBuilder::try_new(actix_web::web::Bytes::from(bytes)).unwrap();
+        assert_eq!(reader.metadata().num_row_groups(), 3);
+
+        let batches: Vec<RecordBatch> = reader.build().unwrap().collect::<std::result::Result<_, _>>().unwrap();
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 7);
+        assert_eq!(batches[0].schema().field(1).data_type(), &DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())));
+
+        let empty = export(&store, ExportDataset::Snapshots, ExportFormat::Parquet, Utc::now()).await;
+        let reader = ParquetRecordBatchReaderBuilder::try_new(actix_web::web::Bytes::from(empty)).unwrap();
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 0);
+    }
+}
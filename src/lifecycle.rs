@@ -0,0 +1,263 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use uuid::Uuid;
+use tracing::{debug, info};
+
+use crate::arbitrage::ArbitrageOpportunity;
+
+/// Identity of an opportunity across checks: the same pair, venues and
+/// direction are treated as one opportunity for as long as the gap persists.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LifecycleKey {
+    pub pair: String,
+    pub buy_exchange: String,
+    pub sell_exchange: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OpportunityLifecycle {
+    pub id: String,
+    pub pair: String,
+    pub buy_exchange: String,
+    pub sell_exchange: String,
+    pub first_seen_block: u64,
+    pub last_seen_block: u64,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    pub peak_profit: f64,
+    pub peak_profit_percentage: f64,
+    pub observations: u64,
+    pub closed_at: Option<DateTime<Utc>>,
+}
+
+impl OpportunityLifecycle {
+    fn open(pair: &str, block: u64, opportunity: &ArbitrageOpportunity) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            pair: pair.to_string(),
+            buy_exchange: opportunity.buy_exchange.clone(),
+            sell_exchange: opportunity.sell_exchange.clone(),
+            first_seen_block: block,
+            last_seen_block: block,
+            first_seen_at: opportunity.timestamp,
+            last_seen_at: opportunity.timestamp,
+            peak_profit: opportunity.estimated_profit,
+            peak_profit_percentage: opportunity.profit_percentage,
+            observations: 1,
+            closed_at: None,
+        }
+    }
+
+    fn key(&self) -> LifecycleKey {
+        LifecycleKey {
+            pair: self.pair.clone(),
+            buy_exchange: self.buy_exchange.clone(),
+            sell_exchange: self.sell_exchange.clone(),
+        }
+    }
+
+    pub fn duration_seconds(&self) -> i64 {
+        (self.last_seen_at - self.first_seen_at).num_seconds()
+    }
+
+    pub fn duration_blocks(&self) -> u64 {
+        self.last_seen_block.saturating_sub(self.first_seen_block)
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.closed_at.is_none()
+    }
+}
+
+/// Lifecycles touched by a single check, to be persisted by the caller.
+#[derive(Debug, Default)]
+pub struct LifecycleUpdate {
+    pub current: Option<OpportunityLifecycle>,
+    pub closed: Vec<OpportunityLifecycle>,
+}
+
+pub struct OpportunityTracker {
+    open: Mutex<HashMap<LifecycleKey, OpportunityLifecycle>>,
+}
+
+impl OpportunityTracker {
+    /// Creates a tracker resuming the given still-open lifecycles, so a restart
+    /// does not split a persisting opportunity in two.
+    pub fn new(open_lifecycles: Vec<OpportunityLifecycle>) -> Self {
+        let open = open_lifecycles.into_iter()
+            .map(|lifecycle| (lifecycle.key(), lifecycle))
+            .collect();
+
+        Self {
+            open: Mutex::new(open),
+        }
+    }
+
+    /// Records the outcome of one check for `pair` at `block`. Any open
+    /// lifecycle for the pair not matching the detected direction is closed.
+    pub fn observe(
+        &self,
+        pair: &str,
+        block: u64,
+        detected: Option<&ArbitrageOpportunity>,
+    ) -> LifecycleUpdate {
+        let mut open = self.open.lock().unwrap();
+        let mut update = LifecycleUpdate::default();
+
+        let detected_key = detected.map(|opportunity| LifecycleKey {
+            pair: pair.to_string(),
+            buy_exchange: opportunity.buy_exchange.clone(),
+            sell_exchange: opportunity.sell_exchange.clone(),
+        });
+
+        let stale: Vec<LifecycleKey> = open.keys()
+            .filter(|key| key.pair == pair && Some(*key) != detected_key.as_ref())
+            .cloned()
+            .collect();
+
+        for key in stale {
+            if let Some(mut lifecycle) = open.remove(&key) {
+                lifecycle.closed_at = Some(Utc::now());
+                info!("Opportunity {} closed after {} blocks ({}s, peak {:.6} USDC)",
+                      lifecycle.id, lifecycle.duration_blocks(),
+                      lifecycle.duration_seconds(), lifecycle.peak_profit);
+                update.closed.push(lifecycle);
+            }
+        }
+
+        if let (Some(key), Some(opportunity)) = (detected_key, detected) {
+            let lifecycle = open.entry(key)
+                .and_modify(|lifecycle| {
+                    // A lagging RPC node can report an older block; never move backwards
+                    lifecycle.last_seen_block = lifecycle.last_seen_block.max(block);
+                    lifecycle.last_seen_at = lifecycle.last_seen_at.max(opportunity.timestamp);
+                    lifecycle.observations += 1;
+                    if opportunity.estimated_profit > lifecycle.peak_profit {
+                        lifecycle.peak_profit = opportunity.estimated_profit;
+                        lifecycle.peak_profit_percentage = opportunity.profit_percentage;
+                    }
+                })
+                .or_insert_with(|| {
+                    let lifecycle = OpportunityLifecycle::open(pair, block, opportunity);
+                    debug!("Opportunity {} opened at block {}", lifecycle.id, block);
+                    lifecycle
+                });
+
+            update.current = Some(lifecycle.clone());
+        }
+
+        update
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAIR: &str = "WETH/USDC";
+
+    fn opportunity(buy: &str, sell: &str, profit: f64) -> ArbitrageOpportunity {
+        ArbitrageOpportunity {
+            id: Uuid::new_v4().to_string(),
+            timestamp: Utc::now(),
+            pair: PAIR.to_string(),
+            block_number: None,
+            buy_exchange: buy.to_string(),
+            sell_exchange: sell.to_string(),
+            buy_price: 3000.0,
+            sell_price: 3000.0 + profit,
+            estimated_profit: profit,
+            price_difference: profit,
+            gas_cost_estimate: 0.0,
+            profit_percentage: profit / 3000.0 * 100.0,
+        }
+    }
+
+    #[test]
+    fn test_persisting_gap_is_one_lifecycle() {
+        let tracker = OpportunityTracker::new(Vec::new());
+
+        let first = tracker.observe(PAIR, 100, Some(&opportunity("QuickSwap", "SushiSwap", 6.0)));
+        let second = tracker.observe(PAIR, 105, Some(&opportunity("QuickSwap", "SushiSwap", 9.0)));
+        let third = tracker.observe(PAIR, 110, Some(&opportunity("QuickSwap", "SushiSwap", 7.0)));
+
+        let first = first.current.unwrap();
+        let third = third.current.unwrap();
+        assert_eq!(first.id, second.current.unwrap().id);
+        assert_eq!(first.id, third.id);
+        assert_eq!(third.first_seen_block, 100);
+        assert_eq!(third.last_seen_block, 110);
+        assert_eq!(third.peak_profit, 9.0);
+        assert_eq!(third.observations, 3);
+        assert!(third.is_open());
+    }
+
+    #[test]
+    fn test_lifecycle_closes_when_gap_disappears() {
+        let tracker = OpportunityTracker::new(Vec::new());
+
+        tracker.observe(PAIR, 100, Some(&opportunity("QuickSwap", "SushiSwap", 6.0)));
+        let update = tracker.observe(PAIR, 105, None);
+
+        assert!(update.current.is_none());
+        assert_eq!(update.closed.len(), 1);
+        assert!(!update.closed[0].is_open());
+        assert_eq!(update.closed[0].duration_blocks(), 0);
+
+        let reopened = tracker.observe(PAIR, 110, Some(&opportunity("QuickSwap", "SushiSwap", 6.0)));
+        assert_ne!(reopened.current.unwrap().id, update.closed[0].id);
+    }
+
+    #[test]
+    fn test_older_block_does_not_rewind_lifecycle() {
+        let tracker = OpportunityTracker::new(Vec::new());
+
+        tracker.observe(PAIR, 100, Some(&opportunity("QuickSwap", "SushiSwap", 6.0)));
+        tracker.observe(PAIR, 110, Some(&opportunity("QuickSwap", "SushiSwap", 6.0)));
+        let stale = tracker.observe(PAIR, 105, Some(&opportunity("QuickSwap", "SushiSwap", 6.0)));
+
+        let current = stale.current.unwrap();
+        assert_eq!(current.last_seen_block, 110);
+        assert_eq!(current.duration_blocks(), 10);
+        assert_eq!(current.observations, 3);
+
+
+        // Resumed after a restart onto a node that is behind the stored lifecycle
+        let resumed = OpportunityTracker::new(vec![current.clone()]);
+        let behind = resumed.observe(PAIR, 95, Some(&opportunity("QuickSwap", "SushiSwap", 6.0)))
+            .current
+            .unwrap();
+        assert_eq!(behind.id, current.id);
+        assert_eq!((behind.first_seen_block, behind.last_seen_block), (100, 110));
+        assert_eq!(behind.duration_blocks(), 10);
+    }
+
+    #[test]
+    fn test_direction_flip_closes_previous_lifecycle() {
+        let tracker = OpportunityTracker::new(Vec::new());
+
+        let first = tracker.observe(PAIR, 100, Some(&opportunity("QuickSwap", "SushiSwap", 6.0)));
+        let flipped = tracker.observe(PAIR, 101, Some(&opportunity("SushiSwap", "QuickSwap", 6.0)));
+
+        assert_eq!(flipped.closed.len(), 1);
+        assert_eq!(flipped.closed[0].id, first.current.unwrap().id);
+        assert_eq!(flipped.current.unwrap().buy_exchange, "SushiSwap");
+    }
+
+    #[test]
+    fn test_resumes_open_lifecycles() {
+        let tracker = OpportunityTracker::new(Vec::new());
+        let open = tracker.observe(PAIR, 100, Some(&opportunity("QuickSwap", "SushiSwap", 6.0)))
+            .current
+            .unwrap();
+
+        let resumed = OpportunityTracker::new(vec![open.clone()]);
+        let update = resumed.observe(PAIR, 120, Some(&opportunity("QuickSwap", "SushiSwap", 6.0)));
+
+        let current = update.current.unwrap();
+        assert_eq!(current.id, open.id);
+        assert_eq!(current.duration_blocks(), 20);
+    }
+}
@@ -0,0 +1,242 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use tracing::info;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use tokio::fs::{self, File, OpenOptions};
+use tokio::io::{AsyncWriteExt, BufWriter};
+
+use super::OpportunitySink;
+use crate::config::{FileSinkConfig, RotationPeriod};
+
+/// When the active log file is renamed aside and a fresh one started.
+#[derive(Debug, Clone)]
+pub struct RotationPolicy {
+    /// 0 disables size-based rotation
+    pub max_bytes: u64,
+    pub period: RotationPeriod,
+    pub compress: bool,
+    /// 0 keeps every rotated file
+    pub max_files: usize,
+}
+
+impl From<&FileSinkConfig> for RotationPolicy {
+    fn from(config: &FileSinkConfig) -> Self {
+        Self {
+            max_bytes: config.max_size_mb * 1024 * 1024,
+            period: config.rotate,
+            compress: config.compress,
+            max_files: config.max_files,
+        }
+    }
+}
+
+fn period_key(period: RotationPeriod, at: DateTime<Utc>) -> Option<String> {
+    match period {
+        RotationPeriod::Never => None,
+        RotationPeriod::Hourly => Some(at.format("%Y%m%d%H").to_string()),
+        RotationPeriod::Daily => Some(at.format("%Y%m%d").to_string()),
+    }
+}
+
+/// JSON lines appended to a file. Rotated files are named after the active
+/// one with the rotation time inserted before the extension, e.g.
+/// `opportunities.20240101T000000.000000.jsonl.gz`.
+pub struct FileSink {
+    path: PathBuf,
+    policy: RotationPolicy,
+    writer: BufWriter<File>,
+    size: u64,
+    /// Period the active file belongs to, for time-based rotation
+    period: Option<String>,
+}
+
+impl FileSink {
+    pub async fn open(path: impl AsRef<Path>, policy: RotationPolicy) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).await
+                .with_context(|| format!("Failed to create log directory {}", parent.display()))?;
+        }
+
+        let file = open_append(&path).await?;
+        let metadata = file.metadata().await?;
+        // A file left over from an earlier period is rotated on the first write
+        let modified = metadata.modified().map(DateTime::<Utc>::from).unwrap_or_else(|_| Utc::now());
+
+        Ok(Self {
+            period: period_key(policy.period, modified),
+            size: metadata.len(),
+            writer: BufWriter::new(file),
+            policy,
+            path,
+        })
+    }
+
+    fn needs_rotation(&self, incoming: u64, now: DateTime<Utc>) -> bool {
+        if self.size == 0 {
+            return false;
+        }
+        let too_big = self.policy.max_bytes > 0 && self.size + incoming > self.policy.max_bytes;
+        too_big || period_key(self.policy.period, now) != self.period
+    }
+
+    async fn rotate(&mut self, now: DateTime<Utc>) -> Result<()> {
+        self.writer.flush().await?;
+
+        let rotated = self.rotated_path(now);
+        fs::rename(&self.path, &rotated).await
+            .with_context(|| format!("Failed to rotate {}", self.path.display()))?;
+        self.writer = BufWriter::new(open_append(&self.path).await?);
+        self.size = 0;
+
+        let rotated = if self.policy.compress {
+            tokio::task::spawn_blocking(move || gzip(&rotated)).await??
+        } else {
+            rotated
+        };
+        info!("Rotated {} to {}", self.path.display(), rotated.display());
+
+        self.prune().await
+    }
+
+    fn rotated_path(&self, now: DateTime<Utc>) -> PathBuf {
+        let stamp = now.format("%Y%m%dT%H%M%S%.6f");
+        let name = match (self.path.file_stem(), self.path.extension()) {
+            (Some(stem), Some(extension)) => format!("{}.{}.{}", stem.to_string_lossy(), stamp, extension.to_string_lossy()),
+            _ => format!("{}.{}", self.file_name(), stamp),
+        };
+        self.path.with_file_name(name)
+    }
+
+    fn file_name(&self) -> String {
+        self.path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default()
+    }
+
+    /// Deletes the oldest rotated files beyond `max_files`.
+    async fn prune(&self) -> Result<()> {
+        if self.policy.max_files == 0 {
+            return Ok(());
+        }
+
+        let directory = match self.path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            Some(parent) => parent.to_path_buf(),
+            None => PathBuf::from("."),
+        };
+        let active = self.file_name();
+        let prefix = match self.path.file_stem() {
+            Some(stem) => format!("{}.", stem.to_string_lossy()),
+            None => format!("{}.", active),
+        };
+
+        // Rotation stamps sort chronologically
+        let mut rotated = Vec::new();
+        let mut entries = fs::read_dir(&directory).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name != active && name.starts_with(&prefix) {
+                rotated.push(name);
+            }
+        }
+        rotated.sort();
+
+        let excess = rotated.len().saturating_sub(self.policy.max_files);
+        for name in &rotated[..excess] {
+            fs::remove_file(directory.join(name)).await
+                .with_context(|| format!("Failed to remove old log file {}", name))?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl OpportunitySink for FileSink {
+    fn name(&self) -> &'static str {
+        "file"
+    }
+
+    async fn write(&mut self, entries: &[Value]) -> Result<()> {
+        for entry in entries {
+            let mut line = serde_json::to_vec(entry)?;
+            line.push(b'\n');
+
+            let now = Utc::now();
+            if self.needs_rotation(line.len() as u64, now) {
+                self.rotate(now).await?;
+            }
+            self.period = period_key(self.policy.period, now);
+
+            self.writer.write_all(&line).await
+                .with_context(|| format!("Failed to write {}", self.path.display()))?;
+            self.size += line.len() as u64;
+        }
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        self.writer.flush().await
+            .with_context(|| format!("Failed to flush {}", self.path.display()))
+    }
+}
+
+async fn open_append(path: &Path) -> Result<File> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await
+        .with_context(|| format!("Failed to open log file {}", path.display()))
+}
+
+/// Compresses `path` to `path.gz` and removes the original.
+fn gzip(path: &Path) -> Result<PathBuf> {
+    let mut compressed_name = path.as_os_str().to_owned();
+    compressed_name.push(".gz");
+    let compressed = PathBuf::from(compressed_name);
+
+    let mut source = std::fs::File::open(path)?;
+    let mut encoder = GzEncoder::new(std::fs::File::create(&compressed)?, Compression::default());
+    std::io::copy(&mut source, &mut encoder)?;
+    encoder.finish()?.sync_all()?;
+    std::fs::remove_file(path)?;
+
+    Ok(compressed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    #[tokio::test]
+    async fn test_rotates_by_size_and_keeps_newest() {
+        let directory = std::env::temp_dir().join(format!("arb-log-{}", uuid::Uuid::new_v4()));
+        let path = directory.join("opportunities.jsonl");
+        let policy = RotationPolicy { max_bytes: 64, period: RotationPeriod::Never, compress: true, max_files: 2 };
+        let mut sink = FileSink::open(&path, policy).await.unwrap();
+
+        let entries: Vec<Value> = (0..6).map(|n| serde_json::json!({"n": n, "padding": "x".repeat(20)})).collect();
+        sink.write(&entries).await.unwrap();
+        sink.flush().await.unwrap();
+
+        // Each entry fills a file on its own, so five were rotated and three pruned
+        let mut rotated: Vec<_> = std::fs::read_dir(&directory).unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .filter(|name| name != "opportunities.jsonl")
+            .collect();
+        rotated.sort();
+        assert_eq!(rotated.len(), 2);
+        assert!(rotated.iter().all(|name| name.starts_with("opportunities.") && name.ends_with(".jsonl.gz")));
+
+        let mut newest = String::new();
+        GzDecoder::new(std::fs::File::open(directory.join(&rotated[1])).unwrap()).read_to_string(&mut newest).unwrap();
+        assert_eq!(serde_json::from_str::<Value>(newest.trim()).unwrap()["n"], 4);
+
+        let active = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(serde_json::from_str::<Value>(active.trim()).unwrap()["n"], 5);
+    }
+}
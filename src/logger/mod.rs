@@ -0,0 +1,197 @@
+//! Writes every detected opportunity to the sinks configured under
+//! `[logging]`. Entries are queued and written in batches by a background
+//! task, so the monitoring loop never waits on disk or network I/O.
+
+mod file;
+mod remote;
+
+pub use file::{FileSink, RotationPolicy};
+pub use remote::{syslog_facility, KafkaSink, SyslogSink};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tracing::{debug, error, warn};
+use serde_json::Value;
+use std::time::Duration;
+use tokio::io::{AsyncWriteExt, BufWriter, Stdout};
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::{self, MissedTickBehavior};
+
+use crate::arbitrage::ArbitrageOpportunity;
+use crate::config::{LoggingConfig, SinkConfig};
+use crate::metrics;
+
+/// Entries written in one go even if the flush interval has not elapsed.
+const MAX_BATCH: usize = 256;
+
+/// A destination for logged opportunities.
+#[async_trait]
+pub trait OpportunitySink: Send {
+    /// Short kind name used in logs and metrics
+    fn name(&self) -> &'static str;
+
+    /// Writes entries in order; output may stay buffered until `flush`.
+    async fn write(&mut self, entries: &[Value]) -> Result<()>;
+
+    async fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+enum Message {
+    Entry(Value),
+    Flush(oneshot::Sender<()>),
+}
+
+pub struct OpportunityLogger {
+    sender: mpsc::Sender<Message>,
+}
+
+impl OpportunityLogger {
+    pub async fn new(config: &LoggingConfig) -> Result<Self> {
+        let mut sinks = Vec::with_capacity(config.sinks.len());
+        for sink in &config.sinks {
+            sinks.push(open_sink(sink).await?);
+        }
+
+        Ok(Self::with_sinks(sinks, config.buffer_size, Duration::from_millis(config.flush_interval_ms)))
+    }
+
+    pub fn with_sinks(sinks: Vec<Box<dyn OpportunitySink>>, buffer_size: usize, flush_interval: Duration) -> Self {
+        let (sender, receiver) = mpsc::channel(buffer_size);
+        tokio::spawn(run_writer(sinks, receiver, flush_interval));
+        Self { sender }
+    }
+
+    /// Queues an opportunity for the sinks. When the queue is full the entry
+    /// is dropped rather than stalling the caller.
+    pub async fn log_opportunity(&self, opportunity: &ArbitrageOpportunity) -> Result<()> {
+        match self.sender.try_send(Message::Entry(log_entry(opportunity))) {
+            Ok(()) => debug!("Opportunity {} queued for logging", opportunity.id),
+            Err(mpsc::error::TrySendError::Full(_)) => {
+                metrics::get().log_entries_dropped.inc();
+                warn!("Opportunity log queue is full, dropping {}", opportunity.id);
+            },
+            Err(mpsc::error::TrySendError::Closed(_)) => anyhow::bail!("Opportunity logger has stopped"),
+        }
+
+        Ok(())
+    }
+
+    /// Writes everything queued so far and flushes every sink.
+    pub async fn flush(&self) -> Result<()> {
+        let (ack, done) = oneshot::channel();
+        self.sender.send(Message::Flush(ack)).await
+            .map_err(|_| anyhow::anyhow!("Opportunity logger has stopped"))?;
+        done.await?;
+        Ok(())
+    }
+}
+
+fn log_entry(opportunity: &ArbitrageOpportunity) -> Value {
+    serde_json::json!({
+        "id": opportunity.id,
+        "timestamp": opportunity.timestamp.to_rfc3339(),
+        "pair": opportunity.pair,
+        "block_number": opportunity.block_number,
+        "buy_exchange": opportunity.buy_exchange,
+        "sell_exchange": opportunity.sell_exchange,
+        "buy_price": opportunity.buy_price,
+        "sell_price": opportunity.sell_price,
+        "estimated_profit": opportunity.estimated_profit,
+        "price_difference": opportunity.price_difference,
+        "gas_cost_estimate": opportunity.gas_cost_estimate,
+        "profit_percentage": opportunity.profit_percentage
+    })
+}
+
+async fn open_sink(config: &SinkConfig) -> Result<Box<dyn OpportunitySink>> {
+    Ok(match config {
+        SinkConfig::File(file) => Box::new(FileSink::open(&file.path, RotationPolicy::from(file)).await?),
+        SinkConfig::Stdout => Box::new(StdoutSink::new()),
+        SinkConfig::Syslog(syslog) => Box::new(SyslogSink::connect(syslog).await?),
+        SinkConfig::Kafka(kafka) => Box::new(KafkaSink::new(kafka)?),
+    })
+}
+
+async fn run_writer(mut sinks: Vec<Box<dyn OpportunitySink>>, mut receiver: mpsc::Receiver<Message>, flush_interval: Duration) {
+    let mut pending = Vec::new();
+    let mut ticker = time::interval(flush_interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            message = receiver.recv() => match message {
+                Some(Message::Entry(entry)) => {
+                    pending.push(entry);
+                    if pending.len() >= MAX_BATCH {
+                        write_pending(&mut sinks, &mut pending).await;
+                    }
+                },
+                Some(Message::Flush(ack)) => {
+                    write_pending(&mut sinks, &mut pending).await;
+                    let _ = ack.send(());
+                },
+                None => {
+                    write_pending(&mut sinks, &mut pending).await;
+                    break;
+                },
+            },
+            _ = ticker.tick() => write_pending(&mut sinks, &mut pending).await,
+        }
+    }
+}
+
+/// Hands the pending entries to every sink. A failing sink loses this batch
+/// but does not hold up the others.
+async fn write_pending(sinks: &mut [Box<dyn OpportunitySink>], pending: &mut Vec<Value>) {
+    if pending.is_empty() {
+        return;
+    }
+
+    for sink in sinks.iter_mut() {
+        let result = async {
+            sink.write(pending).await?;
+            sink.flush().await
+        }.await;
+
+        if let Err(e) = result {
+            metrics::get().log_sink_errors.with_label_values(&[sink.name()]).inc();
+            error!("Failed to write {} opportunities to {} sink: {:#}", pending.len(), sink.name(), e);
+        }
+    }
+
+    pending.clear();
+}
+
+/// One JSON object per line on standard output.
+pub struct StdoutSink {
+    writer: BufWriter<Stdout>,
+}
+
+impl StdoutSink {
+    pub fn new() -> Self {
+        Self { writer: BufWriter::new(tokio::io::stdout()) }
+    }
+}
+
+#[async_trait]
+impl OpportunitySink for StdoutSink {
+    fn name(&self) -> &'static str {
+        "stdout"
+    }
+
+    async fn write(&mut self, entries: &[Value]) -> Result<()> {
+        for entry in entries {
+            let mut line = serde_json::to_vec(entry)?;
+            line.push(b'\n');
+            self.writer.write_all(&line).await?;
+        }
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        self.writer.flush().await?;
+        Ok(())
+    }
+}
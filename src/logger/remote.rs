@@ -0,0 +1,222 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{SecondsFormat, Utc};
+use reqwest::header;
+use serde_json::Value;
+use std::time::Duration;
+use tokio::net::UdpSocket;
+
+use super::OpportunitySink;
+use crate::config::{KafkaSinkConfig, SyslogSinkConfig};
+
+/// Informational, the RFC 5424 severity given to every opportunity
+const SYSLOG_SEVERITY: u8 = 6;
+
+/// Numeric code of an RFC 5424 facility name.
+pub fn syslog_facility(name: &str) -> Option<u8> {
+    let code = match name {
+        "kern" => 0,
+        "user" => 1,
+        "mail" => 2,
+        "daemon" => 3,
+        "auth" => 4,
+        "syslog" => 5,
+        "lpr" => 6,
+        "news" => 7,
+        "uucp" => 8,
+        "cron" => 9,
+        "authpriv" => 10,
+        "ftp" => 11,
+        _ => {
+            let local: u8 = name.strip_prefix("local")?.parse().ok()?;
+            if local > 7 {
+                return None;
+            }
+            16 + local
+        },
+    };
+    Some(code)
+}
+
+/// RFC 5424 messages over UDP, one datagram per opportunity.
+pub struct SyslogSink {
+    socket: UdpSocket,
+    priority: u8,
+    hostname: String,
+    app_name: String,
+}
+
+impl SyslogSink {
+    pub async fn connect(config: &SyslogSinkConfig) -> Result<Self> {
+        let facility = syslog_facility(&config.facility)
+            .with_context(|| format!("Unknown syslog facility: {}", config.facility))?;
+        let address = tokio::net::lookup_host(&config.address).await
+            .with_context(|| format!("Failed to resolve syslog address {}", config.address))?
+            .next()
+            .with_context(|| format!("No address found for {}", config.address))?;
+
+        let local = if address.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" };
+        let socket = UdpSocket::bind(local).await?;
+        socket.connect(address).await
+            .with_context(|| format!("Failed to connect to syslog collector {}", address))?;
+
+        Ok(Self {
+            socket,
+            priority: facility * 8 + SYSLOG_SEVERITY,
+            hostname: std::env::var("HOSTNAME").unwrap_or_else(|_| "-".to_string()),
+            app_name: config.app_name.clone(),
+        })
+    }
+}
+
+#[async_trait]
+impl OpportunitySink for SyslogSink {
+    fn name(&self) -> &'static str {
+        "syslog"
+    }
+
+    async fn write(&mut self, entries: &[Value]) -> Result<()> {
+        for entry in entries {
+            let message = format!(
+                "<{}>1 {} {} {} {} opportunity - {}",
+                self.priority,
+                Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+                self.hostname,
+                self.app_name,
+                std::process::id(),
+                entry,
+            );
+            self.socket.send(message.as_bytes()).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Produces each batch as one request to the `/topics/{topic}` endpoint of
+/// a Kafka REST proxy, keyed by pair so a pair's entries stay ordered.
+pub struct KafkaSink {
+    client: reqwest::Client,
+    endpoint: String,
+}
+
+impl KafkaSink {
+    pub fn new(config: &KafkaSinkConfig) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(config.timeout_ms))
+            .build()?;
+
+        Ok(Self {
+            client,
+            endpoint: format!("{}/topics/{}", config.url.trim_end_matches('/'), config.topic),
+        })
+    }
+}
+
+#[async_trait]
+impl OpportunitySink for KafkaSink {
+    fn name(&self) -> &'static str {
+        "kafka"
+    }
+
+    async fn write(&mut self, entries: &[Value]) -> Result<()> {
+        let records: Vec<Value> = entries.iter()
+            .map(|entry| serde_json::json!({"key": entry["pair"], "value": entry}))
+            .collect();
+
+        let response: Value = self.client
+            .post(&self.endpoint)
+            .header(header::CONTENT_TYPE, "application/vnd.kafka.json.v2+json")
+            .header(header::ACCEPT, "application/vnd.kafka.v2+json")
+            .json(&serde_json::json!({"records": records}))
+            .send()
+            .await
+            .with_context(|| format!("Failed to reach Kafka REST proxy at {}", self.endpoint))?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        // The proxy answers 200 even when individual records were rejected
+        let rejected = response["offsets"].as_array()
+            .into_iter()
+            .flatten()
+            .find(|offset| !offset["error"].is_null());
+        if let Some(offset) = rejected {
+            anyhow::bail!("Kafka REST proxy rejected a record: {}", offset["error"]);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::tests::opportunity;
+    use crate::logger::{log_entry, OpportunityLogger};
+    use actix_web::{post, web, App, HttpResponse, HttpServer};
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_facility_codes() {
+        assert_eq!(syslog_facility("daemon"), Some(3));
+        assert_eq!(syslog_facility("local7"), Some(23));
+        assert_eq!(syslog_facility("local8"), None);
+        assert_eq!(syslog_facility("system"), None);
+    }
+
+    #[tokio::test]
+    async fn test_syslog_sink_through_logger() {
+        let collector = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let sink = SyslogSink::connect(&SyslogSinkConfig {
+            address: collector.local_addr().unwrap().to_string(),
+            facility: "local0".to_string(),
+            app_name: "arb-test".to_string(),
+        }).await.unwrap();
+
+        let logger = OpportunityLogger::with_sinks(vec![Box::new(sink)], 16, Duration::from_secs(60));
+        logger.log_opportunity(&opportunity(4.0)).await.unwrap();
+        logger.flush().await.unwrap();
+
+        let mut datagram = vec![0; 4096];
+        let len = collector.recv(&mut datagram).await.unwrap();
+        let message = String::from_utf8_lossy(&datagram[..len]).into_owned();
+
+        assert!(message.starts_with("<134>1 "));
+        let (header, payload) = message.split_once(" opportunity - ").unwrap();
+        assert!(header.ends_with(&format!("arb-test {}", std::process::id())));
+        assert_eq!(serde_json::from_str::<Value>(payload).unwrap()["estimated_profit"], 4.0);
+    }
+
+    #[post("/topics/{topic}")]
+    async fn produce(received: web::Data<Mutex<Vec<(String, Value)>>>, topic: web::Path<String>, body: web::Json<Value>) -> HttpResponse {
+        received.lock().unwrap().push((topic.into_inner(), body.into_inner()));
+        HttpResponse::Ok().json(serde_json::json!({"offsets": [{"partition": 0, "offset": 1, "error": null}]}))
+    }
+
+    #[actix_web::test]
+    async fn test_kafka_sink_posts_records() {
+        let received = web::Data::new(Mutex::new(Vec::new()));
+        let server_data = received.clone();
+        let server = HttpServer::new(move || App::new().app_data(server_data.clone()).service(produce))
+            .workers(1)
+            .bind(("127.0.0.1", 0))
+            .unwrap();
+        let addr = server.addrs()[0];
+        actix_web::rt::spawn(server.run());
+
+        let mut sink = KafkaSink::new(&KafkaSinkConfig {
+            url: format!("http://{}/", addr),
+            topic: "opportunities".to_string(),
+            timeout_ms: 5000,
+        }).unwrap();
+        let entries = [log_entry(&opportunity(2.0)), log_entry(&opportunity(3.0))];
+        sink.write(&entries).await.unwrap();
+
+        let received: Arc<Mutex<Vec<(String, Value)>>> = received.into_inner();
+        let received = received.lock().unwrap();
+        let (topic, body) = &received[0];
+        assert_eq!(topic, "opportunities");
+        assert_eq!(body["records"].as_array().unwrap().len(), 2);
+        assert_eq!(body["records"][1]["key"], "WETH/USDC");
+        assert_eq!(body["records"][1]["value"]["estimated_profit"], 3.0);
+    }
+}
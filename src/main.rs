@@ -1,165 +1,527 @@
-mod config;
-mod dex;
-mod price_fetcher;
-mod arbitrage;
-mod logger;
-mod db;
-mod api;
-
-use std::time::Duration;
-use tokio::time;
-use anyhow::Result;
-use log::warn;
-use clap::Parser;
-use actix_web::{web, App, HttpServer, middleware::Logger as ActixLogger};
-use actix_cors::Cors;
-use std::sync::Arc;
-
-use crate::config::AppConfig;
-use crate::price_fetcher::PriceFetcher;
-use crate::arbitrage::ArbitrageDetector;
-use crate::logger::OpportunityLogger;
-use crate::db::Database;
-
-#[derive(Parser)]
-#[command(name = "polygon-arbitrage-bot")]
-#[command(about = "A Polygon arbitrage opportunity detection bot")]
-struct Cli {
-    #[arg(short, long, default_value = "config/config.toml")]
-    config: String,
-    
-    #[arg(short, long)]
-    debug: bool,
-}
-
-#[actix_web::main]
-async fn main() -> Result<()> {
-    let cli = Cli::parse();
-    
-    let log_level = if cli.debug { "debug" } else { "info" };
-    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(log_level)).init();
-    
-    println!("Starting Polygon Arbitrage Bot");
-    
-    let config = AppConfig::load(&cli.config).await?;
-    let config = Arc::new(config);
-    
-    println!("Configuration loaded successfully");
-    println!("Monitoring WETH/USDC pair on QuickSwap vs SushiSwap");
-    println!("Minimum profit threshold: {:.2} USDC", 
-          config.trading.min_profit_usdc.parse::<f64>().unwrap() / 1_000_000.0);
-    
-    let database = Arc::new(Database::new(&config.database.url).await?);
-    println!("Database initialized");
-    
-    let price_fetcher = Arc::new(PriceFetcher::new(&config).await?);
-    let detector = Arc::new(ArbitrageDetector::new(&config));
-    let logger = Arc::new(OpportunityLogger::new().await?);
-    
-    println!("Bot components initialized successfully");
-    
-    // Clone references for the async task
-    let db_clone = database.clone();
-    let fetcher_clone = price_fetcher.clone();
-    let detector_clone = detector.clone();
-    let logger_clone = logger.clone();
-    let config_clone = config.clone();
-    
-    tokio::spawn(async move {
-        monitor_arbitrage_loop(db_clone, fetcher_clone, detector_clone, logger_clone, config_clone).await;
-    });
-    
-    // CRITICAL FIX: Clone database for the HTTP server
-    let db_for_server = database.clone();
-    
-    println!("HTTP API server starting on http://127.0.0.1:8081");
-    println!("Starting monitoring loop (interval: {}s)", config.monitoring.check_interval_seconds);
-    println!("-----------------------------------------------------");
-    
-    HttpServer::new(move || {
-        let cors = Cors::default()
-            .allow_any_origin()
-            .allowed_methods(vec!["GET", "POST", "OPTIONS"])
-            .allowed_headers(vec!["Authorization", "Accept", "Content-Type"])
-            .max_age(3600);
-
-        App::new()
-            .wrap(cors)
-            .wrap(ActixLogger::default())
-            .app_data(web::Data::new(db_for_server.clone())) // Use the cloned database
-            .service(api::get_opportunities)
-            .service(api::get_stats)
-            .service(api::get_status)
-    })
-    .bind("127.0.0.1:8081")?
-    .run()
-    .await?;
-
-    Ok(())
-}
-
-async fn monitor_arbitrage_loop(
-    database: Arc<Database>,
-    fetcher: Arc<PriceFetcher>,
-    detector: Arc<ArbitrageDetector>,
-    logger: Arc<OpportunityLogger>,
-    config: Arc<AppConfig>,
-) {
-    let mut interval = time::interval(Duration::from_secs(config.monitoring.check_interval_seconds));
-    let mut iteration_count = 0u64;
-    
-    loop {
-        interval.tick().await;
-        iteration_count += 1;
-        
-        print!("Check #{}: Fetching prices from DEXes... ", iteration_count);
-        
-        match run_arbitrage_check(&database, &fetcher, &detector, &logger).await {
-            Ok(found_opportunity) => {
-                if !found_opportunity {
-                    println!("No profitable arbitrage opportunities found");
-                }
-            },
-            Err(e) => {
-                println!("Error in arbitrage check: {}", e);
-                warn!("Continuing with next iteration...");
-            }
-        }
-    }
-}
-
-async fn run_arbitrage_check(
-    database: &Database,
-    fetcher: &PriceFetcher,
-    detector: &ArbitrageDetector,
-    logger: &OpportunityLogger,
-) -> Result<bool> {
-    let (quickswap_result, sushiswap_result) = tokio::join!(
-        fetcher.get_quickswap_price(),
-        fetcher.get_sushiswap_price()
-    );
-    
-    let quickswap_price = quickswap_result?;
-    let sushiswap_price = sushiswap_result?;
-    
-    print!("QuickSwap={:.6} USDC, SushiSwap={:.6} USDC -> ", quickswap_price, sushiswap_price);
-    
-    let price_diff = (quickswap_price - sushiswap_price).abs();
-    
-    if let Some(opportunity) = detector.detect_opportunity(quickswap_price, sushiswap_price) {
-        println!("ARBITRAGE OPPORTUNITY DETECTED!");
-        println!("   Buy on: {} at {:.6} USDC", opportunity.buy_exchange, opportunity.buy_price);
-        println!("   Sell on: {} at {:.6} USDC", opportunity.sell_exchange, opportunity.sell_price);
-        println!("   Estimated profit: {:.6} USDC", opportunity.estimated_profit);
-        println!("   Price difference: {:.6} USDC", opportunity.sell_price - opportunity.buy_price);
-        
-        logger.log_opportunity(&opportunity).await?;
-        database.store_opportunity(&opportunity).await?;
-        
-        return Ok(true);
-    } else {
-        println!("Price diff: {:.6} USDC (below threshold)", price_diff);
-    }
-    
-    Ok(false)
-}
+mod config;
+mod dex;
+mod price_fetcher;
+mod arbitrage;
+mod logger;
+mod notifier;
+mod db;
+mod api;
+mod relay;
+mod mempool;
+mod lifecycle;
+mod snapshots;
+mod events;
+mod status;
+mod metrics;
+mod rpc;
+mod control;
+mod auth;
+mod tls;
+mod models;
+mod openapi;
+mod export;
+mod telemetry;
+mod supervisor;
+mod reload;
+mod verify;
+mod cli;
+mod tui;
+
+use std::time::Duration;
+use ethers::types::U256;
+use tokio::time;
+use anyhow::{Context, Result};
+use tracing::{debug, error, info, info_span, warn, Instrument, Span};
+use clap::Parser;
+use actix_web::{web, App, HttpServer, middleware::{from_fn, Logger as ActixLogger}};
+use actix_cors::Cors;
+use std::sync::Arc;
+
+use crate::config::{redact_url, ApiConfig, AppConfig, SinkConfig};
+use crate::price_fetcher::PriceFetcher;
+use crate::arbitrage::{ArbitrageDetector, MONITORED_PAIR};
+use crate::logger::OpportunityLogger;
+use crate::notifier::{Alert, Notifier};
+use crate::db::Store;
+use crate::mempool::MempoolWatcher;
+use crate::lifecycle::OpportunityTracker;
+use crate::snapshots::{PriceSnapshot, SnapshotRecorder};
+use crate::events::{BotEvent, EventBus};
+use crate::status::StatusTracker;
+use crate::control::{BotControl, RuntimeSettings};
+use crate::auth::ApiAuth;
+use crate::export::ExportRequest;
+use crate::telemetry::Telemetry;
+use crate::supervisor::{RestartPolicy, Supervisor};
+use crate::reload::{ConfigReloader, ConfigSource};
+use crate::cli::{Cli, Command, RunMode};
+use chrono::{DateTime, Utc};
+use tokio_util::sync::CancellationToken;
+
+#[actix_web::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+    
+    let source = ConfigSource { path: cli.config, profile: cli.profile };
+    let config = match source.load().await {
+        Ok(config) => config,
+        Err(e) if matches!(cli.command, Some(Command::Check)) => return cli::check_unloadable(e, cli.json),
+        Err(e) => return Err(e),
+    };
+    if cli.print_config {
+        print!("{}", config.to_redacted_toml()?);
+        return Ok(());
+    }
+    let log_file = match &cli.command {
+        Some(Command::Tui { log_file, .. }) => Some(log_file.as_path()),
+        _ => None,
+    };
+    let telemetry = Telemetry::init(&config.telemetry, cli.debug, log_file)?;
+    
+    let result = match cli.command {
+        None => run_bot(config, source, RunMode::Full, false).await,
+        Some(Command::Run { no_api, api_only }) => run_bot(config, source, RunMode::from_flags(no_api, api_only), false).await,
+        Some(Command::Tui { no_api, .. }) => run_bot(config, source, RunMode::from_flags(no_api, false), true).await,
+        Some(Command::Quote) => cli::quote(&config, cli.json).await,
+        Some(Command::Check) => cli::check(&config, cli.json).await,
+        Some(Command::Submit { txs, target_block }) => cli::submit(&config, txs, target_block, cli.json).await,
+        Some(Command::Db { action }) => cli::db(&config, action, cli.json).await,
+        Some(Command::Export { dataset, format, from, to, output }) => {
+            match ExportRequest::new(dataset, format, from.unwrap_or(DateTime::UNIX_EPOCH), to.unwrap_or_else(Utc::now)) {
+                Ok(request) => cli::export(&config, request, output, cli.json).await,
+                Err(e) => Err(e),
+            }
+        },
+    };
+    
+    telemetry.shutdown();
+    result
+}
+
+async fn run_bot(config: AppConfig, source: ConfigSource, mode: RunMode, dashboard: bool) -> Result<()> {
+    info!(?mode, dashboard, "Starting Polygon Arbitrage Bot");
+    
+    if dashboard && config.logging.sinks.iter().any(|sink| matches!(sink, SinkConfig::Stdout)) {
+        anyhow::bail!("The stdout opportunity sink would draw over the dashboard; remove it from [logging] or use `run`");
+    }
+    let config = Arc::new(config);
+    
+    let database = db::open(&config.database.url).await?;
+    info!("Database initialized");
+    
+    let status = StatusTracker::new(
+        &config.network.rpc_url,
+        config.monitoring.stall_after_seconds(),
+    );
+    let status = Arc::new(if mode.monitors() { status } else { status.without_monitoring() });
+    let events = Arc::new(EventBus::new(EVENT_CHANNEL_CAPACITY));
+    let control = Arc::new(BotControl::new(
+        database.clone(),
+        RuntimeSettings::from_config(&config)?,
+    ));
+    
+    let shutdown = CancellationToken::new();
+    supervisor::handle_signals(shutdown.clone());
+    let mut supervisor = Supervisor::new(shutdown.clone(), status.clone(), RestartPolicy::default());
+    
+    let context = if mode.monitors() {
+        Some(start_monitoring(&config, source, &mut supervisor, database.clone(), events.clone(), status.clone(), control.clone()).await?)
+    } else {
+        info!("Monitoring disabled, serving stored data only");
+        None
+    };
+    
+    // Quitting the dashboard stops the bot
+    let dashboard = dashboard.then(|| {
+        let (events, status, control, shutdown) = (events.clone(), status.clone(), control.clone(), shutdown.clone());
+        tokio::spawn(async move {
+            let result = tui::run(events, status, control, shutdown.clone()).await;
+            shutdown.cancel();
+            result
+        })
+    });
+    
+    let served = if mode.serves_api() {
+        serve_api(&config, database, events, status, control, shutdown.clone()).await
+    } else {
+        shutdown.cancelled().await;
+        Ok(())
+    };
+    // The server only returns early on its own when it failed
+    shutdown.cancel();
+    info!("Shutting down");
+    
+    let stopped = supervisor.join(SHUTDOWN_TIMEOUT).await;
+    let drawn = match dashboard {
+        Some(handle) => handle.await.map_err(anyhow::Error::from).and_then(|result| result),
+        None => Ok(()),
+    };
+    
+    // Snapshots and opportunities still buffered would otherwise be lost
+    let drained = match context {
+        Some(context) => context.recorder.flush().await
+            .with_context(|| "Failed to flush price snapshots")
+            .and(context.logger.flush().await.with_context(|| "Failed to flush opportunity log")),
+        None => Ok(()),
+    };
+    
+    served?;
+    stopped?;
+    drained?;
+    drawn.with_context(|| "Dashboard failed")?;
+    info!("Shutdown complete");
+    
+    Ok(())
+}
+
+/// Builds the monitoring components and starts the monitoring loop and the
+/// tasks that feed it under `supervisor`.
+async fn start_monitoring(
+    config: &Arc<AppConfig>,
+    source: ConfigSource,
+    supervisor: &mut Supervisor,
+    database: Arc<dyn Store>,
+    events: Arc<EventBus>,
+    status: Arc<StatusTracker>,
+    control: Arc<BotControl>,
+) -> Result<BotContext> {
+    info!(
+        pair = MONITORED_PAIR,
+        min_profit_usdc = config.trading.min_profit_usdc.parse::<f64>().unwrap() / 1_000_000.0,
+        "Monitoring QuickSwap vs SushiSwap"
+    );
+    
+    let price_fetcher = Arc::new(PriceFetcher::new(config).await?);
+    let detector = Arc::new(ArbitrageDetector::new(config));
+    let logger = Arc::new(OpportunityLogger::new(&config.logging).await?);
+    let notifier = Arc::new(Notifier::new(&config.alerts)?);
+    let tracker = Arc::new(OpportunityTracker::new(database.get_open_lifecycles().await?));
+    let recorder = Arc::new(SnapshotRecorder::new(database.clone(), config.snapshots.clone()));
+    
+    let maintenance_recorder = recorder.clone();
+    supervisor.spawn("snapshot_maintenance", move |shutdown| maintenance_recorder.clone().run_maintenance(shutdown));
+    
+    info!("Bot components initialized successfully");
+    
+    if config.mempool.enabled {
+        let watcher = MempoolWatcher::connect(config, control.subscribe_settings(), detector.clone(), database.clone()).await?;
+        info!(url = %redact_url(&config.mempool.ws_url), "Mempool watcher connected");
+        
+        // The first attempt reuses the startup connection; restarts reconnect
+        let mut connected = Some(watcher);
+        let (mempool_config, mempool_control, mempool_detector, mempool_database) = (config.clone(), control.clone(), detector.clone(), database.clone());
+        supervisor.spawn("mempool", move |shutdown| {
+            let watcher = connected.take();
+            let (config, settings, detector, database) = (mempool_config.clone(), mempool_control.subscribe_settings(), mempool_detector.clone(), mempool_database.clone());
+            async move {
+                let watcher = match watcher {
+                    Some(watcher) => watcher,
+                    None => MempoolWatcher::connect(&config, settings, detector, database).await?,
+                };
+                tokio::select! {
+                    result = watcher.run() => result,
+                    _ = shutdown.cancelled() => Ok(()),
+                }
+            }
+        });
+    }
+    
+    let context = BotContext {
+        database,
+        fetcher: price_fetcher.clone(),
+        detector,
+        logger,
+        notifier,
+        tracker,
+        recorder,
+        events,
+        status: status.clone(),
+    };
+    
+    let reloader = Arc::new(ConfigReloader::new(
+        source,
+        (**config).clone(),
+        price_fetcher,
+        control.clone(),
+        status,
+    )?);
+    supervisor.spawn("config_reload", move |shutdown| reloader.clone().run(shutdown));
+    
+    info!(interval_seconds = config.monitoring.check_interval_seconds, "Starting monitoring loop");
+    let monitor_context = context.clone();
+    supervisor.spawn("monitor", move |shutdown| {
+        let (context, control) = (monitor_context.clone(), control.clone());
+        async move {
+            monitor_arbitrage_loop(context, control, shutdown).await;
+            Ok(())
+        }
+    });
+    
+    Ok(context)
+}
+
+/// Serves the HTTP API until `shutdown` is cancelled or the server fails.
+async fn serve_api(
+    config: &AppConfig,
+    database: Arc<dyn Store>,
+    events: Arc<EventBus>,
+    status: Arc<StatusTracker>,
+    control: Arc<BotControl>,
+    shutdown: CancellationToken,
+) -> Result<()> {
+    let api_auth = Arc::new(ApiAuth::new(config.api.keys.clone()));
+    if !api_auth.is_enabled() {
+        warn!("No API keys configured: read endpoints are open and the control API is disabled");
+    }
+    
+    let db_for_server = database.clone();
+    let events_for_server = events.clone();
+    let status_for_server = status.clone();
+    let control_for_server = control.clone();
+    let auth_for_server = api_auth.clone();
+    let api_config = config.api.clone();
+    let tls_config = match (&api_config.tls_cert, &api_config.tls_key) {
+        (Some(cert), Some(key)) => Some(tls::load_server_config(cert, key)?),
+        _ => None,
+    };
+    
+    info!(
+        scheme = if tls_config.is_some() { "https" } else { "http" },
+        bind = %api_config.bind,
+        port = api_config.port,
+        "HTTP API server starting"
+    );
+    
+    let server = HttpServer::new(move || {
+        App::new()
+            .wrap(from_fn(auth::enforce))
+            .wrap(build_cors(&api_config))
+            .wrap(ActixLogger::default())
+            .app_data(web::Data::new(auth_for_server.clone()))
+            .app_data(web::Data::new(db_for_server.clone()))
+            .app_data(web::Data::new(events_for_server.clone()))
+            .app_data(web::Data::new(status_for_server.clone()))
+            .app_data(web::Data::new(control_for_server.clone()))
+            .service(api::get_opportunities)
+            .service(api::get_stats)
+            .service(api::get_status)
+            .service(api::healthz)
+            .service(api::readyz)
+            .service(api::get_metrics)
+            .service(api::get_predictions)
+            .service(api::get_lifecycles)
+            .service(api::get_snapshots)
+            .service(api::get_profit_buckets)
+            .service(api::get_venue_leaderboard)
+            .service(api::export_data)
+            .service(api::stream_ws)
+            .service(api::stream_sse)
+            .service(api::pause_monitoring)
+            .service(api::resume_monitoring)
+            .service(api::trigger_check)
+            .service(api::update_settings)
+            .service(api::get_control_audit)
+            .service(openapi::get_openapi)
+            .service(openapi::swagger_ui)
+    });
+    
+    let address = (config.api.bind.as_str(), config.api.port);
+    // Signals are handled by the supervisor so every task stops together
+    let server = server.disable_signals().shutdown_timeout(SHUTDOWN_TIMEOUT.as_secs());
+    let server = match tls_config {
+        Some(tls_config) => server.bind_rustls_021(address, tls_config),
+        None => server.bind(address),
+    }
+    .with_context(|| format!("Failed to bind API server to {}:{}", config.api.bind, config.api.port))?;
+    
+    let server = server.run();
+    let server_handle = server.handle();
+    let server_shutdown = shutdown.clone();
+    tokio::spawn(async move {
+        server_shutdown.cancelled().await;
+        server_handle.stop(true).await;
+    });
+    
+    server.await.with_context(|| "API server failed")
+}
+
+fn build_cors(config: &ApiConfig) -> Cors {
+    let cors = Cors::default()
+        .allowed_methods(vec!["GET", "POST", "OPTIONS"])
+        .allowed_headers(vec!["Authorization", "Accept", "Content-Type", "X-API-Key"])
+        .max_age(3600);
+    
+    if config.allowed_origins.iter().any(|origin| origin == "*") {
+        cors.allow_any_origin()
+    } else {
+        config.allowed_origins.iter().fold(cors, |cors, origin| cors.allowed_origin(origin))
+    }
+}
+
+/// Live events buffered per subscriber before slow clients start missing them
+const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// How long in-flight requests and checks get to finish once shutdown starts
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Everything a single arbitrage check needs.
+#[derive(Clone)]
+struct BotContext {
+    database: Arc<dyn Store>,
+    fetcher: Arc<PriceFetcher>,
+    detector: Arc<ArbitrageDetector>,
+    logger: Arc<OpportunityLogger>,
+    notifier: Arc<Notifier>,
+    tracker: Arc<OpportunityTracker>,
+    recorder: Arc<SnapshotRecorder>,
+    events: Arc<EventBus>,
+    status: Arc<StatusTracker>,
+}
+
+struct CheckOutcome {
+    block_number: u64,
+}
+
+/// Runs checks until `shutdown` is cancelled. A check already in progress
+/// is allowed to finish.
+async fn monitor_arbitrage_loop(context: BotContext, control: Arc<BotControl>, shutdown: CancellationToken) {
+    let mut settings_rx = control.subscribe_settings();
+    let mut period = Duration::from_secs(settings_rx.borrow_and_update().check_interval_seconds);
+    let mut interval = time::interval(period);
+    let mut iteration_count = 0u64;
+    
+    loop {
+        let manual = tokio::select! {
+            _ = shutdown.cancelled() => return,
+            _ = interval.tick() => false,
+            _ = control.check_requested() => true,
+            Ok(()) = settings_rx.changed() => {
+                let settings = settings_rx.borrow_and_update().clone();
+                context.detector.set_thresholds(settings.min_profit_usdc, settings.gas_estimate_usdc);
+                context.fetcher.set_trade_amount(U256::from(settings.trade_amount_wei));
+                
+                let new_period = Duration::from_secs(settings.check_interval_seconds);
+                if new_period != period {
+                    period = new_period;
+                    interval = time::interval_at(time::Instant::now() + period, period);
+                }
+                continue;
+            }
+        };
+        
+        // Paused ticks still count as the loop being alive
+        context.status.record_heartbeat();
+        if control.is_paused() && !manual {
+            continue;
+        }
+        
+        iteration_count += 1;
+        
+        // Events and child spans of the check carry its iteration and block
+        let span = info_span!("check", iteration = iteration_count, manual, block = tracing::field::Empty);
+        
+        let started = std::time::Instant::now();
+        let result = run_arbitrage_check(&context).instrument(span.clone()).await;
+        metrics::get().check_duration.observe(started.elapsed().as_secs_f64());
+        metrics::get().checks
+            .with_label_values(&[if result.is_ok() { "ok" } else { "error" }])
+            .inc();
+        
+        // Only transitions are published so subscribers are not flooded
+        let health_changed = match &result {
+            Ok(outcome) => context.status.record_success(outcome.block_number),
+            Err(e) => context.status.record_failure(e),
+        };
+        context.notifier.record_check(context.status.consecutive_errors(), result.as_ref().err());
+        if health_changed {
+            context.events.publish(BotEvent::Status {
+                healthy: result.is_ok(),
+                message: result.as_ref().err().map(|e| format!("{:#}", e)),
+                at: chrono::Utc::now(),
+            });
+        }
+        
+        if let Err(e) = result {
+            span.in_scope(|| error!(error = %format!("{:#}", e), "Arbitrage check failed; continuing with next iteration"));
+        }
+    }
+}
+
+async fn run_arbitrage_check(context: &BotContext) -> Result<CheckOutcome> {
+    let BotContext { database, fetcher, detector, logger, notifier, tracker, recorder, events, .. } = context;
+    
+    let (quickswap_result, sushiswap_result, block_result) = tokio::join!(
+        fetcher.get_quickswap_quote(),
+        fetcher.get_sushiswap_quote(),
+        fetcher.get_block_number()
+    );
+    
+    let block_number = block_result?;
+    Span::current().record("block", block_number);
+    
+    // Record whichever quotes succeeded, even if the check itself fails
+    let check_id = uuid::Uuid::new_v4().to_string();
+    let checked_at = chrono::Utc::now();
+    let snapshots: Vec<PriceSnapshot> = [&quickswap_result, &sushiswap_result].into_iter()
+        .filter_map(|result| result.as_ref().ok())
+        .map(|quote| PriceSnapshot::from_quote(&check_id, checked_at, block_number, MONITORED_PAIR, quote))
+        .collect();
+    events.publish(BotEvent::Quotes {
+        block_number,
+        pair: MONITORED_PAIR.to_string(),
+        quotes: snapshots.clone(),
+    });
+    if let Err(e) = recorder.record(snapshots).await {
+        warn!("Failed to record price snapshots: {:#}", e);
+    }
+    
+    let quickswap_price = quickswap_result?.price;
+    let sushiswap_price = sushiswap_result?.price;
+    
+    let price_diff = (quickswap_price - sushiswap_price).abs();
+    debug!(quickswap_price, sushiswap_price, spread = price_diff, "Prices fetched");
+    metrics::get().spread.observe(price_diff);
+    
+    let detected = match detector.evaluate(quickswap_price, sushiswap_price) {
+        Ok(opportunity) => {
+            metrics::get().opportunities_detected.inc();
+            Some(opportunity)
+        },
+        Err(rejection) => {
+            metrics::get().opportunities_rejected.with_label_values(&[rejection.as_str()]).inc();
+            None
+        }
+    };
+    
+    let lifecycle_update = tracker.observe(MONITORED_PAIR, block_number, detected.as_ref());
+    for lifecycle in lifecycle_update.closed.iter().chain(lifecycle_update.current.iter()) {
+        metrics::timed_write("upsert_lifecycle", database.upsert_lifecycle(lifecycle)).await?;
+    }
+    
+    if let Some(mut opportunity) = detected {
+        opportunity.block_number = Some(block_number);
+        
+        info!(
+            buy_exchange = %opportunity.buy_exchange,
+            buy_price = opportunity.buy_price,
+            sell_exchange = %opportunity.sell_exchange,
+            sell_price = opportunity.sell_price,
+            estimated_profit = opportunity.estimated_profit,
+            price_difference = opportunity.sell_price - opportunity.buy_price,
+            lifecycle = lifecycle_update.current.as_ref().map(|lifecycle| tracing::field::display(&lifecycle.id)),
+            observations = lifecycle_update.current.as_ref().map(|lifecycle| lifecycle.observations),
+            "Arbitrage opportunity detected"
+        );
+        
+        logger.log_opportunity(&opportunity).await?;
+        metrics::timed_write("store_opportunity", database.store_opportunity(&opportunity)).await?;
+        notifier.notify(Alert::Opportunity(opportunity.clone()));
+        events.publish(BotEvent::Opportunity { opportunity });
+        
+        return Ok(CheckOutcome { block_number });
+    } else {
+        info!(quickswap_price, sushiswap_price, spread = price_diff, "No profitable opportunity");
+    }
+    
+    Ok(CheckOutcome { block_number })
+}
@@ -0,0 +1,511 @@
+use anyhow::{Result, Context};
+use ethers::{
+    abi::AbiDecode,
+    contract::abigen,
+    providers::{Middleware, StreamExt},
+    types::{Address, BlockId, Transaction, H256, U256},
+};
+use std::{str::FromStr, sync::Arc};
+use tokio::sync::{watch, Mutex};
+use tracing::{info, debug, warn};
+
+use crate::arbitrage::{ArbitrageDetector, PredictedOpportunity};
+use crate::config::{redact_url, AppConfig};
+use crate::control::RuntimeSettings;
+use crate::db::Store;
+use crate::metrics;
+use crate::rpc::{self, WsProvider};
+use crate::price_fetcher::usdc_to_float;
+
+abigen!(
+    IUniswapV2SwapRouter,
+    r#"[
+        function swapExactTokensForTokens(uint amountIn, uint amountOutMin, address[] calldata path, address to, uint deadline) external returns (uint[] memory amounts)
+        function swapTokensForExactTokens(uint amountOut, uint amountInMax, address[] calldata path, address to, uint deadline) external returns (uint[] memory amounts)
+        function swapExactETHForTokens(uint amountOutMin, address[] calldata path, address to, uint deadline) external payable returns (uint[] memory amounts)
+        function swapExactTokensForETH(uint amountIn, uint amountOutMin, address[] calldata path, address to, uint deadline) external returns (uint[] memory amounts)
+        function getAmountsOut(uint amountIn, address[] calldata path) external view returns (uint[] memory amounts)
+        function getAmountsIn(uint amountOut, address[] calldata path) external view returns (uint[] memory amounts)
+    ]"#
+);
+
+abigen!(
+    ISwapRouterV3,
+    r#"[
+        struct ExactInputSingleParams { address tokenIn; address tokenOut; uint24 fee; address recipient; uint256 deadline; uint256 amountIn; uint256 amountOutMinimum; uint160 sqrtPriceLimitX96; }
+        function exactInputSingle(ExactInputSingleParams calldata params) external payable returns (uint256 amountOut)
+    ]"#
+);
+
+abigen!(
+    IUniswapV2Factory,
+    r#"[
+        function getPair(address tokenA, address tokenB) external view returns (address pair)
+    ]"#
+);
+
+abigen!(
+    IUniswapV2Pair,
+    r#"[
+        function getReserves() external view returns (uint112 reserve0, uint112 reserve1, uint32 blockTimestampLast)
+        function token0() external view returns (address)
+    ]"#
+);
+
+/// Pending transactions fetched at once; the rest wait in the subscription
+const PENDING_FETCH_CONCURRENCY: usize = 32;
+
+/// A router call decoded from a pending transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodedSwap {
+    /// Exact input amount along a V2 path
+    ExactIn { amount_in: U256, path: Vec<Address> },
+    /// Exact output amount along a V2 path
+    ExactOut { amount_out: U256, path: Vec<Address> },
+    /// Single-pool V3 swap; V3 pools are not monitored, so these are only counted
+    V3Single { token_in: Address, token_out: Address, amount_in: U256 },
+}
+
+/// Decodes the router calls we care about from raw calldata.
+pub fn decode_swap(input: &[u8], value: U256) -> Option<DecodedSwap> {
+    if let Ok(call) = IUniswapV2SwapRouterCalls::decode(input) {
+        return match call {
+            IUniswapV2SwapRouterCalls::SwapExactTokensForTokens(c) => {
+                Some(DecodedSwap::ExactIn { amount_in: c.amount_in, path: c.path })
+            },
+            IUniswapV2SwapRouterCalls::SwapExactTokensForETH(c) => {
+                Some(DecodedSwap::ExactIn { amount_in: c.amount_in, path: c.path })
+            },
+            IUniswapV2SwapRouterCalls::SwapExactETHForTokens(c) => {
+                Some(DecodedSwap::ExactIn { amount_in: value, path: c.path })
+            },
+            IUniswapV2SwapRouterCalls::SwapTokensForExactTokens(c) => {
+                Some(DecodedSwap::ExactOut { amount_out: c.amount_out, path: c.path })
+            },
+            _ => None,
+        };
+    }
+
+    if let Ok(c) = ExactInputSingleCall::decode(input) {
+        return Some(DecodedSwap::V3Single {
+            token_in: c.params.token_in,
+            token_out: c.params.token_out,
+            amount_in: c.params.amount_in,
+        });
+    }
+
+    None
+}
+
+/// Pool reserves normalised to the monitored WETH/USDC pair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reserves {
+    pub weth: U256,
+    pub usdc: U256,
+}
+
+impl Reserves {
+    /// Applies one hop through the pool, `weth_in` selecting the direction.
+    pub fn apply_swap(&self, weth_in: bool, amount_in: U256, amount_out: U256) -> Reserves {
+        if weth_in {
+            Reserves { weth: self.weth + amount_in, usdc: self.usdc.saturating_sub(amount_out) }
+        } else {
+            Reserves { weth: self.weth.saturating_sub(amount_out), usdc: self.usdc + amount_in }
+        }
+    }
+
+    /// USDC received for selling `amount_in` WETH into this pool.
+    pub fn quote_weth_to_usdc(&self, amount_in: U256) -> U256 {
+        get_amount_out(amount_in, self.weth, self.usdc)
+    }
+}
+
+/// Uniswap V2 constant-product output amount with the 0.3% fee.
+pub fn get_amount_out(amount_in: U256, reserve_in: U256, reserve_out: U256) -> U256 {
+    if amount_in.is_zero() || reserve_in.is_zero() || reserve_out.is_zero() {
+        return U256::zero();
+    }
+
+    let amount_in_with_fee = amount_in * 997;
+    let numerator = amount_in_with_fee * reserve_out;
+    let denominator = reserve_in * 1000 + amount_in_with_fee;
+    numerator / denominator
+}
+
+struct MonitoredPool {
+    venue: String,
+    router: IUniswapV2SwapRouter<WsProvider>,
+    pair: IUniswapV2Pair<WsProvider>,
+    weth_is_token0: bool,
+}
+
+impl MonitoredPool {
+    async fn reserves(&self, block: Option<BlockId>) -> Result<Reserves> {
+        let mut call = self.pair.get_reserves();
+        if let Some(block) = block {
+            call = call.block(block);
+        }
+
+        let (reserve0, reserve1, _) = call.call().await
+            .with_context(|| format!("Failed to fetch {} pair reserves", self.venue))?;
+        let (reserve0, reserve1) = (U256::from(reserve0), U256::from(reserve1));
+
+        Ok(if self.weth_is_token0 {
+            Reserves { weth: reserve0, usdc: reserve1 }
+        } else {
+            Reserves { weth: reserve1, usdc: reserve0 }
+        })
+    }
+}
+
+pub struct MempoolWatcher {
+    provider: Arc<WsProvider>,
+    pools: Vec<MonitoredPool>,
+    weth: Address,
+    usdc: Address,
+    // Follows the control API and config reloads
+    settings: watch::Receiver<RuntimeSettings>,
+    detector: Arc<ArbitrageDetector>,
+    database: Arc<dyn Store>,
+    pending: Mutex<Vec<PredictedOpportunity>>,
+}
+
+impl MempoolWatcher {
+    pub async fn connect(
+        config: &AppConfig,
+        settings: watch::Receiver<RuntimeSettings>,
+        detector: Arc<ArbitrageDetector>,
+        database: Arc<dyn Store>,
+    ) -> Result<Self> {
+        info!("Connecting mempool watcher: {}", redact_url(&config.mempool.ws_url));
+
+        let provider = rpc::ws_provider(&config.mempool.ws_url).await?;
+        let provider = Arc::new(provider);
+
+        let weth = Address::from_str(&config.tokens.weth)
+            .with_context(|| format!("Invalid WETH address: {}", config.tokens.weth))?;
+        let usdc = Address::from_str(&config.tokens.usdc)
+            .with_context(|| format!("Invalid USDC address: {}", config.tokens.usdc))?;
+
+        let venues = [
+            ("QuickSwap", &config.dex.quickswap_router, &config.dex.quickswap_factory),
+            ("SushiSwap", &config.dex.sushiswap_router, &config.dex.sushiswap_factory),
+        ];
+
+        let mut pools = Vec::new();
+        for (venue, router, factory) in venues {
+            let router = Address::from_str(router)
+                .with_context(|| format!("Invalid {} router address: {}", venue, router))?;
+            let factory = Address::from_str(factory)
+                .with_context(|| format!("Invalid {} factory address: {}", venue, factory))?;
+
+            let pair_address = IUniswapV2Factory::new(factory, provider.clone())
+                .get_pair(weth, usdc)
+                .call()
+                .await
+                .with_context(|| format!("Failed to look up {} WETH/USDC pair", venue))?;
+            if pair_address.is_zero() {
+                anyhow::bail!("{} has no WETH/USDC pair", venue);
+            }
+
+            let pair = IUniswapV2Pair::new(pair_address, provider.clone());
+            let token0 = pair.token_0().call().await
+                .with_context(|| format!("Failed to read {} pair token0", venue))?;
+
+            debug!("{} WETH/USDC pair: {:?}", venue, pair_address);
+
+            pools.push(MonitoredPool {
+                venue: venue.to_string(),
+                router: IUniswapV2SwapRouter::new(router, provider.clone()),
+                pair,
+                weth_is_token0: token0 == weth,
+            });
+        }
+
+        Ok(Self {
+            provider,
+            pools,
+            weth,
+            usdc,
+            settings,
+            detector,
+            database,
+            pending: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// Follows pending transactions and new blocks until the subscription ends.
+    pub async fn run(&self) -> Result<()> {
+        let pending_txs = self.provider.subscribe_pending_txs().await
+            .with_context(|| "Failed to subscribe to pending transactions")?;
+        // Each hash costs a round trip, too slow to make one at a time at
+        // Polygon's pending volume
+        let mut pending_txs = pending_txs
+            .map(|hash| async move { (hash, self.handle_pending(hash).await) })
+            .buffer_unordered(PENDING_FETCH_CONCURRENCY);
+        let mut blocks = self.provider.subscribe_blocks().await
+            .with_context(|| "Failed to subscribe to new blocks")?;
+
+        info!("Mempool watcher subscribed to pending transactions");
+
+        loop {
+            tokio::select! {
+                Some((hash, result)) = pending_txs.next() => {
+                    if let Err(e) = result {
+                        debug!("Skipping pending transaction {:?}: {:#}", hash, e);
+                    }
+                },
+                Some(block) = blocks.next() => {
+                    if let Some(number) = block.number {
+                        if let Err(e) = self.resolve_predictions(number.as_u64()).await {
+                            warn!("Failed to resolve predictions for block {}: {:#}", number, e);
+                        }
+                    }
+                },
+                else => anyhow::bail!("Mempool subscriptions closed"),
+            }
+        }
+    }
+
+    async fn handle_pending(&self, hash: H256) -> Result<()> {
+        let Some(tx) = self.provider.get_transaction(hash).await? else {
+            return Ok(());
+        };
+        let Some(to) = tx.to else {
+            return Ok(());
+        };
+        let Some(pool_index) = self.pools.iter().position(|p| p.router.address() == to) else {
+            return Ok(());
+        };
+        let Some(swap) = decode_swap(&tx.input, tx.value) else {
+            return Ok(());
+        };
+
+        debug!("Pending {} swap {:?}: {:?}", self.pools[pool_index].venue, hash, swap);
+
+        self.predict(pool_index, &tx, swap).await
+    }
+
+    async fn predict(&self, pool_index: usize, tx: &Transaction, swap: DecodedSwap) -> Result<()> {
+        let pool = &self.pools[pool_index];
+
+        let (path, amounts) = match swap {
+            DecodedSwap::ExactIn { amount_in, path } => {
+                let amounts = pool.router.get_amounts_out(amount_in, path.clone()).call().await?;
+                (path, amounts)
+            },
+            DecodedSwap::ExactOut { amount_out, path } => {
+                let amounts = pool.router.get_amounts_in(amount_out, path.clone()).call().await?;
+                (path, amounts)
+            },
+            DecodedSwap::V3Single { .. } => return Ok(()),
+        };
+
+        // Find the hop through the monitored pair, if the path uses it at all
+        let Some(hop) = path.windows(2).position(|w| {
+            (w[0] == self.weth && w[1] == self.usdc) || (w[0] == self.usdc && w[1] == self.weth)
+        }) else {
+            return Ok(());
+        };
+        let weth_in = path[hop] == self.weth;
+
+        let trade_amount = self.trade_amount();
+        let current_block = self.provider.get_block_number().await?.as_u64();
+        let mut prices = Vec::with_capacity(self.pools.len());
+        for (i, other) in self.pools.iter().enumerate() {
+            let mut reserves = other.reserves(None).await?;
+            if i == pool_index {
+                reserves = reserves.apply_swap(weth_in, amounts[hop], amounts[hop + 1]);
+            }
+            prices.push(usdc_to_float(reserves.quote_weth_to_usdc(trade_amount)));
+        }
+
+        let Some(mut opportunity) = self.detector.detect_opportunity(prices[0], prices[1]) else {
+            return Ok(());
+        };
+        opportunity.block_number = Some(current_block + 1);
+
+        let prediction = PredictedOpportunity {
+            opportunity,
+            trigger_tx: format!("{:?}", tx.hash),
+            trigger_venue: pool.venue.clone(),
+            target_block: current_block + 1,
+            confirmed: None,
+            expired: false,
+        };
+
+        info!("Predicted opportunity from pending {} swap {}: buy {} sell {} profit {:.6} USDC",
+              prediction.trigger_venue, prediction.trigger_tx,
+              prediction.opportunity.buy_exchange, prediction.opportunity.sell_exchange,
+              prediction.opportunity.estimated_profit);
+
+        metrics::timed_write("store_prediction", self.database.store_prediction(&prediction)).await?;
+        self.pending.lock().await.push(prediction);
+
+        Ok(())
+    }
+
+    fn trade_amount(&self) -> U256 {
+        U256::from(self.settings.borrow().trade_amount_wei)
+    }
+
+    /// Re-quotes both pools at the target block of each due prediction and
+    /// records whether the predicted direction actually materialised.
+    /// Predictions whose target block was missed are recorded as expired.
+    async fn resolve_predictions(&self, block_number: u64) -> Result<()> {
+        let (due, expired) = take_due(&mut *self.pending.lock().await, block_number);
+
+        for prediction in expired {
+            debug!("Prediction {} expired: target block {} passed unobserved",
+                   prediction.opportunity.id, prediction.target_block);
+            metrics::timed_write("resolve_prediction", self.database.resolve_prediction(&prediction.opportunity.id, None)).await?;
+        }
+
+        if due.is_empty() {
+            return Ok(());
+        }
+
+        let block = Some(BlockId::from(block_number));
+        let trade_amount = self.trade_amount();
+        let mut prices = Vec::with_capacity(self.pools.len());
+        for pool in &self.pools {
+            let reserves = pool.reserves(block).await?;
+            prices.push(usdc_to_float(reserves.quote_weth_to_usdc(trade_amount)));
+        }
+        let actual = self.detector.detect_opportunity(prices[0], prices[1]);
+
+        for prediction in due {
+            let confirmed = actual.as_ref().is_some_and(|a| {
+                a.buy_exchange == prediction.opportunity.buy_exchange
+                    && a.sell_exchange == prediction.opportunity.sell_exchange
+            });
+
+            debug!("Prediction {} at block {}: confirmed={}",
+                   prediction.opportunity.id, block_number, confirmed);
+
+            metrics::timed_write("resolve_prediction", self.database.resolve_prediction(&prediction.opportunity.id, Some(confirmed))).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Removes the predictions that `block_number` settles from `pending`,
+/// returning those targeting it and those whose target has already passed.
+fn take_due(pending: &mut Vec<PredictedOpportunity>, block_number: u64) -> (Vec<PredictedOpportunity>, Vec<PredictedOpportunity>) {
+    let (settled, rest): (Vec<_>, Vec<_>) = pending.drain(..).partition(|p| p.target_block <= block_number);
+    *pending = rest;
+    settled.into_iter().partition(|p| p.target_block == block_number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::abi::AbiEncode;
+
+    fn weth() -> Address {
+        Address::repeat_byte(0x01)
+    }
+
+    fn usdc() -> Address {
+        Address::repeat_byte(0x02)
+    }
+
+    #[test]
+    fn test_decode_exact_tokens_for_tokens() {
+        let call = SwapExactTokensForTokensCall {
+            amount_in: U256::exp10(18),
+            amount_out_min: U256::zero(),
+            path: vec![weth(), usdc()],
+            to: Address::zero(),
+            deadline: U256::MAX,
+        };
+
+        let decoded = decode_swap(&call.encode(), U256::zero());
+        assert_eq!(decoded, Some(DecodedSwap::ExactIn {
+            amount_in: U256::exp10(18),
+            path: vec![weth(), usdc()],
+        }));
+    }
+
+    #[test]
+    fn test_decode_exact_eth_uses_value() {
+        let call = SwapExactETHForTokensCall {
+            amount_out_min: U256::zero(),
+            path: vec![weth(), usdc()],
+            to: Address::zero(),
+            deadline: U256::MAX,
+        };
+
+        let decoded = decode_swap(&call.encode(), U256::from(42));
+        assert_eq!(decoded, Some(DecodedSwap::ExactIn {
+            amount_in: U256::from(42),
+            path: vec![weth(), usdc()],
+        }));
+    }
+
+    #[test]
+    fn test_decode_exact_input_single() {
+        let call = ExactInputSingleCall {
+            params: ExactInputSingleParams {
+                token_in: usdc(),
+                token_out: weth(),
+                fee: 500,
+                recipient: Address::zero(),
+                deadline: U256::MAX,
+                amount_in: U256::from(1_000_000u64),
+                amount_out_minimum: U256::zero(),
+                sqrt_price_limit_x96: U256::zero(),
+            },
+        };
+
+        let decoded = decode_swap(&call.encode(), U256::zero());
+        assert_eq!(decoded, Some(DecodedSwap::V3Single {
+            token_in: usdc(),
+            token_out: weth(),
+            amount_in: U256::from(1_000_000u64),
+        }));
+    }
+
+    #[test]
+    fn test_decode_ignores_unrelated_calldata() {
+        assert_eq!(decode_swap(&[0xde, 0xad, 0xbe, 0xef], U256::zero()), None);
+    }
+
+    #[test]
+    fn test_missed_target_blocks_expire() {
+        let prediction = |target_block| PredictedOpportunity {
+            opportunity: crate::db::tests::opportunity(6.0),
+            trigger_tx: format!("0x{}", "ab".repeat(32)),
+            trigger_venue: "QuickSwap".to_string(),
+            target_block,
+            confirmed: None,
+            expired: false,
+        };
+        let mut pending = vec![prediction(98), prediction(100), prediction(101)];
+
+        let (due, expired) = take_due(&mut pending, 100);
+        assert_eq!(due.iter().map(|p| p.target_block).collect::<Vec<_>>(), vec![100]);
+        assert_eq!(expired.iter().map(|p| p.target_block).collect::<Vec<_>>(), vec![98]);
+        assert_eq!(pending.iter().map(|p| p.target_block).collect::<Vec<_>>(), vec![101]);
+    }
+
+    #[test]
+    fn test_get_amount_out_matches_v2_formula() {
+        // 1 WETH into a 100 WETH / 300,000 USDC pool
+        let out = get_amount_out(U256::exp10(18), U256::exp10(20), U256::from(300_000_000_000u64));
+        assert_eq!(out, U256::from(2_961_474_103u64));
+    }
+
+    #[test]
+    fn test_weth_sale_lowers_weth_price() {
+        let reserves = Reserves { weth: U256::exp10(20), usdc: U256::from(300_000_000_000u64) };
+        let amount_in = U256::exp10(19);
+        let amount_out = get_amount_out(amount_in, reserves.weth, reserves.usdc);
+
+        let after = reserves.apply_swap(true, amount_in, amount_out);
+
+        assert_eq!(after.weth, reserves.weth + amount_in);
+        assert!(after.quote_weth_to_usdc(U256::exp10(18)) < reserves.quote_weth_to_usdc(U256::exp10(18)));
+    }
+}
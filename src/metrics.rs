@@ -0,0 +1,187 @@
+use anyhow::Result;
+use prometheus::{
+    Encoder, Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, Opts, Registry,
+    TextEncoder,
+};
+use std::future::Future;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+use tracing::Instrument;
+
+/// Every metric the bot exports on `/metrics`.
+pub struct Metrics {
+    registry: Registry,
+    pub checks: IntCounterVec,
+    pub check_duration: Histogram,
+    pub quote_latency: HistogramVec,
+    pub quote_failures: IntCounterVec,
+    pub opportunities_detected: IntCounter,
+    pub opportunities_rejected: IntCounterVec,
+    pub spread: Histogram,
+    pub db_write_duration: HistogramVec,
+    pub rpc_calls: IntCounterVec,
+    pub rpc_duration: HistogramVec,
+    pub log_entries_dropped: IntCounter,
+    pub snapshots_dropped: IntCounter,
+    pub log_sink_errors: IntCounterVec,
+    pub alerts: IntCounterVec,
+    pub task_restarts: IntCounterVec,
+}
+
+static METRICS: LazyLock<Metrics> = LazyLock::new(|| Metrics::new().expect("metric definitions are valid"));
+
+/// The process-wide metrics, registered on first use.
+pub fn get() -> &'static Metrics {
+    &METRICS
+}
+
+const LATENCY_BUCKETS: &[f64] = &[0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+const SPREAD_BUCKETS: &[f64] = &[0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.0, 5.0, 10.0, 25.0, 50.0];
+
+impl Metrics {
+    fn new() -> Result<Self> {
+        let registry = Registry::new_custom(Some("arb".to_string()), None)?;
+
+        let checks = IntCounterVec::new(
+            Opts::new("checks_total", "Arbitrage checks run, by result"),
+            &["result"],
+        )?;
+        let check_duration = Histogram::with_opts(
+            HistogramOpts::new("check_duration_seconds", "Wall time of a full arbitrage check")
+                .buckets(LATENCY_BUCKETS.to_vec()),
+        )?;
+        let quote_latency = HistogramVec::new(
+            HistogramOpts::new("quote_latency_seconds", "Latency of successful venue quotes")
+                .buckets(LATENCY_BUCKETS.to_vec()),
+            &["venue"],
+        )?;
+        let quote_failures = IntCounterVec::new(
+            Opts::new("quote_failures_total", "Venue quotes that failed"),
+            &["venue"],
+        )?;
+        let opportunities_detected = IntCounter::new(
+            "opportunities_detected_total",
+            "Opportunities that cleared gas and the profit threshold",
+        )?;
+        let opportunities_rejected = IntCounterVec::new(
+            Opts::new("opportunities_rejected_total", "Checks that found no opportunity, by reason"),
+            &["reason"],
+        )?;
+        let spread = Histogram::with_opts(
+            HistogramOpts::new("spread_usdc", "Absolute price difference between venues per check")
+                .buckets(SPREAD_BUCKETS.to_vec()),
+        )?;
+        let db_write_duration = HistogramVec::new(
+            HistogramOpts::new("db_write_duration_seconds", "Latency of database writes")
+                .buckets(LATENCY_BUCKETS.to_vec()),
+            &["operation"],
+        )?;
+        let rpc_calls = IntCounterVec::new(
+            Opts::new("rpc_calls_total", "JSON-RPC calls, by method and result"),
+            &["method", "result"],
+        )?;
+        let rpc_duration = HistogramVec::new(
+            HistogramOpts::new("rpc_call_duration_seconds", "JSON-RPC call latency, by method")
+                .buckets(LATENCY_BUCKETS.to_vec()),
+            &["method"],
+        )?;
+
+        let log_entries_dropped = IntCounter::new(
+            "log_entries_dropped_total",
+            "Opportunities not logged because the sink queue was full",
+        )?;
+        let snapshots_dropped = IntCounter::new(
+            "snapshots_dropped_total",
+            "Price snapshots discarded because writes kept failing and the buffer was full",
+        )?;
+        let log_sink_errors = IntCounterVec::new(
+            Opts::new("log_sink_errors_total", "Batches an opportunity log sink failed to write, by sink"),
+            &["sink"],
+        )?;
+        let alerts = IntCounterVec::new(
+            Opts::new("alerts_total", "Alerts handled per channel, by result"),
+            &["channel", "result"],
+        )?;
+        let task_restarts = IntCounterVec::new(
+            Opts::new("task_restarts_total", "Supervised background tasks restarted after a crash, by task"),
+            &["task"],
+        )?;
+
+        registry.register(Box::new(checks.clone()))?;
+        registry.register(Box::new(check_duration.clone()))?;
+        registry.register(Box::new(quote_latency.clone()))?;
+        registry.register(Box::new(quote_failures.clone()))?;
+        registry.register(Box::new(opportunities_detected.clone()))?;
+        registry.register(Box::new(opportunities_rejected.clone()))?;
+        registry.register(Box::new(spread.clone()))?;
+        registry.register(Box::new(db_write_duration.clone()))?;
+        registry.register(Box::new(rpc_calls.clone()))?;
+        registry.register(Box::new(rpc_duration.clone()))?;
+        registry.register(Box::new(log_entries_dropped.clone()))?;
+        registry.register(Box::new(snapshots_dropped.clone()))?;
+        registry.register(Box::new(log_sink_errors.clone()))?;
+        registry.register(Box::new(alerts.clone()))?;
+        registry.register(Box::new(task_restarts.clone()))?;
+
+        Ok(Self {
+            registry,
+            checks,
+            check_duration,
+            quote_latency,
+            quote_failures,
+            opportunities_detected,
+            opportunities_rejected,
+            spread,
+            db_write_duration,
+            rpc_calls,
+            rpc_duration,
+            log_entries_dropped,
+            snapshots_dropped,
+            log_sink_errors,
+            alerts,
+            task_restarts,
+        })
+    }
+
+    pub fn observe_rpc_call(&self, method: &str, ok: bool, elapsed: Duration) {
+        let result = if ok { "ok" } else { "error" };
+        self.rpc_calls.with_label_values(&[method, result]).inc();
+        self.rpc_duration.with_label_values(&[method]).observe(elapsed.as_secs_f64());
+    }
+
+    /// Renders all metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> Result<String> {
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+        Ok(String::from_utf8(buffer)?)
+    }
+}
+
+/// Runs a database write in its own span, recording its latency under
+/// `operation`.
+pub async fn timed_write<T>(operation: &str, write: impl Future<Output = Result<T>>) -> Result<T> {
+    let started = Instant::now();
+    let result = write.instrument(tracing::info_span!("db_write", operation)).await;
+    get().db_write_duration
+        .with_label_values(&[operation])
+        .observe(started.elapsed().as_secs_f64());
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_renders_text_format() {
+        let metrics = get();
+        metrics.checks.with_label_values(&["ok"]).inc();
+        metrics.observe_rpc_call("eth_blockNumber", true, Duration::from_millis(20));
+        timed_write("store_opportunity", async { Ok(()) }).await.unwrap();
+
+        let text = metrics.render().unwrap();
+        assert!(text.contains("# TYPE arb_checks_total counter"));
+        assert!(text.contains(r#"arb_rpc_calls_total{method="eth_blockNumber",result="ok"}"#));
+        assert!(text.contains(r#"arb_db_write_duration_seconds_count{operation="store_opportunity"}"#));
+    }
+}
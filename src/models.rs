@@ -1,14 +1,338 @@
-use serde::{Serialize, Deserialize};
-use sqlx::FromRow;
-
-#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
-pub struct ArbitrageOpportunity {
-    pub id: String,
-    pub buy_exchange: String,
-    pub sell_exchange: String,
-    pub buy_price: f64,
-    pub sell_price: f64,
-    pub estimated_profit: f64,
-    pub profit_percentage: f64,
-    pub created_at: String,
-}
+//! Request and response bodies of the HTTP API. Handlers only build these
+//! types, so the OpenAPI document generated from them stays accurate.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::arbitrage::{ArbitrageOpportunity, PredictedOpportunity};
+use crate::control::{ControlAction, RuntimeSettings};
+use crate::db::{BucketInterval, OpportunityCursor, OpportunityQuery, OpportunitySort, ProfitBucket, SortOrder, VenueStanding};
+use crate::events::EventFilter;
+use crate::export::{ExportDataset, ExportFormat, ExportRequest};
+use crate::lifecycle::OpportunityLifecycle;
+use crate::status::StatusReport;
+
+/// Envelope of every successful JSON response.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ApiResponse<T> {
+    pub success: bool,
+    pub data: T,
+}
+
+impl<T> ApiResponse<T> {
+    pub fn ok(data: T) -> Self {
+        Self { success: true, data }
+    }
+}
+
+/// Envelope of every failed JSON response.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErrorResponse {
+    pub success: bool,
+    pub error: String,
+}
+
+impl ErrorResponse {
+    pub fn new(error: impl Into<String>) -> Self {
+        Self { success: false, error: error.into() }
+    }
+}
+
+/// Query string accepted by `GET /api/opportunities`.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct OpportunityParams {
+    /// Only opportunities seen at or after this time
+    pub from: Option<DateTime<Utc>>,
+    /// Only opportunities seen before this time
+    pub to: Option<DateTime<Utc>>,
+    pub pair: Option<String>,
+    pub buy_exchange: Option<String>,
+    pub sell_exchange: Option<String>,
+    pub min_profit: Option<f64>,
+    #[serde(default)]
+    #[param(inline)]
+    pub sort: OpportunitySort,
+    #[serde(default)]
+    #[param(inline)]
+    pub order: SortOrder,
+    /// `next_cursor` from the previous page
+    pub cursor: Option<String>,
+    /// Page size, 1 to 500; defaults to 50
+    pub limit: Option<i64>,
+}
+
+const MAX_PAGE_SIZE: i64 = 500;
+
+impl OpportunityParams {
+    pub fn into_query(self) -> anyhow::Result<OpportunityQuery> {
+        let cursor = self.cursor
+            .map(|token| OpportunityCursor::decode(&token, self.sort))
+            .transpose()?;
+
+        Ok(OpportunityQuery {
+            from: self.from,
+            to: self.to,
+            pair: self.pair,
+            buy_exchange: self.buy_exchange,
+            sell_exchange: self.sell_exchange,
+            min_profit: self.min_profit,
+            sort: self.sort,
+            order: self.order,
+            cursor,
+            limit: self.limit.unwrap_or(50).clamp(1, MAX_PAGE_SIZE),
+        })
+    }
+}
+
+/// An opportunity as listed by `GET /api/opportunities`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OpportunityView {
+    pub id: String,
+    pub timestamp: DateTime<Utc>,
+    pub pair: String,
+    pub block_number: Option<u64>,
+    pub buy_exchange: String,
+    pub sell_exchange: String,
+    pub buy_price: f64,
+    pub sell_price: f64,
+    pub estimated_profit: f64,
+    pub profit_percentage: f64,
+    /// Same as `timestamp`, kept for older dashboard builds
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<ArbitrageOpportunity> for OpportunityView {
+    fn from(opportunity: ArbitrageOpportunity) -> Self {
+        Self {
+            id: opportunity.id,
+            timestamp: opportunity.timestamp,
+            pair: opportunity.pair,
+            block_number: opportunity.block_number,
+            buy_exchange: opportunity.buy_exchange,
+            sell_exchange: opportunity.sell_exchange,
+            buy_price: opportunity.buy_price,
+            sell_price: opportunity.sell_price,
+            estimated_profit: opportunity.estimated_profit,
+            profit_percentage: opportunity.profit_percentage,
+            created_at: opportunity.timestamp,
+        }
+    }
+}
+
+/// One page of opportunities. Paging fields sit beside `data` rather than
+/// inside it so the list stays directly usable by the dashboard.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OpportunityPageResponse {
+    pub success: bool,
+    pub data: Vec<OpportunityView>,
+    /// Opportunities matching the filters across all pages
+    pub total: i64,
+    /// Pass as `cursor` to fetch the next page; absent on the last page
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StatsSummary {
+    /// Every recorded detection, one per check the gap was seen in
+    pub total_opportunities: i64,
+    /// Opportunities tracked as lifecycles, counting a gap that persists
+    /// across checks once. Detections from before lifecycle tracking have none.
+    pub distinct_opportunities: i64,
+    pub average_profit: f64,
+    pub best_profit: f64,
+    pub runtime: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StatusSummary {
+    #[serde(flatten)]
+    pub report: StatusReport,
+    /// Human-readable summary of the loop state
+    pub status: String,
+    pub paused: bool,
+    pub settings: RuntimeSettings,
+    pub recent_changes: Vec<ControlAction>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LivenessResponse {
+    /// `ok` or `stalled`
+    pub status: String,
+    pub last_check: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReadinessResponse {
+    /// `ready` or `not_ready`
+    pub status: String,
+    pub last_success: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PredictionSummary {
+    pub total_predictions: i64,
+    pub confirmed: i64,
+    pub refuted: i64,
+    /// Target block passed unobserved, so never scored
+    pub expired: i64,
+    /// Percentage of resolved predictions that were confirmed
+    pub confirmation_rate: f64,
+    pub recent: Vec<PredictedOpportunity>,
+}
+
+/// A lifecycle with its derived durations.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LifecycleView {
+    pub id: String,
+    pub pair: String,
+    pub buy_exchange: String,
+    pub sell_exchange: String,
+    pub first_seen_block: u64,
+    pub last_seen_block: u64,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    pub duration_seconds: i64,
+    pub duration_blocks: u64,
+    pub peak_profit: f64,
+    pub peak_profit_percentage: f64,
+    pub observations: u64,
+    pub open: bool,
+    pub closed_at: Option<DateTime<Utc>>,
+}
+
+impl From<OpportunityLifecycle> for LifecycleView {
+    fn from(lifecycle: OpportunityLifecycle) -> Self {
+        Self {
+            duration_seconds: lifecycle.duration_seconds(),
+            duration_blocks: lifecycle.duration_blocks(),
+            open: lifecycle.is_open(),
+            id: lifecycle.id,
+            pair: lifecycle.pair,
+            buy_exchange: lifecycle.buy_exchange,
+            sell_exchange: lifecycle.sell_exchange,
+            first_seen_block: lifecycle.first_seen_block,
+            last_seen_block: lifecycle.last_seen_block,
+            first_seen_at: lifecycle.first_seen_at,
+            last_seen_at: lifecycle.last_seen_at,
+            peak_profit: lifecycle.peak_profit,
+            peak_profit_percentage: lifecycle.peak_profit_percentage,
+            observations: lifecycle.observations,
+            closed_at: lifecycle.closed_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LifecycleSummary {
+    pub total_lifecycles: i64,
+    pub open_lifecycles: i64,
+    pub average_duration_seconds: f64,
+    pub average_observations: f64,
+    pub recent: Vec<LifecycleView>,
+}
+
+/// Query string accepted by the analytics endpoints. Without `from`, hourly
+/// buckets cover the last day and daily buckets the last 30 days.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AnalyticsParams {
+    #[serde(default)]
+    #[param(inline)]
+    pub interval: BucketInterval,
+    pub from: Option<DateTime<Utc>>,
+    /// Defaults to now
+    pub to: Option<DateTime<Utc>>,
+}
+
+impl AnalyticsParams {
+    pub fn range(&self) -> (DateTime<Utc>, DateTime<Utc>) {
+        let to = self.to.unwrap_or_else(Utc::now);
+        let default_span = match self.interval {
+            BucketInterval::Hour => Duration::days(1),
+            BucketInterval::Day => Duration::days(30),
+        };
+        (self.from.unwrap_or(to - default_span), to)
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProfitBuckets {
+    pub interval: BucketInterval,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub buckets: Vec<ProfitBucket>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct VenueLeaderboard {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub standings: Vec<VenueStanding>,
+}
+
+/// Query string accepted by `GET /api/stream`.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct StreamParams {
+    /// Comma-separated event types: `opportunity`, `quotes`, `status`
+    pub types: Option<String>,
+    pub pair: Option<String>,
+    pub venue: Option<String>,
+    pub min_profit: Option<f64>,
+}
+
+impl StreamParams {
+    pub fn into_filter(self) -> anyhow::Result<EventFilter> {
+        let types = self.types
+            .map(|types| types.split(',').map(|t| t.trim().parse()).collect::<anyhow::Result<Vec<_>>>())
+            .transpose()?;
+
+        Ok(EventFilter {
+            types,
+            pair: self.pair,
+            venue: self.venue,
+            min_profit: self.min_profit,
+        })
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PauseState {
+    pub paused: bool,
+    /// False when the loop was already in the requested state
+    pub changed: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CheckRequested {
+    pub requested: bool,
+}
+
+/// Query string accepted by `GET /api/export`.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ExportParams {
+    #[param(inline)]
+    pub dataset: ExportDataset,
+    #[serde(default)]
+    #[param(inline)]
+    pub format: ExportFormat,
+    /// Defaults to the earliest record
+    pub from: Option<DateTime<Utc>>,
+    /// Defaults to now
+    pub to: Option<DateTime<Utc>>,
+}
+
+impl ExportParams {
+    pub fn into_request(self) -> anyhow::Result<ExportRequest> {
+        ExportRequest::new(
+            self.dataset,
+            self.format,
+            self.from.unwrap_or(DateTime::UNIX_EPOCH),
+            self.to.unwrap_or_else(Utc::now),
+        )
+    }
+}
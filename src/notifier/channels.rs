@@ -0,0 +1,208 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use lettre::message::{header::ContentType, Mailbox};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use serde_json::Value;
+
+use super::{AlertChannel, AlertMessage};
+use crate::config::{EmailChannelConfig, SmtpTls};
+
+/// Discord rejects messages longer than this
+const DISCORD_MAX_LENGTH: usize = 2000;
+
+/// POSTs `{"subject", "text", "fields"}` as JSON.
+pub struct WebhookChannel {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl WebhookChannel {
+    pub fn new(client: reqwest::Client, url: &str) -> Self {
+        Self { client, url: url.to_string() }
+    }
+}
+
+#[async_trait]
+impl AlertChannel for WebhookChannel {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    async fn send(&self, message: &AlertMessage) -> Result<()> {
+        self.client
+            .post(&self.url)
+            .json(&serde_json::json!({
+                "subject": message.subject,
+                "text": message.text,
+                "fields": message.fields,
+            }))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+/// Bot API `sendMessage` to a single chat.
+pub struct TelegramChannel {
+    client: reqwest::Client,
+    endpoint: String,
+    chat_id: String,
+}
+
+impl TelegramChannel {
+    pub fn new(client: reqwest::Client, api_url: &str, bot_token: &str, chat_id: &str) -> Self {
+        Self {
+            client,
+            endpoint: format!("{}/bot{}/sendMessage", api_url.trim_end_matches('/'), bot_token),
+            chat_id: chat_id.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl AlertChannel for TelegramChannel {
+    fn name(&self) -> &'static str {
+        "telegram"
+    }
+
+    async fn send(&self, message: &AlertMessage) -> Result<()> {
+        // The endpoint embeds the bot token, so it is kept out of errors
+        let response: Value = self.client
+            .post(&self.endpoint)
+            .json(&serde_json::json!({
+                "chat_id": self.chat_id,
+                "text": message.text,
+                "disable_web_page_preview": true,
+            }))
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!("Telegram request failed: {}", e.without_url()))?
+            .json()
+            .await
+            .map_err(|e| anyhow::anyhow!("Invalid Telegram response: {}", e.without_url()))?;
+
+        if response["ok"] != Value::Bool(true) {
+            anyhow::bail!("Telegram rejected the message: {}", response["description"]);
+        }
+        Ok(())
+    }
+}
+
+/// Discord incoming webhook.
+pub struct DiscordChannel {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl DiscordChannel {
+    pub fn new(client: reqwest::Client, webhook_url: &str) -> Self {
+        Self { client, url: webhook_url.to_string() }
+    }
+}
+
+#[async_trait]
+impl AlertChannel for DiscordChannel {
+    fn name(&self) -> &'static str {
+        "discord"
+    }
+
+    async fn send(&self, message: &AlertMessage) -> Result<()> {
+        let content: String = message.text.chars().take(DISCORD_MAX_LENGTH).collect();
+        self.client
+            .post(&self.url)
+            .json(&serde_json::json!({ "content": content }))
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!("Discord request failed: {}", e.without_url()))?
+            .error_for_status()
+            .map_err(|e| anyhow::anyhow!("Discord rejected the message: {}", e.without_url()))?;
+        Ok(())
+    }
+}
+
+/// Plain-text mail through an SMTP relay.
+pub struct EmailChannel {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    to: Vec<Mailbox>,
+}
+
+impl EmailChannel {
+    pub fn new(config: &EmailChannelConfig) -> Result<Self> {
+        let builder = match config.tls {
+            SmtpTls::Starttls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.smtp_host)?,
+            SmtpTls::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.smtp_host)?,
+            SmtpTls::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.smtp_host),
+        };
+        let builder = match (&config.username, &config.password) {
+            (Some(username), Some(password)) => builder.credentials(Credentials::new(username.clone(), password.clone())),
+            _ => builder,
+        };
+
+        let to = config.to.iter()
+            .map(|address| address.parse().with_context(|| format!("Invalid alert recipient: {}", address)))
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            transport: builder.port(config.smtp_port).build(),
+            from: config.from.parse().with_context(|| format!("Invalid alert sender: {}", config.from))?,
+            to,
+        })
+    }
+
+    fn build(&self, message: &AlertMessage) -> Result<Message> {
+        let mut builder = Message::builder()
+            .from(self.from.clone())
+            .subject(&message.subject)
+            .header(ContentType::TEXT_PLAIN);
+        for recipient in &self.to {
+            builder = builder.to(recipient.clone());
+        }
+        Ok(builder.body(message.text.clone())?)
+    }
+}
+
+#[async_trait]
+impl AlertChannel for EmailChannel {
+    fn name(&self) -> &'static str {
+        "email"
+    }
+
+    async fn send(&self, message: &AlertMessage) -> Result<()> {
+        self.transport.send(self.build(message)?).await
+            .with_context(|| "SMTP delivery failed")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_email_message() {
+        let channel = EmailChannel::new(&EmailChannelConfig {
+            smtp_host: "localhost".to_string(),
+            smtp_port: 25,
+            tls: SmtpTls::None,
+            username: None,
+            password: None,
+            from: "Arbitrage Bot <bot@example.org>".to_string(),
+            to: vec!["ops@example.org".to_string(), "desk@example.org".to_string()],
+        }).unwrap();
+
+        let message = channel.build(&AlertMessage {
+            subject: "Arbitrage bot alert: rpc_failures".to_string(),
+            text: "3 consecutive checks failed".to_string(),
+            fields: Value::Null,
+        }).unwrap();
+        let raw = String::from_utf8(message.formatted()).unwrap();
+
+        assert!(raw.contains("From: \"Arbitrage Bot\" <bot@example.org>"));
+        assert!(raw.contains("To: ops@example.org, desk@example.org"));
+        assert!(raw.contains("Subject: Arbitrage bot alert: rpc_failures"));
+        assert!(raw.ends_with("3 consecutive checks failed"));
+    }
+}
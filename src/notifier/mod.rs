@@ -0,0 +1,425 @@
+//! Sends alerts about opportunities and operational problems to the
+//! channels configured under `[alerts]`. Alerts are queued and delivered by
+//! a background task, so a slow channel never delays a check.
+
+mod channels;
+
+pub use channels::{DiscordChannel, EmailChannel, TelegramChannel, WebhookChannel};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tracing::{debug, error, info, warn};
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+use crate::arbitrage::ArbitrageOpportunity;
+use crate::config::{AlertChannelConfig, AlertChannelKind, AlertConfig};
+use crate::metrics;
+
+/// Alerts queued for delivery; further ones are dropped until it catches up.
+const QUEUE_CAPACITY: usize = 256;
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Placeholders available to opportunity templates.
+pub const OPPORTUNITY_FIELDS: &[&str] = &[
+    "id", "pair", "buy_exchange", "sell_exchange", "buy_price", "sell_price",
+    "profit", "profit_percentage", "block_number", "timestamp",
+];
+
+/// Placeholders available to operational templates.
+pub const OPERATIONAL_FIELDS: &[&str] = &["event", "message", "timestamp"];
+
+#[derive(Debug, Clone)]
+pub enum Alert {
+    Opportunity(ArbitrageOpportunity),
+    Operational { event: &'static str, message: String },
+}
+
+impl Alert {
+    /// Alerts with the same key are duplicates of each other.
+    fn dedup_key(&self) -> String {
+        match self {
+            Alert::Opportunity(opportunity) => format!(
+                "opportunity:{}:{}:{}", opportunity.pair, opportunity.buy_exchange, opportunity.sell_exchange),
+            Alert::Operational { event, .. } => format!("operational:{}", event),
+        }
+    }
+
+    fn subject(&self) -> String {
+        match self {
+            Alert::Opportunity(opportunity) => format!(
+                "Arbitrage opportunity on {}: {:.2} USDC", opportunity.pair, opportunity.estimated_profit),
+            Alert::Operational { event, .. } => format!("Arbitrage bot alert: {}", event),
+        }
+    }
+
+    fn fields(&self) -> Vec<(&'static str, String)> {
+        match self {
+            Alert::Opportunity(opportunity) => vec![
+                ("id", opportunity.id.clone()),
+                ("pair", opportunity.pair.clone()),
+                ("buy_exchange", opportunity.buy_exchange.clone()),
+                ("sell_exchange", opportunity.sell_exchange.clone()),
+                ("buy_price", format!("{:.6}", opportunity.buy_price)),
+                ("sell_price", format!("{:.6}", opportunity.sell_price)),
+                ("profit", format!("{:.2}", opportunity.estimated_profit)),
+                ("profit_percentage", format!("{:.3}", opportunity.profit_percentage)),
+                ("block_number", opportunity.block_number.map(|block| block.to_string()).unwrap_or_default()),
+                ("timestamp", opportunity.timestamp.to_rfc3339()),
+            ],
+            Alert::Operational { event, message } => vec![
+                ("event", event.to_string()),
+                ("message", message.clone()),
+                ("timestamp", chrono::Utc::now().to_rfc3339()),
+            ],
+        }
+    }
+}
+
+/// A rendered alert as handed to a channel.
+#[derive(Debug, Clone)]
+pub struct AlertMessage {
+    pub subject: String,
+    pub text: String,
+    /// Template fields, for channels that post structured data
+    pub fields: Value,
+}
+
+/// A destination for alerts.
+#[async_trait]
+pub trait AlertChannel: Send + Sync {
+    /// Short kind name used in logs and metrics
+    fn name(&self) -> &'static str;
+
+    async fn send(&self, message: &AlertMessage) -> Result<()>;
+}
+
+/// Fills `{{field}}` placeholders; unknown ones are left as written.
+pub fn render(template: &str, fields: &[(&str, String)]) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        let Some(length) = rest[start..].find("}}") else { break };
+        let name = rest[start + 2..start + length].trim();
+
+        rendered.push_str(&rest[..start]);
+        match fields.iter().find(|(field, _)| *field == name) {
+            Some((_, value)) => rendered.push_str(value),
+            None => rendered.push_str(&rest[start..start + length + 2]),
+        }
+        rest = &rest[start + length + 2..];
+    }
+
+    rendered.push_str(rest);
+    rendered
+}
+
+/// Fails on placeholders that `render` would not be able to fill.
+pub fn check_template(template: &str, fields: &[&str]) -> Result<()> {
+    for placeholder in template.split("{{").skip(1) {
+        let name = placeholder.split("}}").next().unwrap_or_default().trim();
+        if !fields.contains(&name) {
+            anyhow::bail!("Unknown placeholder {{{{{}}}}}; available: {}", name, fields.join(", "));
+        }
+    }
+    Ok(())
+}
+
+/// Sliding one-minute window of delivered alerts.
+struct RateLimiter {
+    max_per_minute: usize,
+    sent: VecDeque<Instant>,
+}
+
+impl RateLimiter {
+    fn allow(&mut self, now: Instant) -> bool {
+        if self.max_per_minute == 0 {
+            return true;
+        }
+        while self.sent.front().is_some_and(|sent| now.duration_since(*sent) >= Duration::from_secs(60)) {
+            self.sent.pop_front();
+        }
+        if self.sent.len() >= self.max_per_minute {
+            return false;
+        }
+        self.sent.push_back(now);
+        true
+    }
+}
+
+/// A channel with the filters and limits configured for it.
+pub struct Route {
+    channel: Box<dyn AlertChannel>,
+    min_profit: f64,
+    operational: bool,
+    template: String,
+    operational_template: String,
+    dedup_window: Duration,
+    limiter: RateLimiter,
+    last_sent: HashMap<String, Instant>,
+}
+
+impl Route {
+    pub fn new(channel: Box<dyn AlertChannel>, config: &AlertChannelConfig, alerts: &AlertConfig) -> Self {
+        Self {
+            channel,
+            min_profit: config.min_profit,
+            operational: config.operational,
+            template: config.template.clone().unwrap_or_else(|| alerts.opportunity_template.clone()),
+            operational_template: alerts.operational_template.clone(),
+            dedup_window: Duration::from_secs(alerts.dedup_window_seconds),
+            limiter: RateLimiter { max_per_minute: config.max_per_minute, sent: VecDeque::new() },
+            last_sent: HashMap::new(),
+        }
+    }
+
+    fn accepts(&self, alert: &Alert) -> bool {
+        match alert {
+            Alert::Opportunity(opportunity) => opportunity.estimated_profit >= self.min_profit,
+            Alert::Operational { .. } => self.operational,
+        }
+    }
+
+    async fn deliver(&mut self, alert: &Alert) {
+        if !self.accepts(alert) {
+            return;
+        }
+
+        let now = Instant::now();
+        let key = alert.dedup_key();
+        let result = if self.last_sent.get(&key).is_some_and(|sent| now.duration_since(*sent) < self.dedup_window) {
+            "duplicate"
+        } else if !self.limiter.allow(now) {
+            "rate_limited"
+        } else {
+            let fields = alert.fields();
+            let template = match alert {
+                Alert::Opportunity(_) => &self.template,
+                Alert::Operational { .. } => &self.operational_template,
+            };
+            let message = AlertMessage {
+                subject: alert.subject(),
+                text: render(template, &fields),
+                fields: fields.iter().map(|(name, value)| (name.to_string(), Value::from(value.as_str()))).collect(),
+            };
+
+            match self.channel.send(&message).await {
+                Ok(()) => {
+                    self.last_sent.insert(key, now);
+                    "sent"
+                },
+                Err(e) => {
+                    error!("Failed to send alert to {} channel: {:#}", self.channel.name(), e);
+                    "failed"
+                },
+            }
+        };
+
+        debug!("Alert {} on {} channel: {}", alert.dedup_key(), self.channel.name(), result);
+        metrics::get().alerts.with_label_values(&[self.channel.name(), result]).inc();
+    }
+}
+
+pub struct Notifier {
+    sender: mpsc::Sender<Alert>,
+    rpc_failure_threshold: u64,
+    failure_alerted: AtomicBool,
+}
+
+impl Notifier {
+    pub fn new(config: &AlertConfig) -> Result<Self> {
+        let client = reqwest::Client::builder().timeout(HTTP_TIMEOUT).build()?;
+
+        let mut routes = Vec::with_capacity(config.channels.len());
+        for channel_config in &config.channels {
+            let channel: Box<dyn AlertChannel> = match &channel_config.kind {
+                AlertChannelKind::Webhook { url } => Box::new(WebhookChannel::new(client.clone(), url)),
+                AlertChannelKind::Telegram { bot_token, chat_id, api_url } => {
+                    Box::new(TelegramChannel::new(client.clone(), api_url, bot_token, chat_id))
+                },
+                AlertChannelKind::Discord { webhook_url } => Box::new(DiscordChannel::new(client.clone(), webhook_url)),
+                AlertChannelKind::Email(email) => Box::new(EmailChannel::new(email)?),
+            };
+            routes.push(Route::new(channel, channel_config, config));
+        }
+        info!("Alert channels configured: {}", routes.len());
+
+        Ok(Self::with_routes(routes, config.rpc_failure_threshold))
+    }
+
+    pub fn with_routes(mut routes: Vec<Route>, rpc_failure_threshold: u64) -> Self {
+        let (sender, mut receiver) = mpsc::channel::<Alert>(QUEUE_CAPACITY);
+        tokio::spawn(async move {
+            while let Some(alert) = receiver.recv().await {
+                for route in routes.iter_mut() {
+                    route.deliver(&alert).await;
+                }
+            }
+        });
+
+        Self { sender, rpc_failure_threshold, failure_alerted: AtomicBool::new(false) }
+    }
+
+    /// Queues an alert for every channel that accepts it.
+    pub fn notify(&self, alert: Alert) {
+        if let Err(mpsc::error::TrySendError::Full(alert)) = self.sender.try_send(alert) {
+            warn!("Alert queue is full, dropping {}", alert.dedup_key());
+        }
+    }
+
+    /// Alerts once when consecutive check failures reach the threshold, and
+    /// again when checks recover after such an alert.
+    pub fn record_check(&self, consecutive_errors: u64, error: Option<&anyhow::Error>) {
+        if self.rpc_failure_threshold == 0 {
+            return;
+        }
+
+        match error {
+            Some(error) if consecutive_errors == self.rpc_failure_threshold => {
+                self.failure_alerted.store(true, Ordering::Relaxed);
+                self.notify(Alert::Operational {
+                    event: "rpc_failures",
+                    message: format!("{} consecutive checks failed, last error: {:#}", consecutive_errors, error),
+                });
+            },
+            None if self.failure_alerted.swap(false, Ordering::Relaxed) => {
+                self.notify(Alert::Operational {
+                    event: "rpc_recovered",
+                    message: "Checks are succeeding again".to_string(),
+                });
+            },
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::tests::opportunity;
+    use actix_web::{post, web, App, HttpResponse, HttpServer};
+    use std::sync::Mutex;
+
+    type Received = web::Data<Mutex<Vec<(String, Value)>>>;
+
+    #[post("/{path:.*}")]
+    async fn capture(received: Received, path: web::Path<String>, body: web::Json<Value>) -> HttpResponse {
+        received.lock().unwrap().push((path.into_inner(), body.into_inner()));
+        if received.lock().unwrap().last().unwrap().0.ends_with("sendMessage") {
+            HttpResponse::Ok().json(serde_json::json!({"ok": true, "result": {}}))
+        } else {
+            HttpResponse::NoContent().finish()
+        }
+    }
+
+    /// Local stand-in for the webhook, Telegram and Discord endpoints.
+    async fn mock_server() -> (String, Received) {
+        let received: Received = web::Data::new(Mutex::new(Vec::new()));
+        let server_data = received.clone();
+        let server = HttpServer::new(move || App::new().app_data(server_data.clone()).service(capture))
+            .workers(1)
+            .bind(("127.0.0.1", 0))
+            .unwrap();
+        let addr = server.addrs()[0];
+        actix_web::rt::spawn(server.run());
+        (format!("http://{}", addr), received)
+    }
+
+    fn channel_config(kind: AlertChannelKind, min_profit: f64) -> AlertChannelConfig {
+        AlertChannelConfig { kind, min_profit, max_per_minute: 10, template: None, operational: true }
+    }
+
+    /// Waits until the background task has handled everything queued.
+    async fn received_after(received: &Received, count: usize) -> Vec<(String, Value)> {
+        for _ in 0..100 {
+            if received.lock().unwrap().len() >= count {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        received.lock().unwrap().clone()
+    }
+
+    #[test]
+    fn test_templates() {
+        let fields = [("pair", "WETH/USDC".to_string()), ("profit", "12.50".to_string())];
+        assert_eq!(render("{{pair}} +{{ profit }} {{missing}} {{", &fields), "WETH/USDC +12.50 {{missing}} {{");
+        assert!(check_template("{{pair}} {{profit}}", OPPORTUNITY_FIELDS).is_ok());
+        assert!(check_template("{{pair}} {{profti}}", OPPORTUNITY_FIELDS).is_err());
+    }
+
+    #[actix_web::test]
+    async fn test_channels_filter_and_deduplicate() {
+        let (base_url, received) = mock_server().await;
+        let alerts = AlertConfig::default();
+        let client = reqwest::Client::new();
+
+        let webhook = channel_config(AlertChannelKind::Webhook { url: format!("{}/hook", base_url) }, 0.0);
+        let telegram = AlertChannelConfig {
+            template: Some("{{pair}} +{{profit}}".to_string()),
+            ..channel_config(AlertChannelKind::Telegram {
+                bot_token: "123:abc".to_string(),
+                chat_id: "42".to_string(),
+                api_url: base_url.clone(),
+            }, 5.0)
+        };
+        let discord = AlertChannelConfig {
+            operational: false,
+            ..channel_config(AlertChannelKind::Discord { webhook_url: format!("{}/discord", base_url) }, 0.0)
+        };
+        let notifier = Notifier::with_routes(vec![
+            Route::new(Box::new(WebhookChannel::new(client.clone(), &format!("{}/hook", base_url))), &webhook, &alerts),
+            Route::new(Box::new(TelegramChannel::new(client.clone(), &base_url, "123:abc", "42")), &telegram, &alerts),
+            Route::new(Box::new(DiscordChannel::new(client, &format!("{}/discord", base_url))), &discord, &alerts),
+        ], 2);
+
+        // Below the Telegram threshold, then a repeat of the same route
+        notifier.notify(Alert::Opportunity(opportunity(3.0)));
+        notifier.notify(Alert::Opportunity(opportunity(8.0)));
+        let error = anyhow::anyhow!("rpc timeout");
+        notifier.record_check(1, Some(&error));
+        notifier.record_check(2, Some(&error));
+        notifier.record_check(3, Some(&error));
+
+        let received = received_after(&received, 5).await;
+        let paths: Vec<&str> = received.iter().map(|(path, _)| path.as_str()).collect();
+        assert_eq!(paths, ["hook", "discord", "bot123:abc/sendMessage", "hook", "bot123:abc/sendMessage"]);
+
+        assert_eq!(received[0].1["fields"]["profit"], "3.00");
+        assert!(received[1].1["content"].as_str().unwrap().contains("estimated 3.00 USDC"));
+        assert_eq!(received[2].1["chat_id"], "42");
+        assert_eq!(received[2].1["text"], "WETH/USDC +8.00");
+        assert_eq!(received[3].1["subject"], "Arbitrage bot alert: rpc_failures");
+        assert_eq!(received[3].1["text"], "rpc_failures: 2 consecutive checks failed, last error: rpc timeout");
+    }
+
+    #[actix_web::test]
+    async fn test_rate_limit() {
+        let (base_url, received) = mock_server().await;
+        let alerts = AlertConfig { dedup_window_seconds: 0, ..AlertConfig::default() };
+        let telegram = AlertChannelConfig {
+            max_per_minute: 2,
+            template: Some("{{pair}} +{{profit}}".to_string()),
+            ..channel_config(AlertChannelKind::Telegram {
+                bot_token: "123:abc".to_string(),
+                chat_id: "42".to_string(),
+                api_url: base_url.clone(),
+            }, 5.0)
+        };
+        let channel = TelegramChannel::new(reqwest::Client::new(), &base_url, "123:abc", "42");
+        let notifier = Notifier::with_routes(vec![Route::new(Box::new(channel), &telegram, &alerts)], 0);
+
+        for profit in [6.0, 7.0, 9.0] {
+            notifier.notify(Alert::Opportunity(opportunity(profit)));
+        }
+
+        let received = received_after(&received, 2).await;
+        let texts: Vec<&Value> = received.iter().map(|(_, body)| &body["text"]).collect();
+        assert_eq!(texts, ["WETH/USDC +6.00", "WETH/USDC +7.00"]);
+    }
+}
@@ -0,0 +1,236 @@
+use actix_web::{get, HttpResponse, Responder};
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityRequirement, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+
+use crate::api;
+
+/// OpenAPI document for every HTTP route, generated from the handler
+/// annotations and the types in `models`.
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Polygon arbitrage bot API"),
+    paths(
+        api::get_opportunities,
+        api::get_stats,
+        api::get_status,
+        api::healthz,
+        api::readyz,
+        api::get_metrics,
+        api::get_predictions,
+        api::get_lifecycles,
+        api::get_snapshots,
+        api::get_profit_buckets,
+        api::get_venue_leaderboard,
+        api::export_data,
+        api::stream_ws,
+        api::stream_sse,
+        api::pause_monitoring,
+        api::resume_monitoring,
+        api::trigger_check,
+        api::update_settings,
+        api::get_control_audit,
+        get_openapi,
+        swagger_ui,
+    ),
+    modifiers(&ApiKeySecurity),
+    tags(
+        (name = "opportunities", description = "Detected and predicted opportunities"),
+        (name = "analytics", description = "Aggregates over opportunities and quotes"),
+        (name = "monitoring", description = "Health and state of the monitoring loop"),
+        (name = "streams", description = "Live events over WebSocket and SSE"),
+        (name = "control", description = "Runtime control; requires an admin-scope key"),
+        (name = "docs", description = "This document"),
+    )
+)]
+pub struct ApiDoc;
+
+/// Declares the two ways of presenting an API key and requires one of them
+/// everywhere except the routes that opt out with `security(())`.
+struct ApiKeySecurity;
+
+impl Modify for ApiKeySecurity {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+        );
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new("X-API-Key"))),
+        );
+
+        openapi.security = Some(vec![
+            SecurityRequirement::new("bearer", Vec::<String>::new()),
+            SecurityRequirement::new("api_key", Vec::<String>::new()),
+        ]);
+    }
+}
+
+/// This OpenAPI document.
+#[utoipa::path(
+    tag = "docs",
+    security(()),
+    responses((status = 200, description = "OpenAPI 3.1 document", content_type = "application/json"))
+)]
+#[get("/api/openapi.json")]
+pub async fn get_openapi() -> impl Responder {
+    HttpResponse::Ok().json(ApiDoc::openapi())
+}
+
+/// Swagger UI page, loaded from a CDN, for browsing `/api/openapi.json`.
+#[utoipa::path(
+    tag = "docs",
+    security(()),
+    responses((status = 200, description = "HTML page", content_type = "text/html"))
+)]
+#[get("/api/docs")]
+pub async fn swagger_ui() -> impl Responder {
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(SWAGGER_UI_HTML)
+}
+
+const SWAGGER_UI_HTML: &str = r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="utf-8">
+  <title>Polygon arbitrage bot API</title>
+  <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css">
+</head>
+<body>
+  <div id="swagger-ui"></div>
+  <script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js" crossorigin></script>
+  <script>
+    window.ui = SwaggerUIBundle({ url: "/api/openapi.json", dom_id: "#swagger-ui" });
+  </script>
+</body>
+</html>
+"##;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::ApiAuth;
+    use crate::config::{ApiKeyConfig, ApiScope};
+    use crate::control::{BotControl, RuntimeSettings};
+    use crate::db::{self, tests::opportunity, Store};
+    use crate::status::StatusTracker;
+    use actix_web::{body::MessageBody, dev::ServiceResponse, middleware::from_fn, test, web, App};
+    use std::sync::Arc;
+
+    /// Validates a response body against the schema the document declares
+    /// for that path, method and status.
+    fn schema_errors(doc: &serde_json::Value, path: &str, method: &str, status: u16, body: &serde_json::Value) -> Vec<String> {
+        let schema = &doc["paths"][path][method]["responses"][status.to_string()]["content"]["application/json"]["schema"];
+        assert!(!schema.is_null(), "no schema documented for {} {} {}", method, path, status);
+
+        // Resolve `#/components/...` references against the document itself
+        let root = serde_json::json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "allOf": [schema],
+            "components": doc["components"],
+        });
+        let validator = jsonschema::validator_for(&root).unwrap();
+        validator.iter_errors(body).map(|e| format!("{} at {}", e, e.instance_path)).collect()
+    }
+
+    fn assert_matches_schema(doc: &serde_json::Value, path: &str, method: &str, status: u16, body: &serde_json::Value) {
+        let errors = schema_errors(doc, path, method, status, body);
+        assert!(errors.is_empty(), "{} {} {} does not match its schema: {:?}\n{}", method, path, status, errors, body);
+    }
+
+    async fn read_json<B: MessageBody>(response: ServiceResponse<B>) -> (u16, serde_json::Value) {
+        let status = response.status().as_u16();
+        let body = test::read_body(response).await;
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[actix_web::test]
+    async fn test_responses_match_schema() {
+        let doc = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        let store: Arc<dyn Store> = db::open(&db::tests::temp_sqlite_url()).await.unwrap();
+        store.store_opportunity(&opportunity(3.0)).await.unwrap();
+        store.store_opportunity(&opportunity(5.0)).await.unwrap();
+
+        let status = Arc::new(StatusTracker::new("https://polygon-rpc.com", 30));
+        status.record_failure(&anyhow::anyhow!("rpc timeout"));
+        let control = Arc::new(BotControl::new(store.clone(), RuntimeSettings {
+            min_profit_usdc: 1_000_000,
+            gas_estimate_usdc: 500_000,
+            trade_amount_wei: 1_000_000_000_000_000_000,
+            check_interval_seconds: 10,
+        }));
+        let auth = Arc::new(ApiAuth::new(vec![ApiKeyConfig {
+            name: "operator".to_string(),
+            token: "secret".to_string(),
+            scope: ApiScope::Admin,
+        }]));
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(auth))
+                .wrap(from_fn(crate::auth::enforce))
+                .app_data(web::Data::new(store.clone()))
+                .app_data(web::Data::new(status))
+                .app_data(web::Data::new(control))
+                .service(api::get_opportunities)
+                .service(api::get_stats)
+                .service(api::get_status)
+                .service(api::healthz)
+                .service(api::readyz)
+                .service(api::get_predictions)
+                .service(api::get_lifecycles)
+                .service(api::get_snapshots)
+                .service(api::get_profit_buckets)
+                .service(api::get_venue_leaderboard)
+                .service(api::get_control_audit)
+        ).await;
+
+        let cases = [
+            ("/api/opportunities", "/api/opportunities?limit=1"),
+            ("/api/opportunities", "/api/opportunities?cursor=zz"),
+            ("/api/stats", "/api/stats"),
+            ("/api/status", "/api/status"),
+            ("/healthz", "/healthz"),
+            ("/readyz", "/readyz"),
+            ("/api/predictions", "/api/predictions"),
+            ("/api/lifecycles", "/api/lifecycles"),
+            ("/api/snapshots", "/api/snapshots"),
+            ("/api/analytics/buckets", "/api/analytics/buckets?interval=day"),
+            ("/api/analytics/leaderboard", "/api/analytics/leaderboard"),
+            ("/api/control/audit", "/api/control/audit"),
+        ];
+        for (path, uri) in cases {
+            let request = test::TestRequest::get().uri(uri).insert_header(("X-API-Key", "secret")).to_request();
+            let (status, body) = read_json(test::call_service(&app, request).await).await;
+            assert_matches_schema(&doc, path, "get", status, &body);
+        }
+
+        let drifted = serde_json::json!({"success": true, "data": {"total_opportunities": "2"}});
+        assert!(!schema_errors(&doc, "/api/stats", "get", 200, &drifted).is_empty());
+    }
+
+    #[actix_web::test]
+    async fn test_every_route_is_documented() {
+        let doc = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        let paths = doc["paths"].as_object().unwrap();
+
+        assert_eq!(doc["openapi"], "3.1.0");
+        assert_eq!(paths.len(), 21);
+        assert!(paths["/api/control/settings"]["post"]["requestBody"].is_object());
+        assert_eq!(paths["/healthz"]["get"]["security"], serde_json::json!([{}]));
+    }
+
+    #[actix_web::test]
+    async fn test_docs_are_served() {
+        let app = test::init_service(App::new().service(get_openapi).service(swagger_ui)).await;
+
+        let doc: serde_json::Value = test::call_and_read_body_json(
+            &app, test::TestRequest::get().uri("/api/openapi.json").to_request()).await;
+        assert!(doc["paths"]["/api/opportunities"]["get"].is_object());
+
+        let page = test::call_and_read_body(&app, test::TestRequest::get().uri("/api/docs").to_request()).await;
+        assert!(String::from_utf8(page.to_vec()).unwrap().contains("/api/openapi.json"));
+    }
+}
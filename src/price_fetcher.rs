@@ -1,95 +1,184 @@
-use anyhow::{Result, Context};
-use ethers::{
-    providers::{Provider, Http}, 
-    types::U256,
-    middleware::Middleware,
-};
-use std::{str::FromStr, sync::Arc};
-use log::info;
-
-use crate::config::AppConfig;
-use crate::dex::{
-    QuickSwapPriceFetcher,
-    SushiSwapPriceFetcher,
-    DexPriceFetcher,
-};
-
-pub struct PriceFetcher {
-    quickswap: QuickSwapPriceFetcher,
-    sushiswap: SushiSwapPriceFetcher,
-    trade_amount: U256,
-}
-
-impl PriceFetcher {
-    pub async fn new(config: &AppConfig) -> Result<Self> {
-        info!("Connecting to Polygon RPC: {}", config.network.rpc_url);
-        
-        let provider = Provider::<Http>::try_from(&config.network.rpc_url)
-            .with_context(|| format!("Failed to connect to RPC: {}", config.network.rpc_url))?;
-        let provider = Arc::new(provider);
-        
-        let chain_id = provider.get_chainid().await
-            .with_context(|| "Failed to get chain ID from provider")?;
-        
-        if chain_id.as_u64() != config.network.chain_id {
-            anyhow::bail!("Chain ID mismatch: expected {}, got {}", 
-                         config.network.chain_id, chain_id);
-        }
-        
-        info!("Connected to Polygon (Chain ID: {})", chain_id);
-        
-        let quickswap = QuickSwapPriceFetcher::new(
-            provider.clone(),
-            &config.dex.quickswap_router,
-            &config.tokens.weth,
-            &config.tokens.usdc,
-        ).with_context(|| "Failed to initialize QuickSwap price fetcher")?;
-        
-        let sushiswap = SushiSwapPriceFetcher::new(
-            provider,
-            &config.dex.sushiswap_router,
-            &config.tokens.weth,
-            &config.tokens.usdc,
-        ).with_context(|| "Failed to initialize SushiSwap price fetcher")?;
-        
-        let trade_amount = U256::from_str(&config.trading.trade_amount_wei)
-            .with_context(|| "Invalid trade amount format")?;
-        
-        info!("Trade amount: {} WETH", ethers::utils::format_ether(trade_amount));
-        
-        Ok(Self {
-            quickswap,
-            sushiswap,
-            trade_amount,
-        })
-    }
-    
-    pub async fn get_quickswap_price(&self) -> Result<f64> {
-        let usdc_amount = self.quickswap.get_price(self.trade_amount).await?;
-        Ok(usdc_to_float(usdc_amount))
-    }
-    
-    pub async fn get_sushiswap_price(&self) -> Result<f64> {
-        let usdc_amount = self.sushiswap.get_price(self.trade_amount).await?;
-        Ok(usdc_to_float(usdc_amount))
-    }
-}
-
-fn usdc_to_float(usdc_amount: U256) -> f64 {
-    // USDC has 6 decimals
-    usdc_amount.as_u128() as f64 / 1_000_000.0
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    
-    #[test]
-    fn test_usdc_to_float() {
-        let usdc_amount = U256::from(5_000_000u64); // 5 USDC
-        assert_eq!(usdc_to_float(usdc_amount), 5.0);
-        
-        let usdc_amount = U256::from(1_500_000u64); // 1.5 USDC
-        assert_eq!(usdc_to_float(usdc_amount), 1.5);
-    }
-}
+use anyhow::{Result, Context};
+use ethers::{
+    types::U256,
+    middleware::Middleware,
+};
+use std::{str::FromStr, sync::{Arc, RwLock}, time::{Duration, Instant}};
+use tracing::info;
+
+use crate::config::{redact_url, AppConfig};
+use crate::metrics;
+use crate::rpc::{self, HttpProvider};
+use crate::verify;
+use crate::dex::{
+    QuickSwapPriceFetcher,
+    SushiSwapPriceFetcher,
+    DexPriceFetcher,
+};
+
+/// One venue's quote for the configured trade amount.
+#[derive(Debug, Clone)]
+pub struct VenueQuote {
+    pub venue: String,
+    pub amount_in: U256,
+    pub amount_out: U256,
+    pub price: f64,
+    pub latency: Duration,
+}
+
+pub struct PriceFetcher {
+    // Replaced as a whole when the network, venue or token config is reloaded
+    venues: RwLock<Arc<Venues>>,
+    // Adjustable at runtime through the control API
+    trade_amount: RwLock<U256>,
+}
+
+/// The RPC connection and the venue fetchers built on it.
+pub struct Venues {
+    provider: Arc<HttpProvider>,
+    quickswap: QuickSwapPriceFetcher,
+    sushiswap: SushiSwapPriceFetcher,
+}
+
+impl PriceFetcher {
+    pub async fn new(config: &AppConfig) -> Result<Self> {
+        let venues = Venues::connect(config).await?;
+        
+        let trade_amount = U256::from_str(&config.trading.trade_amount_wei)
+            .with_context(|| "Invalid trade amount format")?;
+        
+        info!("Trade amount: {} WETH", ethers::utils::format_ether(trade_amount));
+        
+        Ok(Self {
+            venues: RwLock::new(Arc::new(venues)),
+            trade_amount: RwLock::new(trade_amount),
+        })
+    }
+    
+    /// Connects to the network and venues in `config` without using them
+    /// yet, so a reload can still be rejected after this succeeds.
+    pub async fn connect_venues(config: &AppConfig) -> Result<Venues> {
+        Venues::connect(config).await
+    }
+    
+    /// Switches over to venues from `connect_venues`. Quotes already in
+    /// flight finish against the previous ones.
+    pub fn switch_venues(&self, venues: Venues) {
+        *self.venues.write().unwrap() = Arc::new(venues);
+    }
+    
+    fn venues(&self) -> Arc<Venues> {
+        self.venues.read().unwrap().clone()
+    }
+    
+    pub async fn get_quickswap_quote(&self) -> Result<VenueQuote> {
+        self.quote(&self.venues().quickswap).await
+    }
+    
+    pub async fn get_sushiswap_quote(&self) -> Result<VenueQuote> {
+        self.quote(&self.venues().sushiswap).await
+    }
+    
+    #[tracing::instrument(name = "quote", skip_all, fields(venue = dex.get_name(), price = tracing::field::Empty))]
+    async fn quote(&self, dex: &dyn DexPriceFetcher) -> Result<VenueQuote> {
+        let trade_amount = self.trade_amount();
+        let started = Instant::now();
+        let usdc_amount = match dex.get_price(trade_amount).await {
+            Ok(amount) => amount,
+            Err(e) => {
+                metrics::get().quote_failures.with_label_values(&[dex.get_name()]).inc();
+                return Err(e);
+            }
+        };
+        let latency = started.elapsed();
+        metrics::get().quote_latency.with_label_values(&[dex.get_name()]).observe(latency.as_secs_f64());
+        
+        let price = usdc_to_float(usdc_amount);
+        tracing::Span::current().record("price", price);
+        
+        Ok(VenueQuote {
+            venue: dex.get_name().to_string(),
+            amount_in: trade_amount,
+            amount_out: usdc_amount,
+            price,
+            latency,
+        })
+    }
+    
+    pub fn trade_amount(&self) -> U256 {
+        *self.trade_amount.read().unwrap()
+    }
+    
+    pub fn set_trade_amount(&self, amount: U256) {
+        *self.trade_amount.write().unwrap() = amount;
+    }
+    
+    pub async fn get_block_number(&self) -> Result<u64> {
+        let block = self.venues().provider.get_block_number().await
+            .with_context(|| "Failed to fetch current block number")?;
+        Ok(block.as_u64())
+    }
+}
+
+impl Venues {
+    async fn connect(config: &AppConfig) -> Result<Self> {
+        let rpc_host = redact_url(&config.network.rpc_url);
+        info!("Connecting to Polygon RPC: {}", rpc_host);
+        
+        let provider = rpc::http_provider(&config.network.rpc_url)
+            .with_context(|| format!("Failed to connect to RPC: {}", rpc_host))?;
+        let provider = Arc::new(provider);
+        
+        let chain_id = provider.get_chainid().await
+            .with_context(|| "Failed to get chain ID from provider")?;
+        
+        if chain_id.as_u64() != config.network.chain_id {
+            anyhow::bail!("Chain ID mismatch: expected {}, got {}", 
+                         config.network.chain_id, chain_id);
+        }
+        
+        info!("Connected to Polygon (Chain ID: {})", chain_id);
+        
+        // Quoting against the wrong contract or decimals gives plausible but
+        // wrong prices, so a mismatch fails here rather than in the detector
+        let report = verify::verify_contracts(provider.clone(), config).await?.ensure()?;
+        info!(checks = report.checks.len(), "Verified configured contracts on-chain");
+        
+        let quickswap = QuickSwapPriceFetcher::new(
+            provider.clone(),
+            &config.dex.quickswap_router,
+            &config.tokens.weth,
+            &config.tokens.usdc,
+        ).with_context(|| "Failed to initialize QuickSwap price fetcher")?;
+        
+        let sushiswap = SushiSwapPriceFetcher::new(
+            provider.clone(),
+            &config.dex.sushiswap_router,
+            &config.tokens.weth,
+            &config.tokens.usdc,
+        ).with_context(|| "Failed to initialize SushiSwap price fetcher")?;
+        
+        info!("Price fetchers ready: {}, {}", quickswap.get_name(), sushiswap.get_name());
+        
+        Ok(Self { provider, quickswap, sushiswap })
+    }
+}
+
+pub fn usdc_to_float(usdc_amount: U256) -> f64 {
+    // USDC has 6 decimals
+    usdc_amount.as_u128() as f64 / 1_000_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_usdc_to_float() {
+        let usdc_amount = U256::from(5_000_000u64); // 5 USDC
+        assert_eq!(usdc_to_float(usdc_amount), 5.0);
+        
+        let usdc_amount = U256::from(1_500_000u64); // 1.5 USDC
+        assert_eq!(usdc_to_float(usdc_amount), 1.5);
+    }
+}
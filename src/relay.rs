@@ -0,0 +1,400 @@
+use anyhow::{Result, Context};
+use futures_util::future::join_all;
+use ethers::{
+    providers::Middleware,
+    types::{Bytes, H256, U64},
+    utils::keccak256,
+};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time;
+use tracing::{info, warn, debug};
+
+use crate::config::{RelayConfig, RelayMethod};
+use crate::rpc::{self, HttpProvider};
+
+/// Name under which public mempool broadcasts are tracked in the stats.
+pub const PUBLIC_ROUTE: &str = "public";
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+pub struct RelayStats {
+    pub submitted: u64,
+    pub accepted: u64,
+    pub failed: u64,
+    pub included: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Submission {
+    pub tx_hashes: Vec<H256>,
+    pub target_block: u64,
+    /// Relays (or `PUBLIC_ROUTE`) that accepted the submission
+    pub accepted_by: Vec<String>,
+    /// Set once the inclusion has been credited, so polling again does not
+    /// count it twice
+    pub included: bool,
+}
+
+struct RelayEndpoint {
+    name: String,
+    provider: HttpProvider,
+    method: RelayMethod,
+}
+
+pub struct RelaySubmitter {
+    relays: Vec<RelayEndpoint>,
+    public: Option<Arc<HttpProvider>>,
+    public_fallback: bool,
+    max_block_offset: u64,
+    timeout: Duration,
+    stats: Mutex<HashMap<String, RelayStats>>,
+}
+
+impl RelaySubmitter {
+    pub fn new(config: &RelayConfig, public: Option<Arc<HttpProvider>>) -> Result<Self> {
+        let mut relays = Vec::new();
+        let mut stats = HashMap::new();
+
+        for endpoint in &config.endpoints {
+            let provider = rpc::http_provider(&endpoint.url)
+                .with_context(|| format!("Invalid URL for relay {}: {}", endpoint.name, endpoint.url))?;
+
+            info!("Private relay configured: {} ({:?})", endpoint.name, endpoint.method);
+
+            stats.insert(endpoint.name.clone(), RelayStats::default());
+            relays.push(RelayEndpoint {
+                name: endpoint.name.clone(),
+                provider,
+                method: endpoint.method,
+            });
+        }
+
+        stats.insert(PUBLIC_ROUTE.to_string(), RelayStats::default());
+
+        Ok(Self {
+            relays,
+            public,
+            public_fallback: config.public_fallback,
+            max_block_offset: config.max_block_offset,
+            timeout: Duration::from_millis(config.timeout_ms),
+            stats: Mutex::new(stats),
+        })
+    }
+
+    /// Submits signed transactions to every configured relay at once, falling
+    /// back to a public broadcast when no relay accepts them and fallback is
+    /// enabled.
+    pub async fn submit(&self, signed_txs: &[Bytes], target_block: u64) -> Result<Submission> {
+        if signed_txs.is_empty() {
+            anyhow::bail!("Nothing to submit: no signed transactions");
+        }
+
+        let tx_hashes: Vec<H256> = signed_txs.iter()
+            .map(|tx| H256::from(keccak256(tx)))
+            .collect();
+        let mut accepted_by = Vec::new();
+
+        let results = join_all(self.relays.iter().map(|relay| async move {
+            self.record(&relay.name, |s| s.submitted += 1);
+            let result = time::timeout(self.timeout, self.send_to_relay(relay, signed_txs, target_block)).await
+                .unwrap_or_else(|_| Err(anyhow::anyhow!("timed out after {:?}", self.timeout)));
+            (relay, result)
+        })).await;
+
+        for (relay, result) in results {
+            match result {
+                Ok(()) => {
+                    debug!("Relay {} accepted {} transaction(s)", relay.name, signed_txs.len());
+                    self.record(&relay.name, |s| s.accepted += 1);
+                    accepted_by.push(relay.name.clone());
+                },
+                Err(e) => {
+                    warn!("Relay {} rejected submission: {:#}", relay.name, e);
+                    self.record(&relay.name, |s| s.failed += 1);
+                }
+            }
+        }
+
+        if accepted_by.is_empty() {
+            if !self.public_fallback {
+                anyhow::bail!("No relay accepted the submission and public fallback is disabled");
+            }
+
+            warn!("No relay accepted the submission, broadcasting publicly");
+            self.broadcast_public(signed_txs).await?;
+            accepted_by.push(PUBLIC_ROUTE.to_string());
+        }
+
+        Ok(Submission {
+            tx_hashes,
+            target_block,
+            accepted_by,
+            included: false,
+        })
+    }
+
+    /// Checks whether every transaction of a submission has been mined and, if
+    /// so, credits the inclusion to each route that accepted it. The credit is
+    /// given once per submission however often it is polled.
+    pub async fn check_inclusion(&self, submission: &mut Submission) -> Result<bool> {
+        if submission.included {
+            return Ok(true);
+        }
+
+        let provider = self.public.as_ref()
+            .context("No public provider configured for inclusion checks")?;
+
+        for hash in &submission.tx_hashes {
+            let receipt = provider.get_transaction_receipt(*hash).await
+                .with_context(|| format!("Failed to fetch receipt for {:?}", hash))?;
+
+            if receipt.and_then(|r| r.block_number).is_none() {
+                return Ok(false);
+            }
+        }
+
+        debug!("Submission targeting block {} included via {:?}",
+               submission.target_block, submission.accepted_by);
+
+        for route in &submission.accepted_by {
+            self.record(route, |s| s.included += 1);
+        }
+        submission.included = true;
+
+        Ok(true)
+    }
+
+    pub fn stats(&self) -> HashMap<String, RelayStats> {
+        self.stats.lock().unwrap().clone()
+    }
+
+    async fn send_to_relay(&self, relay: &RelayEndpoint, signed_txs: &[Bytes], target_block: u64) -> Result<()> {
+        let max_block = U64::from(target_block + self.max_block_offset);
+
+        match relay.method {
+            RelayMethod::PrivateTransaction if signed_txs.len() == 1 => {
+                let params = serde_json::json!([{
+                    "tx": signed_txs[0],
+                    "maxBlockNumber": max_block,
+                }]);
+                let _: H256 = relay.provider.request("eth_sendPrivateTransaction", params).await
+                    .with_context(|| "eth_sendPrivateTransaction failed")?;
+            },
+            // A failure partway through one call per transaction would leave
+            // the rest to the public fallback, so several go as one bundle
+            // that is accepted whole or not at all
+            RelayMethod::PrivateTransaction | RelayMethod::Bundle => {
+                let params = serde_json::json!([{
+                    "txs": signed_txs,
+                    "blockNumber": U64::from(target_block),
+                    "maxBlockNumber": max_block,
+                }]);
+                let _: serde_json::Value = relay.provider.request("eth_sendBundle", params).await
+                    .with_context(|| "eth_sendBundle failed")?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn broadcast_public(&self, signed_txs: &[Bytes]) -> Result<()> {
+        let provider = self.public.as_ref()
+            .context("Public fallback enabled but no public provider configured")?;
+
+        self.record(PUBLIC_ROUTE, |s| s.submitted += 1);
+
+        for tx in signed_txs {
+            if let Err(e) = provider.send_raw_transaction(tx.clone()).await {
+                self.record(PUBLIC_ROUTE, |s| s.failed += 1);
+                return Err(e).with_context(|| "Public broadcast failed");
+            }
+        }
+
+        self.record(PUBLIC_ROUTE, |s| s.accepted += 1);
+
+        Ok(())
+    }
+
+    fn record(&self, route: &str, update: impl FnOnce(&mut RelayStats)) {
+        let mut stats = self.stats.lock().unwrap();
+        update(stats.entry(route.to_string()).or_default());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RelayEndpointConfig;
+    use actix_web::{web, App, HttpResponse, HttpServer};
+    use ethers::types::TransactionReceipt;
+
+    #[derive(Clone, Default)]
+    struct MockRelay {
+        calls: Arc<Mutex<Vec<String>>>,
+        reject: bool,
+        delay: Duration,
+    }
+
+    async fn handle_rpc(state: web::Data<MockRelay>, body: web::Json<serde_json::Value>) -> HttpResponse {
+        let method = body["method"].as_str().unwrap_or_default().to_string();
+        state.calls.lock().unwrap().push(method.clone());
+        time::sleep(state.delay).await;
+
+        if state.reject {
+            return HttpResponse::Ok().json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": body["id"],
+                "error": { "code": -32000, "message": "bundle rejected" }
+            }));
+        }
+
+        let result = match method.as_str() {
+            "eth_sendPrivateTransaction" | "eth_sendRawTransaction" => {
+                serde_json::json!(H256::repeat_byte(0x11))
+            },
+            "eth_sendBundle" => serde_json::json!({ "bundleHash": H256::repeat_byte(0x22) }),
+            "eth_getTransactionReceipt" => {
+                serde_json::to_value(TransactionReceipt {
+                    transaction_hash: H256::repeat_byte(0x11),
+                    block_number: Some(U64::from(100)),
+                    ..Default::default()
+                }).unwrap()
+            },
+            _ => serde_json::Value::Null,
+        };
+
+        HttpResponse::Ok().json(serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": body["id"],
+            "result": result
+        }))
+    }
+
+    fn start_mock(state: MockRelay) -> String {
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(web::Data::new(state.clone()))
+                .route("/", web::post().to(handle_rpc))
+        })
+        .workers(1)
+        .bind(("127.0.0.1", 0))
+        .unwrap();
+
+        let addr = server.addrs()[0];
+        actix_web::rt::spawn(server.run());
+        format!("http://{}", addr)
+    }
+
+    fn relay_config(endpoints: Vec<(&str, String, RelayMethod)>, public_fallback: bool) -> RelayConfig {
+        RelayConfig {
+            endpoints: endpoints.into_iter()
+                .map(|(name, url, method)| RelayEndpointConfig { name: name.to_string(), url, method })
+                .collect(),
+            public_fallback,
+            max_block_offset: 3,
+            timeout_ms: 500,
+        }
+    }
+
+    fn signed_tx() -> Bytes {
+        Bytes::from(vec![0x02, 0xf8, 0x70, 0x01])
+    }
+
+    #[actix_web::test]
+    async fn test_submit_to_private_relays() {
+        let private = MockRelay::default();
+        let bundle = MockRelay::default();
+        let config = relay_config(vec![
+            ("private", start_mock(private.clone()), RelayMethod::PrivateTransaction),
+            ("bundle", start_mock(bundle.clone()), RelayMethod::Bundle),
+        ], true);
+
+        let submitter = RelaySubmitter::new(&config, None).unwrap();
+        let submission = submitter.submit(&[signed_tx()], 100).await.unwrap();
+
+        assert_eq!(submission.accepted_by, vec!["private", "bundle"]);
+        assert_eq!(submission.tx_hashes, vec![H256::from(keccak256(signed_tx()))]);
+        assert_eq!(*private.calls.lock().unwrap(), vec!["eth_sendPrivateTransaction"]);
+        assert_eq!(*bundle.calls.lock().unwrap(), vec!["eth_sendBundle"]);
+
+        let stats = submitter.stats();
+        assert_eq!(stats["private"], RelayStats { submitted: 1, accepted: 1, failed: 0, included: 0 });
+        assert_eq!(stats[PUBLIC_ROUTE].submitted, 0);
+    }
+
+    #[actix_web::test]
+    async fn test_slow_relay_times_out_without_holding_up_the_others() {
+        let slow = MockRelay { delay: Duration::from_secs(5), ..Default::default() };
+        let fast = MockRelay::default();
+        let config = relay_config(vec![
+            ("slow", start_mock(slow), RelayMethod::Bundle),
+            ("fast", start_mock(fast.clone()), RelayMethod::Bundle),
+        ], false);
+
+        let submitter = RelaySubmitter::new(&config, None).unwrap();
+        let started = std::time::Instant::now();
+        let submission = submitter.submit(&[signed_tx()], 100).await.unwrap();
+
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert_eq!(submission.accepted_by, vec!["fast"]);
+        let stats = submitter.stats();
+        assert_eq!(stats["slow"].failed, 1);
+        assert_eq!(stats["fast"].accepted, 1);
+    }
+
+    #[actix_web::test]
+    async fn test_multiple_private_transactions_go_as_one_bundle() {
+        let private = MockRelay::default();
+        let config = relay_config(vec![
+            ("private", start_mock(private.clone()), RelayMethod::PrivateTransaction),
+        ], false);
+
+        let submitter = RelaySubmitter::new(&config, None).unwrap();
+        let txs = [signed_tx(), Bytes::from(vec![0x02, 0xf8, 0x70, 0x02])];
+        let submission = submitter.submit(&txs, 100).await.unwrap();
+
+        assert_eq!(submission.accepted_by, vec!["private"]);
+        assert_eq!(submission.tx_hashes.len(), 2);
+        assert_eq!(*private.calls.lock().unwrap(), vec!["eth_sendBundle"]);
+    }
+
+    #[actix_web::test]
+    async fn test_falls_back_to_public_broadcast() {
+        let failing = MockRelay { reject: true, ..Default::default() };
+        let public = MockRelay::default();
+        let public_url = start_mock(public.clone());
+        let config = relay_config(vec![
+            ("failing", start_mock(failing), RelayMethod::Bundle),
+        ], true);
+
+        let provider = Arc::new(rpc::http_provider(&public_url).unwrap());
+        let submitter = RelaySubmitter::new(&config, Some(provider)).unwrap();
+        let mut submission = submitter.submit(&[signed_tx()], 100).await.unwrap();
+
+        assert_eq!(submission.accepted_by, vec![PUBLIC_ROUTE]);
+        assert_eq!(*public.calls.lock().unwrap(), vec!["eth_sendRawTransaction"]);
+
+        // Polling an included submission again does not credit it twice
+        assert!(submitter.check_inclusion(&mut submission).await.unwrap());
+        assert!(submitter.check_inclusion(&mut submission).await.unwrap());
+
+        let stats = submitter.stats();
+        assert_eq!(stats["failing"], RelayStats { submitted: 1, accepted: 0, failed: 1, included: 0 });
+        assert_eq!(stats[PUBLIC_ROUTE], RelayStats { submitted: 1, accepted: 1, failed: 0, included: 1 });
+    }
+
+    #[actix_web::test]
+    async fn test_no_fallback_when_disabled() {
+        let failing = MockRelay { reject: true, ..Default::default() };
+        let config = relay_config(vec![
+            ("failing", start_mock(failing), RelayMethod::PrivateTransaction),
+        ], false);
+
+        let submitter = RelaySubmitter::new(&config, None).unwrap();
+
+        assert!(submitter.submit(&[signed_tx()], 100).await.is_err());
+        assert_eq!(submitter.stats()["failing"].failed, 1);
+    }
+}
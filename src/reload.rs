@@ -0,0 +1,312 @@
+//! Applies edits to the config file while the bot keeps running.
+
+use anyhow::{Context, Result};
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, Mutex};
+use tokio::time;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::config::{self, AppConfig};
+use crate::control::{BotControl, RuntimeSettings, SettingsUpdate};
+use crate::price_fetcher::PriceFetcher;
+use crate::status::StatusTracker;
+
+/// Sections applied without a restart
+const LIVE_SECTIONS: &[&str] = &["network", "dex", "tokens", "trading", "monitoring"];
+/// Sections whose changes rebuild the price fetchers
+const VENUE_SECTIONS: &[&str] = &["network", "dex", "tokens"];
+/// Editors often save in several writes; changes are applied once they settle
+const DEBOUNCE: Duration = Duration::from_millis(500);
+/// Recorded in the control audit trail for settings changed by a reload
+const RELOAD_ACTOR: &str = "config_reload";
+
+/// Where the config was loaded from, so it can be loaded again.
+#[derive(Debug, Clone)]
+pub struct ConfigSource {
+    pub path: String,
+    pub profile: Option<String>,
+}
+
+impl ConfigSource {
+    pub async fn load(&self) -> Result<AppConfig> {
+        AppConfig::load(&self.path, self.profile.as_deref()).await
+    }
+
+    fn files(&self) -> Vec<PathBuf> {
+        let path = PathBuf::from(&self.path);
+        let mut files = vec![path.clone()];
+        if let Some(profile) = &self.profile {
+            files.push(config::profile_path(&path, profile));
+        }
+        files
+    }
+}
+
+pub struct ConfigReloader {
+    source: ConfigSource,
+    /// Held for the whole reload so edits are applied one at a time
+    current: Mutex<AppConfig>,
+    /// Sections outside `LIVE_SECTIONS` are compared against this
+    started_with: toml::Table,
+    fetcher: Arc<PriceFetcher>,
+    control: Arc<BotControl>,
+    status: Arc<StatusTracker>,
+}
+
+impl ConfigReloader {
+    pub fn new(
+        source: ConfigSource,
+        config: AppConfig,
+        fetcher: Arc<PriceFetcher>,
+        control: Arc<BotControl>,
+        status: Arc<StatusTracker>,
+    ) -> Result<Self> {
+        Ok(Self {
+            source,
+            started_with: sections(&config)?,
+            current: Mutex::new(config),
+            fetcher,
+            control,
+            status,
+        })
+    }
+
+    /// Reloads whenever the config or profile file changes, until `shutdown`
+    /// is cancelled.
+    pub async fn run(self: Arc<Self>, shutdown: CancellationToken) -> Result<()> {
+        let files = self.source.files();
+        let names: Vec<OsString> = files.iter().filter_map(|file| file.file_name().map(Into::into)).collect();
+
+        let (sender, mut changes) = mpsc::unbounded_channel();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            match event {
+                Ok(event) if is_edit(&event.kind) && event.paths.iter().any(|path| {
+                    path.file_name().is_some_and(|name| names.iter().any(|watched| watched == name))
+                }) => {
+                    let _ = sender.send(());
+                },
+                Ok(_) => {},
+                Err(e) => warn!("Config watcher error: {}", e),
+            }
+        })?;
+
+        // Directories are watched rather than the files, since many editors
+        // save by replacing the file
+        let mut directories: Vec<&Path> = files.iter().map(|file| parent_directory(file)).collect();
+        directories.dedup();
+        for directory in directories {
+            watcher.watch(directory, RecursiveMode::NonRecursive)
+                .with_context(|| format!("Failed to watch {}", directory.display()))?;
+        }
+        info!(files = ?files, "Watching config for changes");
+
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => return Ok(()),
+                change = changes.recv() => if change.is_none() {
+                    anyhow::bail!("Config watcher stopped");
+                },
+            }
+
+            time::sleep(DEBOUNCE).await;
+            while changes.try_recv().is_ok() {}
+
+            // Failures are reported on /api/status and the next edit retried
+            let _ = self.reload().await;
+        }
+    }
+
+    /// Loads the config again and applies what changed. An invalid config
+    /// is rejected as a whole and the running one kept.
+    pub async fn reload(&self) -> Result<()> {
+        let mut current = self.current.lock().await;
+
+        let result = async {
+            let next = self.source.load().await?;
+            let restart_required = self.apply(&current, &next).await?;
+            Ok((next, restart_required))
+        }.await;
+
+        match result {
+            Ok((next, restart_required)) => {
+                *current = next;
+                self.status.record_config_reload(restart_required);
+                Ok(())
+            },
+            Err(e) => {
+                warn!("Rejected config change, keeping the previous config: {:#}", e);
+                self.status.record_config_error(&e);
+                Err(e)
+            },
+        }
+    }
+
+    /// Applies the live sections that differ, returning the other sections
+    /// that now differ from the startup config.
+    async fn apply(&self, current: &AppConfig, next: &AppConfig) -> Result<Vec<String>> {
+        let (before, after) = (sections(current)?, sections(next)?);
+        let changed: Vec<&str> = after.iter()
+            .filter(|(name, section)| before.get(name.as_str()) != Some(section))
+            .map(|(name, _)| name.as_str())
+            .collect();
+
+        // Every fallible step runs before anything is switched over, so a
+        // rejected reload leaves the running venues and settings untouched
+        let venues = if changed.iter().any(|name| VENUE_SECTIONS.contains(name)) {
+            Some(PriceFetcher::connect_venues(next).await
+                .with_context(|| "Failed to connect to the new network or venues")?)
+        } else {
+            None
+        };
+
+        let settings = settings_update(&RuntimeSettings::from_config(current)?, &RuntimeSettings::from_config(next)?);
+        if let Some(update) = settings {
+            self.control.update_settings(RELOAD_ACTOR, update).await?;
+        }
+
+        if let Some(venues) = venues {
+            self.fetcher.switch_venues(venues);
+            info!("Rebuilt price fetchers for the new network and venue config");
+        }
+        self.status.set_stall_after(next.monitoring.stall_after_seconds());
+
+        let restart_required: Vec<String> = after.iter()
+            .filter(|(name, section)| !LIVE_SECTIONS.contains(&name.as_str()) && self.started_with.get(name.as_str()) != Some(section))
+            .map(|(name, _)| name.clone())
+            .collect();
+        if !restart_required.is_empty() {
+            warn!("Changes to [{}] take effect after a restart", restart_required.join("], ["));
+        }
+
+        if changed.is_empty() {
+            debug!("Config file changed without changing the effective config");
+        } else {
+            info!(sections = ?changed, "Config reloaded");
+        }
+        Ok(restart_required)
+    }
+}
+
+fn is_edit(kind: &EventKind) -> bool {
+    matches!(kind, EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_))
+}
+
+fn parent_directory(file: &Path) -> &Path {
+    match file.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    }
+}
+
+fn sections(config: &AppConfig) -> Result<toml::Table> {
+    match toml::Value::try_from(config)? {
+        toml::Value::Table(table) => Ok(table),
+        _ => anyhow::bail!("Config did not serialize to a table"),
+    }
+}
+
+/// Only values edited in the file are sent, so settings changed through the
+/// control API are kept unless the file changes them too.
+fn settings_update(current: &RuntimeSettings, next: &RuntimeSettings) -> Option<SettingsUpdate> {
+    let changed = |current: u128, next: u128| (current != next).then(|| next.to_string());
+    let update = SettingsUpdate {
+        min_profit_usdc: changed(current.min_profit_usdc.into(), next.min_profit_usdc.into()),
+        gas_estimate_usdc: changed(current.gas_estimate_usdc.into(), next.gas_estimate_usdc.into()),
+        trade_amount_wei: changed(current.trade_amount_wei, next.trade_amount_wei),
+        check_interval_seconds: (current.check_interval_seconds != next.check_interval_seconds)
+            .then_some(next.check_interval_seconds),
+    };
+
+    let empty = update.min_profit_usdc.is_none() && update.gas_estimate_usdc.is_none()
+        && update.trade_amount_wei.is_none() && update.check_interval_seconds.is_none();
+    (!empty).then_some(update)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+    use crate::verify::tests::{mock_contracts, start_mock_chain, start_mock_chain_at};
+    use chrono::Utc;
+
+    fn write_config(path: &Path, rpc_url: &str, edit: &[(&str, &str)]) {
+        let mut content = std::fs::read_to_string("config/config.toml").unwrap()
+            .replace("rpc_url = \"https://polygon-rpc.com\"", &format!("rpc_url = \"{}\"", rpc_url));
+        for (from, to) in edit {
+            assert!(content.contains(from), "{} not in config", from);
+            content = content.replace(from, to);
+        }
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[actix_web::test]
+    async fn test_applies_valid_changes_and_keeps_config_on_error() {
+        let path = std::env::temp_dir().join(format!("arb-config-{}.toml", uuid::Uuid::new_v4()));
+        write_config(&path, "http://127.0.0.1:1", &[]);
+        let source = ConfigSource { path: path.to_string_lossy().into_owned(), profile: None };
+        let rpc_url = start_mock_chain(mock_contracts(&source.load().await.unwrap()));
+        write_config(&path, &rpc_url, &[]);
+
+        let config = source.load().await.unwrap();
+        let store = db::open(&db::tests::temp_sqlite_url()).await.unwrap();
+        let control = Arc::new(BotControl::new(store, RuntimeSettings::from_config(&config).unwrap()));
+        let status = Arc::new(StatusTracker::new(&rpc_url, 30));
+        let fetcher = Arc::new(PriceFetcher::new(&config).await.unwrap());
+        let reloader = ConfigReloader::new(source, config, fetcher, control.clone(), status.clone()).unwrap();
+
+        write_config(&path, &rpc_url, &[
+            ("min_profit_usdc = \"5 USDC\"", "min_profit_usdc = \"7 USDC\""),
+            ("port = 8081", "port = 9000"),
+        ]);
+        reloader.reload().await.unwrap();
+        assert_eq!(control.settings().min_profit_usdc, 7_000_000);
+        let report = status.report(Utc::now());
+        assert_eq!(report.config.restart_required, vec!["api"]);
+        assert!(report.config.last_reload.is_some() && report.config.reload_error.is_none());
+
+        // Invalid values, a chain ID the RPC does not serve and a factory
+        // with no contract behind it are all rejected
+        write_config(&path, &rpc_url, &[("min_profit_usdc = \"5 USDC\"", "min_profit_usdc = \"lots\"")]);
+        assert!(reloader.reload().await.is_err());
+        write_config(&path, &rpc_url, &[("chain_id = 137", "chain_id = 1")]);
+        assert!(reloader.reload().await.is_err());
+        let report = status.report(Utc::now());
+        assert!(report.config.reload_error.unwrap().contains("Chain ID mismatch"));
+
+        let factory = reloader.current.lock().await.dex.quickswap_factory.clone();
+        write_config(&path, &rpc_url, &[(factory.as_str(), "0x0000000000000000000000000000000000000001")]);
+        assert!(reloader.reload().await.is_err());
+        let report = status.report(Utc::now());
+        assert!(report.config.reload_error.unwrap().contains("failed verification"));
+        assert_eq!(control.settings().min_profit_usdc, 7_000_000);
+        assert_eq!(reloader.current.lock().await.network.chain_id, 137);
+    }
+
+    #[actix_web::test]
+    async fn test_rejected_reload_keeps_running_venues() {
+        let path = std::env::temp_dir().join(format!("arb-config-{}.toml", uuid::Uuid::new_v4()));
+        write_config(&path, "http://127.0.0.1:1", &[]);
+        let source = ConfigSource { path: path.to_string_lossy().into_owned(), profile: None };
+        let contracts = mock_contracts(&source.load().await.unwrap());
+        let (running, next) = (start_mock_chain_at(contracts.clone(), 100), start_mock_chain_at(contracts, 200));
+        write_config(&path, &running, &[]);
+
+        // Not migrated, so auditing the settings change fails
+        let config = source.load().await.unwrap();
+        let store = db::connect(&db::tests::temp_sqlite_url()).await.unwrap();
+        let control = Arc::new(BotControl::new(store, RuntimeSettings::from_config(&config).unwrap()));
+        let status = Arc::new(StatusTracker::new(&running, 30));
+        let fetcher = Arc::new(PriceFetcher::new(&config).await.unwrap());
+        let reloader = ConfigReloader::new(source, config, fetcher.clone(), control.clone(), status).unwrap();
+
+        write_config(&path, &next, &[("min_profit_usdc = \"5 USDC\"", "min_profit_usdc = \"7 USDC\"")]);
+        assert!(reloader.reload().await.is_err());
+        assert_eq!(fetcher.get_block_number().await.unwrap(), 100);
+        assert_eq!(control.settings().min_profit_usdc, 5_000_000);
+    }
+}
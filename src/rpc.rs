@@ -0,0 +1,67 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use ethers::providers::{Http, JsonRpcClient, Provider, PubsubClient, Ws};
+use ethers::types::U256;
+use serde::{de::DeserializeOwned, Serialize};
+use std::fmt::Debug;
+use std::str::FromStr;
+use std::time::Instant;
+
+use crate::config::redact_url;
+use crate::metrics;
+
+/// Wraps an ethers transport so every JSON-RPC call is counted and timed by
+/// method.
+#[derive(Debug, Clone)]
+pub struct Metered<C> {
+    inner: C,
+}
+
+impl<C> Metered<C> {
+    pub fn new(inner: C) -> Self {
+        Self { inner }
+    }
+}
+
+pub type HttpProvider = Provider<Metered<Http>>;
+pub type WsProvider = Provider<Metered<Ws>>;
+
+pub fn http_provider(url: &str) -> Result<HttpProvider> {
+    let http = Http::from_str(url)
+        .with_context(|| format!("Invalid RPC URL: {}", redact_url(url)))?;
+    Ok(Provider::new(Metered::new(http)))
+}
+
+pub async fn ws_provider(url: &str) -> Result<WsProvider> {
+    let ws = Ws::connect(url).await
+        .with_context(|| format!("Failed to connect to WebSocket RPC: {}", redact_url(url)))?;
+    Ok(Provider::new(Metered::new(ws)))
+}
+
+#[async_trait]
+impl<C: JsonRpcClient> JsonRpcClient for Metered<C> {
+    type Error = C::Error;
+
+    async fn request<T, R>(&self, method: &str, params: T) -> Result<R, Self::Error>
+    where
+        T: Debug + Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        let started = Instant::now();
+        let result = self.inner.request(method, params).await;
+        metrics::get().observe_rpc_call(method, result.is_ok(), started.elapsed());
+        result
+    }
+}
+
+impl<C: PubsubClient> PubsubClient for Metered<C> {
+    type NotificationStream = C::NotificationStream;
+
+    fn subscribe<T: Into<U256>>(&self, id: T) -> Result<Self::NotificationStream, Self::Error> {
+        self.inner.subscribe(id)
+    }
+
+    fn unsubscribe<T: Into<U256>>(&self, id: T) -> Result<(), Self::Error> {
+        self.inner.unsubscribe(id)
+    }
+}
@@ -0,0 +1,297 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tokio::time;
+use tokio_util::sync::CancellationToken;
+use utoipa::ToSchema;
+use tracing::{debug, info, warn};
+
+use crate::config::SnapshotConfig;
+use crate::db::Store;
+use crate::metrics;
+use crate::price_fetcher::VenueQuote;
+
+/// A single venue quote taken during a check, recorded whether or not an
+/// opportunity was found.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct PriceSnapshot {
+    /// Shared by all quotes taken in the same check
+    pub check_id: String,
+    pub timestamp: DateTime<Utc>,
+    pub block_number: u64,
+    pub pair: String,
+    pub venue: String,
+    /// Raw base-unit amounts, kept as decimal strings to avoid precision loss
+    pub amount_in: String,
+    pub amount_out: String,
+    pub price: f64,
+    pub latency_ms: f64,
+}
+
+impl PriceSnapshot {
+    pub fn from_quote(
+        check_id: &str,
+        timestamp: DateTime<Utc>,
+        block_number: u64,
+        pair: &str,
+        quote: &VenueQuote,
+    ) -> Self {
+        Self {
+            check_id: check_id.to_string(),
+            timestamp,
+            block_number,
+            pair: pair.to_string(),
+            venue: quote.venue.clone(),
+            amount_in: quote.amount_in.to_string(),
+            amount_out: quote.amount_out.to_string(),
+            price: quote.price,
+            latency_ms: quote.latency.as_secs_f64() * 1000.0,
+        }
+    }
+}
+
+/// How many batches failed writes may hold back before the oldest snapshots
+/// are dropped.
+const MAX_BUFFERED_BATCHES: usize = 4;
+
+/// Buffers snapshots and writes them to the store in batches.
+pub struct SnapshotRecorder {
+    store: Arc<dyn Store>,
+    config: SnapshotConfig,
+    buffer: Mutex<Vec<PriceSnapshot>>,
+}
+
+impl SnapshotRecorder {
+    pub fn new(store: Arc<dyn Store>, config: SnapshotConfig) -> Self {
+        Self {
+            store,
+            buffer: Mutex::new(Vec::with_capacity(config.batch_size)),
+            config,
+        }
+    }
+
+    /// Buffers snapshots, flushing once a full batch has accumulated.
+    pub async fn record(&self, snapshots: Vec<PriceSnapshot>) -> Result<()> {
+        if !self.config.enabled {
+            return Ok(());
+        }
+
+        let batch = {
+            let mut buffer = self.buffer.lock().await;
+            buffer.extend(snapshots);
+            if buffer.len() < self.config.batch_size {
+                return Ok(());
+            }
+            std::mem::take(&mut *buffer)
+        };
+
+        self.write(batch).await
+    }
+
+    /// Writes everything buffered so far, returning the number of snapshots written.
+    pub async fn flush(&self) -> Result<usize> {
+        let batch = std::mem::take(&mut *self.buffer.lock().await);
+        let count = batch.len();
+
+        if count > 0 {
+            self.write(batch).await?;
+        }
+
+        Ok(count)
+    }
+
+    async fn write(&self, batch: Vec<PriceSnapshot>) -> Result<()> {
+        if let Err(e) = metrics::timed_write("store_price_snapshots", self.store.store_price_snapshots(&batch)).await {
+            // Keep the batch so the next flush retries it, up to a limit so an
+            // unreachable store cannot grow the buffer without bound
+            let mut buffer = self.buffer.lock().await;
+            let newer = std::mem::replace(&mut *buffer, batch);
+            buffer.extend(newer);
+
+            let capacity = self.config.batch_size.max(1) * MAX_BUFFERED_BATCHES;
+            if buffer.len() > capacity {
+                let dropped = buffer.len() - capacity;
+                buffer.drain(..dropped);
+                metrics::get().snapshots_dropped.inc_by(dropped as u64);
+                warn!("Snapshot buffer is full after failed writes, dropping the {} oldest", dropped);
+            }
+            return Err(e);
+        }
+
+        debug!("Wrote {} price snapshots", batch.len());
+        Ok(())
+    }
+
+    /// Applies the downsampling and retention policies, returning the number
+    /// of snapshots (downsampled, pruned).
+    pub async fn apply_retention(&self, now: DateTime<Utc>) -> Result<(u64, u64)> {
+        let mut downsampled = 0;
+        let mut pruned = 0;
+
+        if self.config.downsample_after_hours > 0 {
+            let cutoff = now - Duration::hours(self.config.downsample_after_hours as i64);
+            downsampled = self.store
+                .downsample_price_snapshots(cutoff, self.config.downsample_bucket_seconds as i64)
+                .await?;
+        }
+
+        if self.config.retention_days > 0 {
+            let cutoff = now - Duration::days(self.config.retention_days as i64);
+            pruned = self.store.prune_price_snapshots(cutoff).await?;
+        }
+
+        Ok((downsampled, pruned))
+    }
+
+    /// Flushes on the configured interval and applies retention hourly
+    /// until `shutdown` is cancelled. Writes are never interrupted midway,
+    /// so buffered snapshots are left for the final `flush`.
+    pub async fn run_maintenance(self: Arc<Self>, shutdown: CancellationToken) -> Result<()> {
+        let mut interval = time::interval(std::time::Duration::from_secs(self.config.flush_interval_seconds.max(1)));
+        let mut last_retention: Option<DateTime<Utc>> = None;
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {},
+                _ = shutdown.cancelled() => return Ok(()),
+            }
+
+            if let Err(e) = self.flush().await {
+                warn!("Failed to flush price snapshots: {:#}", e);
+            }
+
+            let now = Utc::now();
+            if last_retention.is_none_or(|last| now - last >= Duration::hours(1)) {
+                match self.apply_retention(now).await {
+                    Ok((0, 0)) => {},
+                    Ok((downsampled, pruned)) => {
+                        info!("Snapshot retention: {} downsampled, {} pruned", downsampled, pruned);
+                    },
+                    Err(e) => warn!("Failed to apply snapshot retention: {:#}", e),
+                }
+                last_retention = Some(now);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arbitrage::MONITORED_PAIR;
+    use crate::db;
+
+    fn snapshot(check_id: &str, timestamp: DateTime<Utc>, venue: &str) -> PriceSnapshot {
+        PriceSnapshot {
+            check_id: check_id.to_string(),
+            timestamp,
+            block_number: 100,
+            pair: MONITORED_PAIR.to_string(),
+            venue: venue.to_string(),
+            amount_in: "1000000000000000000".to_string(),
+            amount_out: "3000000000".to_string(),
+            price: 3000.0,
+            latency_ms: 12.5,
+        }
+    }
+
+    fn config(batch_size: usize) -> SnapshotConfig {
+        SnapshotConfig {
+            batch_size,
+            ..SnapshotConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_writes_in_batches() {
+        let store = db::open(&db::tests::temp_sqlite_url()).await.unwrap();
+        let recorder = SnapshotRecorder::new(store.clone(), config(4));
+        let now = Utc::now();
+
+        recorder.record(vec![snapshot("a", now, "QuickSwap"), snapshot("a", now, "SushiSwap")]).await.unwrap();
+        assert!(store.get_recent_price_snapshots(10).await.unwrap().is_empty());
+
+        recorder.record(vec![snapshot("b", now, "QuickSwap"), snapshot("b", now, "SushiSwap")]).await.unwrap();
+        assert_eq!(store.get_recent_price_snapshots(10).await.unwrap().len(), 4);
+
+        recorder.record(vec![snapshot("c", now, "QuickSwap")]).await.unwrap();
+        assert_eq!(recorder.flush().await.unwrap(), 1);
+        assert_eq!(store.get_recent_price_snapshots(10).await.unwrap().len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_failed_writes_keep_a_bounded_buffer() {
+        // Not migrated yet, so there is no table to write snapshots to
+        let url = db::tests::temp_sqlite_url();
+        let store = db::connect(&url).await.unwrap();
+        let recorder = SnapshotRecorder::new(store.clone(), config(2));
+        let dropped = metrics::get().snapshots_dropped.get();
+        let now = Utc::now();
+
+        for check in 0..12 {
+            let check_id = check.to_string();
+            let result = recorder.record(vec![snapshot(&check_id, now, "QuickSwap"), snapshot(&check_id, now, "SushiSwap")]).await;
+            assert!(result.is_err());
+        }
+        assert_eq!(recorder.buffer.lock().await.len(), 2 * MAX_BUFFERED_BATCHES);
+        assert!(metrics::get().snapshots_dropped.get() - dropped >= 16);
+
+        store.migrate().await.unwrap();
+        assert_eq!(recorder.flush().await.unwrap(), 8);
+
+        // The newest checks survive
+        let written = store.get_recent_price_snapshots(100).await.unwrap();
+        let mut checks: Vec<&str> = written.iter().map(|s| s.check_id.as_str()).collect();
+        checks.sort();
+        checks.dedup();
+        assert_eq!(checks, vec!["10", "11", "8", "9"]);
+    }
+
+    #[tokio::test]
+    async fn test_disabled_recorder_writes_nothing() {
+        let store = db::open(&db::tests::temp_sqlite_url()).await.unwrap();
+        let recorder = SnapshotRecorder::new(store.clone(), SnapshotConfig {
+            enabled: false,
+            batch_size: 1,
+            ..SnapshotConfig::default()
+        });
+
+        recorder.record(vec![snapshot("a", Utc::now(), "QuickSwap")]).await.unwrap();
+        assert!(store.get_recent_price_snapshots(10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_retention_policies() {
+        let store = db::open(&db::tests::temp_sqlite_url()).await.unwrap();
+        let recorder = SnapshotRecorder::new(store.clone(), SnapshotConfig {
+            batch_size: 1,
+            retention_days: 7,
+            downsample_after_hours: 24,
+            downsample_bucket_seconds: 3600,
+            ..SnapshotConfig::default()
+        });
+        let now = Utc::now();
+        let two_days_ago = now - Duration::days(2);
+        let bucket_start = two_days_ago - Duration::seconds(two_days_ago.timestamp() % 3600);
+
+        let mut snapshots = vec![
+            snapshot("old", now - Duration::days(10), "QuickSwap"),
+            snapshot("fresh", now, "QuickSwap"),
+        ];
+        for minute in 0..6 {
+            let timestamp = bucket_start + Duration::minutes(minute * 5);
+            snapshots.push(snapshot(&format!("d{}", minute), timestamp, "QuickSwap"));
+            snapshots.push(snapshot(&format!("d{}", minute), timestamp, "SushiSwap"));
+        }
+        recorder.record(snapshots).await.unwrap();
+
+        let (downsampled, pruned) = recorder.apply_retention(now).await.unwrap();
+
+        // Six quotes per venue in one hourly bucket thin to one each
+        assert_eq!(downsampled, 10);
+        assert_eq!(pruned, 1);
+        assert_eq!(store.get_recent_price_snapshots(100).await.unwrap().len(), 3);
+    }
+}
@@ -0,0 +1,322 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+use utoipa::ToSchema;
+
+use crate::config::redact_url;
+
+/// What the monitoring loop has actually been doing, shared with the API.
+pub struct StatusTracker {
+    started_at: DateTime<Utc>,
+    rpc_endpoint: String,
+    /// False when only the API is served, so there is no loop to go stale
+    monitoring: bool,
+    // Reloadable with the config
+    stall_after_seconds: AtomicU64,
+    state: RwLock<LoopState>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct LoopState {
+    iterations: u64,
+    last_heartbeat_at: Option<DateTime<Utc>>,
+    last_check_at: Option<DateTime<Utc>>,
+    last_success_at: Option<DateTime<Utc>>,
+    last_block: Option<u64>,
+    consecutive_errors: u64,
+    last_error: Option<String>,
+    last_error_at: Option<DateTime<Utc>>,
+    tasks: Vec<TaskStatus>,
+    config_reloaded_at: Option<DateTime<Utc>>,
+    config_restart_required: Vec<String>,
+    config_error: Option<String>,
+    config_error_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskState {
+    Running,
+    /// Crashed and waiting out its backoff before the next attempt
+    Restarting,
+    Stopped,
+}
+
+/// A background task run by the supervisor.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TaskStatus {
+    pub name: String,
+    pub state: TaskState,
+    pub restarts: u64,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<DateTime<Utc>>,
+}
+
+/// Outcome of reloading the config file while running.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ConfigStatus {
+    pub last_reload: Option<DateTime<Utc>>,
+    /// Why the latest edit was rejected; cleared by the next successful
+    /// reload. The previous config stays in effect meanwhile.
+    pub reload_error: Option<String>,
+    pub reload_error_at: Option<DateTime<Utc>>,
+    /// Edited sections that only take effect after a restart
+    pub restart_required: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StatusReport {
+    /// False when this process only serves the API
+    pub monitoring: bool,
+    pub running: bool,
+    pub healthy: bool,
+    pub ready: bool,
+    pub started_at: DateTime<Utc>,
+    pub uptime_seconds: i64,
+    pub rpc_endpoint: String,
+    pub iterations: u64,
+    pub last_check: Option<DateTime<Utc>>,
+    pub last_success: Option<DateTime<Utc>>,
+    pub last_block: Option<u64>,
+    pub consecutive_errors: u64,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<DateTime<Utc>>,
+    pub tasks: Vec<TaskStatus>,
+    pub config: ConfigStatus,
+}
+
+impl StatusTracker {
+    pub fn new(rpc_url: &str, stall_after_seconds: u64) -> Self {
+        Self {
+            started_at: Utc::now(),
+            rpc_endpoint: redact_url(rpc_url),
+            monitoring: true,
+            stall_after_seconds: AtomicU64::new(stall_after_seconds),
+            state: RwLock::new(LoopState::default()),
+        }
+    }
+
+    /// For a process that serves the API without running the loop, which
+    /// is then always live and ready.
+    pub fn without_monitoring(mut self) -> Self {
+        self.monitoring = false;
+        self
+    }
+
+    /// Records that the loop woke up, even if it skipped the check because
+    /// monitoring is paused.
+    pub fn record_heartbeat(&self) {
+        self.state.write().unwrap().last_heartbeat_at = Some(Utc::now());
+    }
+
+    /// Records a successful check, returning true if the loop was not
+    /// healthy before.
+    pub fn record_success(&self, block_number: u64) -> bool {
+        let now = Utc::now();
+        let mut state = self.state.write().unwrap();
+        let changed = state.consecutive_errors > 0 || state.last_success_at.is_none();
+
+        state.iterations += 1;
+        state.last_check_at = Some(now);
+        state.last_success_at = Some(now);
+        state.last_block = Some(block_number);
+        state.consecutive_errors = 0;
+
+        changed
+    }
+
+    /// Records a failed check, returning true if the loop was healthy before.
+    pub fn record_failure(&self, error: &anyhow::Error) -> bool {
+        let now = Utc::now();
+        let mut state = self.state.write().unwrap();
+        let changed = state.consecutive_errors == 0;
+
+        state.iterations += 1;
+        state.last_check_at = Some(now);
+        state.consecutive_errors += 1;
+        state.last_error = Some(format!("{:#}", error));
+        state.last_error_at = Some(now);
+
+        changed
+    }
+
+    /// The loop is live while it keeps waking up, whether or not its checks
+    /// succeed.
+    pub fn is_live(&self, now: DateTime<Utc>) -> bool {
+        if !self.monitoring {
+            return true;
+        }
+        let state = self.state.read().unwrap();
+        let last_seen = state.last_heartbeat_at.max(state.last_check_at).unwrap_or(self.started_at);
+        now - last_seen <= self.stall_after()
+    }
+
+    /// The bot is ready once a check has succeeded recently.
+    pub fn is_ready(&self, now: DateTime<Utc>) -> bool {
+        if !self.monitoring {
+            return true;
+        }
+        let state = self.state.read().unwrap();
+        state.last_success_at.is_some_and(|at| now - at <= self.stall_after())
+    }
+
+    fn stall_after(&self) -> Duration {
+        Duration::seconds(self.stall_after_seconds.load(Ordering::Relaxed) as i64)
+    }
+
+    pub fn set_stall_after(&self, seconds: u64) {
+        self.stall_after_seconds.store(seconds, Ordering::Relaxed);
+    }
+
+    pub fn record_config_reload(&self, restart_required: Vec<String>) {
+        let mut state = self.state.write().unwrap();
+        state.config_reloaded_at = Some(Utc::now());
+        state.config_restart_required = restart_required;
+        state.config_error = None;
+        state.config_error_at = None;
+    }
+
+    pub fn record_config_error(&self, error: &anyhow::Error) {
+        let mut state = self.state.write().unwrap();
+        state.config_error = Some(format!("{:#}", error));
+        state.config_error_at = Some(Utc::now());
+    }
+
+    pub fn task_started(&self, name: &str) {
+        self.update_task(name, |task| task.state = TaskState::Running);
+    }
+
+    pub fn task_failed(&self, name: &str, error: &str) {
+        self.update_task(name, |task| {
+            task.state = TaskState::Restarting;
+            task.restarts += 1;
+            task.last_error = Some(error.to_string());
+            task.last_error_at = Some(Utc::now());
+        });
+    }
+
+    pub fn task_stopped(&self, name: &str) {
+        self.update_task(name, |task| task.state = TaskState::Stopped);
+    }
+
+    fn update_task(&self, name: &str, update: impl FnOnce(&mut TaskStatus)) {
+        let mut state = self.state.write().unwrap();
+        let index = match state.tasks.iter().position(|task| task.name == name) {
+            Some(index) => index,
+            None => {
+                state.tasks.push(TaskStatus {
+                    name: name.to_string(),
+                    state: TaskState::Running,
+                    restarts: 0,
+                    last_error: None,
+                    last_error_at: None,
+                });
+                state.tasks.len() - 1
+            },
+        };
+        update(&mut state.tasks[index]);
+    }
+
+    pub fn consecutive_errors(&self) -> u64 {
+        self.state.read().unwrap().consecutive_errors
+    }
+
+    pub fn report(&self, now: DateTime<Utc>) -> StatusReport {
+        let live = self.is_live(now);
+        let ready = self.is_ready(now);
+        let state = self.state.read().unwrap().clone();
+
+        // A crashed task counts as not running even while the loop keeps ticking
+        let tasks_running = state.tasks.iter().all(|task| task.state == TaskState::Running);
+
+        StatusReport {
+            monitoring: self.monitoring,
+            running: live && tasks_running,
+            healthy: live && state.consecutive_errors == 0,
+            ready,
+            started_at: self.started_at,
+            uptime_seconds: (now - self.started_at).num_seconds(),
+            rpc_endpoint: self.rpc_endpoint.clone(),
+            iterations: state.iterations,
+            last_check: state.last_check_at,
+            last_success: state.last_success_at,
+            last_block: state.last_block,
+            consecutive_errors: state.consecutive_errors,
+            last_error: state.last_error,
+            last_error_at: state.last_error_at,
+            tasks: state.tasks,
+            config: ConfigStatus {
+                last_reload: state.config_reloaded_at,
+                reload_error: state.config_error,
+                reload_error_at: state.config_error_at,
+                restart_required: state.config_restart_required,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracks_errors_and_recovery() {
+        let status = StatusTracker::new("https://polygon-rpc.com", 30);
+
+        assert!(status.record_success(100));
+        assert!(!status.record_success(101));
+        assert!(status.record_failure(&anyhow::anyhow!("rpc timeout")));
+        assert!(!status.record_failure(&anyhow::anyhow!("rpc timeout")));
+
+        let report = status.report(Utc::now());
+        assert_eq!(report.iterations, 4);
+        assert_eq!(report.last_block, Some(101));
+        assert_eq!(report.consecutive_errors, 2);
+        assert_eq!(report.last_error.as_deref(), Some("rpc timeout"));
+        assert!(report.running && !report.healthy && report.ready);
+
+        assert!(status.record_success(102));
+        assert_eq!(status.report(Utc::now()).consecutive_errors, 0);
+    }
+
+    #[test]
+    fn test_stalls_without_checks() {
+        let status = StatusTracker::new("https://polygon-rpc.com", 30);
+        let later = Utc::now() + Duration::seconds(31);
+
+        assert!(status.is_live(Utc::now()));
+        assert!(!status.is_ready(Utc::now()));
+
+        status.record_success(100);
+        assert!(status.is_ready(Utc::now()));
+        assert!(!status.is_live(later));
+        assert!(!status.is_ready(later));
+    }
+
+    #[test]
+    fn test_api_only_never_stalls() {
+        let status = StatusTracker::new("https://polygon-rpc.com", 30).without_monitoring();
+        let later = Utc::now() + Duration::seconds(31);
+
+        assert!(status.is_live(later) && status.is_ready(later));
+        assert!(!status.report(later).monitoring);
+    }
+
+    #[test]
+    fn test_crashed_task_is_not_running() {
+        let status = StatusTracker::new("https://polygon-rpc.com", 30);
+        status.task_started("monitor");
+        assert!(status.report(Utc::now()).running);
+
+        status.task_failed("monitor", "panicked: boom");
+        let report = status.report(Utc::now());
+        assert!(!report.running);
+        assert_eq!(report.tasks[0].state, TaskState::Restarting);
+        assert_eq!(report.tasks[0].restarts, 1);
+        assert_eq!(report.tasks[0].last_error.as_deref(), Some("panicked: boom"));
+
+        status.task_started("monitor");
+        assert!(status.report(Utc::now()).running);
+    }
+}
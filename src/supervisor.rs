@@ -0,0 +1,242 @@
+//! Runs the bot's background tasks, restarting any that crash and stopping
+//! them all once shutdown is requested.
+
+use anyhow::Result;
+use std::any::Any;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinSet;
+use tokio::time;
+use tokio_util::sync::CancellationToken;
+use tokio_util::task::AbortOnDropHandle;
+use tracing::{error, info, warn};
+
+use crate::metrics;
+use crate::status::StatusTracker;
+
+/// How long to wait before restarting a crashed task.
+#[derive(Debug, Clone)]
+pub struct RestartPolicy {
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// A task that ran at least this long before crashing starts over from
+    /// `initial_backoff`
+    pub stable_after: Duration,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            stable_after: Duration::from_secs(300),
+        }
+    }
+}
+
+pub struct Supervisor {
+    shutdown: CancellationToken,
+    status: Arc<StatusTracker>,
+    policy: RestartPolicy,
+    tasks: JoinSet<()>,
+}
+
+impl Supervisor {
+    pub fn new(shutdown: CancellationToken, status: Arc<StatusTracker>, policy: RestartPolicy) -> Self {
+        Self { shutdown, status, policy, tasks: JoinSet::new() }
+    }
+
+    /// Runs `task` until shutdown, calling it again whenever it returns,
+    /// fails or panics. Each attempt gets the shutdown token and should
+    /// return promptly once it is cancelled.
+    pub fn spawn<F, Fut>(&mut self, name: &'static str, task: F)
+    where
+        F: FnMut(CancellationToken) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        self.tasks.spawn(supervise(name, task, self.shutdown.clone(), self.status.clone(), self.policy.clone()));
+    }
+
+    /// Waits for every task to stop after shutdown was requested. Tasks
+    /// still running after `timeout` are aborted.
+    pub async fn join(mut self, timeout: Duration) -> Result<()> {
+        let all_stopped = async {
+            while self.tasks.join_next().await.is_some() {}
+        };
+
+        if time::timeout(timeout, all_stopped).await.is_err() {
+            let remaining = self.tasks.len();
+            self.tasks.shutdown().await;
+            anyhow::bail!("{} task(s) did not stop within {:?} and were aborted", remaining, timeout);
+        }
+        Ok(())
+    }
+}
+
+async fn supervise<F, Fut>(
+    name: &'static str,
+    mut task: F,
+    shutdown: CancellationToken,
+    status: Arc<StatusTracker>,
+    policy: RestartPolicy,
+) where
+    F: FnMut(CancellationToken) -> Fut,
+    Fut: Future<Output = Result<()>> + Send + 'static,
+{
+    let mut backoff = policy.initial_backoff;
+
+    loop {
+        status.task_started(name);
+        let started = Instant::now();
+        // Spawned on its own so a panic surfaces here as a JoinError, and
+        // aborted along with the supervisor if shutdown times out
+        let outcome = AbortOnDropHandle::new(tokio::spawn(task(shutdown.clone()))).await;
+
+        let error = match outcome {
+            Ok(Ok(())) if shutdown.is_cancelled() => None,
+            Ok(Ok(())) => Some("exited unexpectedly".to_string()),
+            Ok(Err(e)) => Some(format!("{:#}", e)),
+            Err(e) if e.is_panic() => Some(format!("panicked: {}", panic_message(e.into_panic()))),
+            Err(e) => Some(e.to_string()),
+        };
+
+        if shutdown.is_cancelled() {
+            if let Some(error) = error {
+                warn!(task = name, "Task failed while shutting down: {}", error);
+            }
+            info!(task = name, "Task stopped");
+            status.task_stopped(name);
+            return;
+        }
+        let error = error.unwrap_or_default();
+
+        if started.elapsed() >= policy.stable_after {
+            backoff = policy.initial_backoff;
+        }
+        metrics::get().task_restarts.with_label_values(&[name]).inc();
+        status.task_failed(name, &error);
+        error!(task = name, "Task failed, restarting in {:?}: {}", backoff, error);
+
+        tokio::select! {
+            _ = time::sleep(backoff) => {},
+            _ = shutdown.cancelled() => {
+                status.task_stopped(name);
+                return;
+            },
+        }
+        backoff = (backoff * 2).min(policy.max_backoff);
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => payload.downcast_ref::<&str>().map(|message| message.to_string())
+            .unwrap_or_else(|| "unknown panic".to_string()),
+    }
+}
+
+/// Cancels `shutdown` on the first SIGINT or SIGTERM. A second signal exits
+/// immediately without draining.
+pub fn handle_signals(shutdown: CancellationToken) {
+    tokio::spawn(async move {
+        for received in 0.. {
+            if let Err(e) = shutdown_signal().await {
+                error!("Failed to listen for shutdown signals: {:#}", e);
+                return;
+            }
+            if received > 0 {
+                warn!("Second shutdown signal received, exiting without draining");
+                std::process::exit(130);
+            }
+            info!("Shutdown signal received, stopping tasks (send again to force exit)");
+            shutdown.cancel();
+        }
+    });
+}
+
+#[cfg(unix)]
+async fn shutdown_signal() -> Result<()> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut terminate = signal(SignalKind::terminate())?;
+    tokio::select! {
+        result = tokio::signal::ctrl_c() => result?,
+        _ = terminate.recv() => {},
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+async fn shutdown_signal() -> Result<()> {
+    tokio::signal::ctrl_c().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::status::TaskState;
+    use chrono::Utc;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn fast_policy() -> RestartPolicy {
+        RestartPolicy {
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(40),
+            stable_after: Duration::from_secs(60),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_restarts_crashed_task_until_shutdown() {
+        let shutdown = CancellationToken::new();
+        let status = Arc::new(StatusTracker::new("http://localhost:8545", 30));
+        let mut supervisor = Supervisor::new(shutdown.clone(), status.clone(), fast_policy());
+
+        let attempts = Arc::new(AtomicU32::new(0));
+        let counter = attempts.clone();
+        supervisor.spawn("monitor", move |shutdown| {
+            let attempt = counter.fetch_add(1, Ordering::SeqCst);
+            async move {
+                match attempt {
+                    0 => panic!("boom"),
+                    1 => anyhow::bail!("rpc closed"),
+                    _ => {
+                        shutdown.cancelled().await;
+                        Ok(())
+                    },
+                }
+            }
+        });
+
+        while attempts.load(Ordering::SeqCst) < 3 {
+            time::sleep(Duration::from_millis(5)).await;
+        }
+        let report = status.report(Utc::now());
+        assert_eq!(report.tasks[0].state, TaskState::Running);
+        assert_eq!(report.tasks[0].restarts, 2);
+        assert_eq!(report.tasks[0].last_error.as_deref(), Some("rpc closed"));
+
+        shutdown.cancel();
+        supervisor.join(Duration::from_secs(1)).await.unwrap();
+        assert_eq!(status.report(Utc::now()).tasks[0].state, TaskState::Stopped);
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_aborts_tasks_that_ignore_shutdown() {
+        let shutdown = CancellationToken::new();
+        let status = Arc::new(StatusTracker::new("http://localhost:8545", 30));
+        let mut supervisor = Supervisor::new(shutdown.clone(), status, fast_policy());
+        supervisor.spawn("stuck", |_| async {
+            time::sleep(Duration::from_secs(60)).await;
+            Ok(())
+        });
+
+        shutdown.cancel();
+        let error = supervisor.join(Duration::from_millis(50)).await.unwrap_err();
+        assert!(error.to_string().contains("did not stop"));
+    }
+}
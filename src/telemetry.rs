@@ -0,0 +1,104 @@
+use anyhow::{Context, Result};
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use opentelemetry_sdk::Resource;
+use std::fs::OpenOptions;
+use std::path::Path;
+use std::sync::Mutex;
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer, Registry};
+
+use crate::config::{LogFormat, TelemetryConfig};
+
+/// The installed tracing subscriber; spans still buffered for the collector
+/// are exported by `shutdown`.
+pub struct Telemetry {
+    provider: Option<SdkTracerProvider>,
+}
+
+impl Telemetry {
+    /// Installs the global subscriber. `RUST_LOG` takes precedence over the
+    /// configured level, and `debug` over both. Logs are appended to
+    /// `log_file` instead of stderr when given.
+    pub fn init(config: &TelemetryConfig, debug: bool, log_file: Option<&Path>) -> Result<Self> {
+        let filter = if debug {
+            EnvFilter::new("debug")
+        } else {
+            EnvFilter::try_from_default_env().or_else(|_| EnvFilter::try_new(&config.level))
+                .with_context(|| format!("Invalid telemetry.level: {}", config.level))?
+        };
+
+        // Logs go to stderr so stdout stays usable for command output and the stdout log sink
+        let writer = match log_file {
+            Some(path) => {
+                let file = OpenOptions::new().create(true).append(true).open(path)
+                    .with_context(|| format!("Failed to open log file {}", path.display()))?;
+                BoxMakeWriter::new(Mutex::new(file))
+            },
+            None => BoxMakeWriter::new(std::io::stderr),
+        };
+
+        let mut layers: Vec<Box<dyn Layer<Registry> + Send + Sync>> = Vec::new();
+        layers.push(match config.format {
+            LogFormat::Text => tracing_subscriber::fmt::layer()
+                .with_ansi(log_file.is_none())
+                .with_writer(writer)
+                .boxed(),
+            LogFormat::Json => tracing_subscriber::fmt::layer()
+                .json()
+                .with_current_span(true)
+                .with_span_list(true)
+                .with_writer(writer)
+                .boxed(),
+        });
+
+        let provider = match &config.otlp_endpoint {
+            Some(endpoint) => {
+                let provider = otlp_provider(endpoint, &config.service_name)?;
+                let tracer = provider.tracer(env!("CARGO_PKG_NAME"));
+                layers.push(tracing_opentelemetry::layer().with_tracer(tracer).boxed());
+                Some(provider)
+            },
+            None => None,
+        };
+
+        tracing_subscriber::registry()
+            .with(layers)
+            .with(filter)
+            .try_init()
+            .with_context(|| "Failed to install tracing subscriber")?;
+
+        Ok(Self { provider })
+    }
+
+    pub fn shutdown(self) {
+        if let Some(provider) = self.provider {
+            if let Err(e) = provider.shutdown() {
+                eprintln!("Failed to export remaining spans: {}", e);
+            }
+        }
+    }
+}
+
+fn otlp_provider(endpoint: &str, service_name: &str) -> Result<SdkTracerProvider> {
+    let endpoint = match endpoint.trim_end_matches('/') {
+        endpoint if endpoint.ends_with("/v1/traces") => endpoint.to_string(),
+        endpoint => format!("{}/v1/traces", endpoint),
+    };
+
+    // The blocking HTTP client cannot be created on an async runtime thread
+    let exporter = std::thread::spawn(move || {
+        SpanExporter::builder().with_http().with_endpoint(endpoint).build()
+    })
+    .join()
+    .map_err(|_| anyhow::anyhow!("OTLP exporter setup panicked"))?
+    .with_context(|| "Failed to create OTLP span exporter")?;
+
+    Ok(SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(Resource::builder().with_service_name(service_name.to_string()).build())
+        .build())
+}
@@ -0,0 +1,37 @@
+use anyhow::{Context, Result};
+use rustls::{Certificate, PrivateKey, ServerConfig};
+use rustls_pemfile::Item;
+use std::fs::File;
+use std::io::BufReader;
+
+/// Builds the HTTPS server config from a PEM certificate chain and a PEM
+/// private key (PKCS#8, PKCS#1 or SEC1).
+pub fn load_server_config(cert_path: &str, key_path: &str) -> Result<ServerConfig> {
+    let mut cert_reader = BufReader::new(File::open(cert_path)
+        .with_context(|| format!("Failed to open TLS certificate: {}", cert_path))?);
+    let certs: Vec<Certificate> = rustls_pemfile::certs(&mut cert_reader)
+        .with_context(|| format!("Failed to parse TLS certificate: {}", cert_path))?
+        .into_iter()
+        .map(Certificate)
+        .collect();
+    if certs.is_empty() {
+        anyhow::bail!("No certificates found in {}", cert_path);
+    }
+
+    let mut key_reader = BufReader::new(File::open(key_path)
+        .with_context(|| format!("Failed to open TLS key: {}", key_path))?);
+    let key = rustls_pemfile::read_all(&mut key_reader)
+        .with_context(|| format!("Failed to parse TLS key: {}", key_path))?
+        .into_iter()
+        .find_map(|item| match item {
+            Item::PKCS8Key(key) | Item::RSAKey(key) | Item::ECKey(key) => Some(PrivateKey(key)),
+            _ => None,
+        })
+        .with_context(|| format!("No private key found in {}", key_path))?;
+
+    ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .with_context(|| "Invalid TLS certificate or key")
+}
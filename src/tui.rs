@@ -0,0 +1,342 @@
+//! Terminal dashboard for operators without the web UI, fed by the same
+//! event bus as the streaming API.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Paragraph, Row, Sparkline, Table};
+use ratatui::Frame;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc};
+use tokio::time;
+use tokio_util::sync::CancellationToken;
+use tracing::warn;
+
+use crate::arbitrage::ArbitrageOpportunity;
+use crate::control::BotControl;
+use crate::events::{BotEvent, EventBus};
+use crate::snapshots::PriceSnapshot;
+use crate::status::{StatusReport, StatusTracker};
+
+/// Spreads kept per pair for the sparkline
+const SPREAD_HISTORY: usize = 240;
+/// Opportunities listed, newest first
+const RECENT_OPPORTUNITIES: usize = 20;
+/// Status is polled rather than evented, so uptime and staleness keep moving
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+/// How long the key reader blocks before checking for shutdown
+const KEY_POLL_INTERVAL: Duration = Duration::from_millis(200);
+/// Recorded in the control audit trail for pauses and checks from the dashboard
+const TUI_ACTOR: &str = "tui";
+
+/// What the dashboard shows, built up from bot events.
+#[derive(Debug, Default)]
+pub struct Dashboard {
+    pairs: BTreeMap<String, PairView>,
+    opportunities: VecDeque<ArbitrageOpportunity>,
+    /// Latest result of a dashboard action, shown in the footer
+    notice: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct PairView {
+    block_number: u64,
+    quotes: Vec<PriceSnapshot>,
+    /// Difference between the best and worst venue price, in millionths
+    spreads: VecDeque<u64>,
+}
+
+impl Dashboard {
+    pub fn apply(&mut self, event: BotEvent) {
+        match event {
+            BotEvent::Quotes { block_number, pair, quotes } => {
+                let view = self.pairs.entry(pair).or_default();
+                if let Some(spread) = spread(&quotes) {
+                    if view.spreads.len() == SPREAD_HISTORY {
+                        view.spreads.pop_front();
+                    }
+                    view.spreads.push_back((spread * 1_000_000.0).round() as u64);
+                }
+                view.block_number = block_number;
+                view.quotes = quotes;
+            },
+            BotEvent::Opportunity { opportunity } => {
+                self.opportunities.push_front(opportunity);
+                self.opportunities.truncate(RECENT_OPPORTUNITIES);
+            },
+            // The status section reads the tracker directly
+            BotEvent::Status { .. } => {},
+        }
+    }
+}
+
+/// Spread across venues, once at least two have quoted.
+fn spread(quotes: &[PriceSnapshot]) -> Option<f64> {
+    if quotes.len() < 2 {
+        return None;
+    }
+    let prices = quotes.iter().map(|quote| quote.price);
+    let (low, high) = prices.fold((f64::MAX, f64::MIN), |(low, high), price| (low.min(price), high.max(price)));
+    Some(high - low)
+}
+
+/// Draws the whole dashboard into `frame`.
+pub fn draw(frame: &mut Frame, dashboard: &Dashboard, report: &StatusReport, paused: bool) {
+    let sparklines = dashboard.pairs.len().max(1) as u16 * 4;
+    let [status, quotes, spreads, opportunities, footer] = Layout::vertical([
+        Constraint::Length(4),
+        Constraint::Length(dashboard.pairs.values().map(|view| view.quotes.len().max(1) as u16).sum::<u16>() + 3),
+        Constraint::Length(sparklines),
+        Constraint::Min(4),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    draw_status(frame, status, report, paused);
+    draw_quotes(frame, quotes, dashboard);
+    draw_spreads(frame, spreads, dashboard);
+    draw_opportunities(frame, opportunities, dashboard);
+
+    let mut keys = vec![
+        Span::raw(" q").bold(), Span::raw(" quit  "),
+        Span::raw("p").bold(), Span::raw(if paused { " resume  " } else { " pause  " }),
+        Span::raw("c").bold(), Span::raw(" check now  "),
+    ];
+    if let Some(notice) = &dashboard.notice {
+        keys.push(Span::raw(notice.as_str()).italic());
+    }
+    frame.render_widget(Line::from(keys), footer);
+}
+
+fn draw_status(frame: &mut Frame, area: Rect, report: &StatusReport, paused: bool) {
+    let (state, color) = match (report.running, paused, report.healthy) {
+        (false, _, _) => ("STALLED", Color::Red),
+        (true, true, _) => ("PAUSED", Color::Yellow),
+        (true, false, false) => ("FAILING", Color::Red),
+        (true, false, true) => ("RUNNING", Color::Green),
+    };
+    let loop_line = Line::from(vec![
+        Span::styled(format!(" {} ", state), Style::new().fg(Color::Black).bg(color).add_modifier(Modifier::BOLD)),
+        Span::raw(format!("  {} checks, last block {}, last success {}, up {}s",
+                          report.iterations,
+                          report.last_block.map(|block| block.to_string()).unwrap_or_else(|| "-".to_string()),
+                          ago(report.last_success),
+                          report.uptime_seconds)),
+    ]);
+
+    let rpc_line = match &report.last_error {
+        Some(error) if report.consecutive_errors > 0 => Line::from(vec![
+            Span::raw(format!(" RPC {}  ", report.rpc_endpoint)),
+            Span::styled(format!("{} consecutive errors: {}", report.consecutive_errors, error), Style::new().fg(Color::Red)),
+        ]),
+        _ => Line::from(vec![
+            Span::raw(format!(" RPC {}  ", report.rpc_endpoint)),
+            Span::styled("healthy", Style::new().fg(Color::Green)),
+        ]),
+    };
+
+    let paragraph = Paragraph::new(vec![loop_line, rpc_line]).block(Block::bordered().title(" Loop "));
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_quotes(frame: &mut Frame, area: Rect, dashboard: &Dashboard) {
+    let rows = dashboard.pairs.iter().flat_map(|(pair, view)| {
+        view.quotes.iter().map(move |quote| Row::new(vec![
+            pair.clone(),
+            quote.venue.clone(),
+            format!("{:.6}", quote.price),
+            format!("{:.0} ms", quote.latency_ms),
+            view.block_number.to_string(),
+        ]))
+    });
+
+    let table = Table::new(rows, [
+        Constraint::Length(12),
+        Constraint::Length(12),
+        Constraint::Length(16),
+        Constraint::Length(10),
+        Constraint::Min(10),
+    ])
+    .header(Row::new(vec!["Pair", "Venue", "Price", "Latency", "Block"]).bold())
+    .block(Block::bordered().title(" Quotes "));
+    frame.render_widget(table, area);
+}
+
+fn draw_spreads(frame: &mut Frame, area: Rect, dashboard: &Dashboard) {
+    if dashboard.pairs.is_empty() {
+        frame.render_widget(Paragraph::new(" Waiting for quotes").block(Block::bordered().title(" Spread ")), area);
+        return;
+    }
+
+    let areas = Layout::vertical(dashboard.pairs.iter().map(|_| Constraint::Length(4))).split(area);
+    for ((pair, view), area) in dashboard.pairs.iter().zip(areas.iter()) {
+        let latest = view.spreads.back().map(|spread| *spread as f64 / 1_000_000.0).unwrap_or_default();
+        // Only the newest spreads that fit, so the chart scrolls left
+        let width = area.width.saturating_sub(2) as usize;
+        let data: Vec<u64> = view.spreads.iter().skip(view.spreads.len().saturating_sub(width)).copied().collect();
+        let sparkline = Sparkline::default()
+            .data(&data)
+            .style(Style::new().fg(Color::Cyan))
+            .block(Block::bordered().title(format!(" {} spread {:.6} ", pair, latest)));
+        frame.render_widget(sparkline, *area);
+    }
+}
+
+fn draw_opportunities(frame: &mut Frame, area: Rect, dashboard: &Dashboard) {
+    let rows = dashboard.opportunities.iter().map(|opportunity| Row::new(vec![
+        opportunity.timestamp.format("%H:%M:%S").to_string(),
+        opportunity.pair.clone(),
+        format!("{} @ {:.6}", opportunity.buy_exchange, opportunity.buy_price),
+        format!("{} @ {:.6}", opportunity.sell_exchange, opportunity.sell_price),
+        format!("{:.6}", opportunity.estimated_profit),
+    ]));
+
+    let table = Table::new(rows, [
+        Constraint::Length(10),
+        Constraint::Length(12),
+        Constraint::Length(24),
+        Constraint::Length(24),
+        Constraint::Min(10),
+    ])
+    .header(Row::new(vec!["Time", "Pair", "Buy", "Sell", "Profit"]).bold())
+    .block(Block::bordered().title(format!(" Recent opportunities ({}) ", dashboard.opportunities.len())));
+    frame.render_widget(table, area);
+}
+
+fn ago(at: Option<DateTime<Utc>>) -> String {
+    match at {
+        Some(at) => format!("{}s ago", (Utc::now() - at).num_seconds().max(0)),
+        None => "never".to_string(),
+    }
+}
+
+/// Runs the dashboard on the terminal until `q` is pressed or `shutdown` is
+/// cancelled. Takes over the terminal, so logs must go elsewhere.
+pub async fn run(
+    events: Arc<EventBus>,
+    status: Arc<StatusTracker>,
+    control: Arc<BotControl>,
+    shutdown: CancellationToken,
+) -> Result<()> {
+    let mut receiver = events.subscribe();
+    let mut keys = read_keys(shutdown.clone());
+    let mut terminal = ratatui::try_init()?;
+    let mut dashboard = Dashboard::default();
+    let mut refresh = time::interval(REFRESH_INTERVAL);
+
+    let result = async {
+        loop {
+            terminal.draw(|frame| draw(frame, &dashboard, &status.report(Utc::now()), control.is_paused()))?;
+
+            tokio::select! {
+                _ = shutdown.cancelled() => return Ok(()),
+                _ = refresh.tick() => {},
+                event = receiver.recv() => match event {
+                    Ok(event) => dashboard.apply(event),
+                    Err(broadcast::error::RecvError::Lagged(missed)) => {
+                        dashboard.notice = Some(format!("skipped {} events", missed));
+                    },
+                    Err(broadcast::error::RecvError::Closed) => return Ok(()),
+                },
+                key = keys.recv() => {
+                    let outcome = match key {
+                        Some(KeyCode::Char('q')) | Some(KeyCode::Esc) | None => return Ok(()),
+                        Some(KeyCode::Char('p')) if control.is_paused() => control.resume(TUI_ACTOR).await.map(|_| "resumed"),
+                        Some(KeyCode::Char('p')) => control.pause(TUI_ACTOR).await.map(|_| "paused"),
+                        Some(KeyCode::Char('c')) => control.request_check(TUI_ACTOR).await.map(|_| "check requested"),
+                        Some(_) => continue,
+                    };
+                    dashboard.notice = Some(match outcome {
+                        Ok(done) => done.to_string(),
+                        Err(e) => {
+                            warn!("Dashboard action failed: {:#}", e);
+                            format!("failed: {:#}", e)
+                        },
+                    });
+                },
+            }
+        }
+    }.await;
+
+    ratatui::restore();
+    result
+}
+
+/// Forwards key presses from a blocking thread, with Ctrl-C as `q` since raw
+/// mode stops it raising SIGINT.
+fn read_keys(shutdown: CancellationToken) -> mpsc::UnboundedReceiver<KeyCode> {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    std::thread::spawn(move || {
+        while !shutdown.is_cancelled() {
+            match event::poll(KEY_POLL_INTERVAL) {
+                Ok(false) => continue,
+                Ok(true) => {},
+                Err(_) => return,
+            }
+            let key = match event::read() {
+                Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => key,
+                Ok(_) => continue,
+                Err(_) => return,
+            };
+            let code = match (key.code, key.modifiers.contains(KeyModifiers::CONTROL)) {
+                (KeyCode::Char('c'), true) => KeyCode::Char('q'),
+                (code, _) => code,
+            };
+            if sender.send(code).is_err() {
+                return;
+            }
+        }
+    });
+    receiver
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    fn quote(venue: &str, price: f64) -> PriceSnapshot {
+        PriceSnapshot {
+            check_id: "check".to_string(),
+            timestamp: Utc::now(),
+            block_number: 100,
+            pair: "WETH/USDC".to_string(),
+            venue: venue.to_string(),
+            amount_in: "1000000000000000000".to_string(),
+            amount_out: "3000000000".to_string(),
+            price,
+            latency_ms: 42.0,
+        }
+    }
+
+    #[test]
+    fn test_renders_quotes_spreads_and_opportunities() {
+        let mut dashboard = Dashboard::default();
+        for (block_number, sushiswap) in [(100, 3001.5), (101, 3002.25)] {
+            dashboard.apply(BotEvent::Quotes {
+                block_number,
+                pair: "WETH/USDC".to_string(),
+                quotes: vec![quote("QuickSwap", 3000.0), quote("SushiSwap", sushiswap)],
+            });
+        }
+        dashboard.apply(BotEvent::Opportunity { opportunity: crate::db::tests::opportunity(2.25) });
+        assert_eq!(dashboard.pairs["WETH/USDC"].spreads, [1_500_000, 2_250_000]);
+
+        let status = StatusTracker::new("https://polygon-rpc.com", 30);
+        status.record_success(101);
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal.draw(|frame| draw(frame, &dashboard, &status.report(Utc::now()), true)).unwrap();
+
+        let screen: String = terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect();
+        for expected in ["PAUSED", "last block 101", "https://polygon-rpc.com", "SushiSwap", "3002.250000",
+                         "WETH/USDC spread 2.250000", "Recent opportunities (1)", "p resume"] {
+            assert!(screen.contains(expected), "{:?} not on screen", expected);
+        }
+    }
+}
@@ -0,0 +1,286 @@
+//! Confirms the configured contracts are what the config says they are
+//! before any quotes are taken.
+
+use anyhow::Result;
+use ethers::contract::abigen;
+use ethers::providers::Middleware;
+use ethers::types::Address;
+use ethers::utils::to_checksum;
+use serde::Serialize;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::arbitrage::MONITORED_PAIR;
+use crate::config::{AppConfig, USDC_DECIMALS, WETH_DECIMALS};
+use crate::rpc::HttpProvider;
+
+abigen!(
+    IVerifyRouter,
+    r#"[
+        function factory() external view returns (address)
+    ]"#
+);
+
+abigen!(
+    IVerifyFactory,
+    r#"[
+        function getPair(address tokenA, address tokenB) external view returns (address pair)
+    ]"#
+);
+
+abigen!(
+    IVerifyToken,
+    r#"[
+        function decimals() external view returns (uint8)
+    ]"#
+);
+
+/// One property of one contract.
+#[derive(Debug, Clone, Serialize)]
+pub struct ContractCheck {
+    /// Config field the address came from, or the pair it was looked up for
+    pub subject: String,
+    pub address: String,
+    pub ok: bool,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct VerificationReport {
+    pub checks: Vec<ContractCheck>,
+}
+
+impl VerificationReport {
+    pub fn is_ok(&self) -> bool {
+        self.checks.iter().all(|check| check.ok)
+    }
+
+    /// Fails with the whole report if any check failed.
+    pub fn ensure(self) -> Result<Self> {
+        if !self.is_ok() {
+            anyhow::bail!("Configured contracts failed verification:\n{}", self);
+        }
+        Ok(self)
+    }
+
+    fn push(&mut self, subject: &str, address: Address, result: Result<String, String>) -> bool {
+        let ok = result.is_ok();
+        self.checks.push(ContractCheck {
+            subject: subject.to_string(),
+            address: to_checksum(&address, None),
+            ok,
+            detail: result.unwrap_or_else(|error| error),
+        });
+        ok
+    }
+}
+
+impl fmt::Display for VerificationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self.checks.iter().map(|check| check.subject.len()).max().unwrap_or(0);
+        for check in &self.checks {
+            writeln!(f, "  {:<4}  {:<width$}  {}  {}",
+                     if check.ok { "ok" } else { "FAIL" }, check.subject, check.address, check.detail, width = width)?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks that every configured contract has code, that each router's
+/// `factory()` is the configured factory, that each factory has a pair for
+/// the monitored tokens, and that the tokens' `decimals()` are the ones
+/// amounts are converted with.
+pub async fn verify_contracts(provider: Arc<HttpProvider>, config: &AppConfig) -> Result<VerificationReport> {
+    let address = |value: &str| Address::from_str(value);
+    let (weth, usdc) = (address(&config.tokens.weth)?, address(&config.tokens.usdc)?);
+    let mut report = VerificationReport::default();
+
+    let tokens = [("tokens.weth", weth, WETH_DECIMALS), ("tokens.usdc", usdc, USDC_DECIMALS)];
+    for (subject, token, expected) in tokens {
+        if !check_code(&provider, &mut report, subject, token).await {
+            continue;
+        }
+        let result = match IVerifyToken::new(token, provider.clone()).decimals().call().await {
+            Ok(decimals) if u32::from(decimals) == expected => Ok(format!("decimals() is {}", decimals)),
+            Ok(decimals) => Err(format!("decimals() is {}, expected {}", decimals, expected)),
+            Err(e) => Err(format!("decimals() failed: {}", e)),
+        };
+        report.push(subject, token, result);
+    }
+
+    let venues = [
+        ("QuickSwap", "dex.quickswap_router", &config.dex.quickswap_router, "dex.quickswap_factory", &config.dex.quickswap_factory),
+        ("SushiSwap", "dex.sushiswap_router", &config.dex.sushiswap_router, "dex.sushiswap_factory", &config.dex.sushiswap_factory),
+    ];
+    for (venue, router_subject, router, factory_subject, factory) in venues {
+        let (router, factory) = (address(router)?, address(factory)?);
+
+        if check_code(&provider, &mut report, router_subject, router).await {
+            let result = match IVerifyRouter::new(router, provider.clone()).factory().call().await {
+                Ok(actual) if actual == factory => Ok(format!("factory() is {}", factory_subject)),
+                Ok(actual) => Err(format!("factory() is {}, not {}", to_checksum(&actual, None), factory_subject)),
+                Err(e) => Err(format!("factory() failed, is this a Uniswap V2 router? {}", e)),
+            };
+            report.push(router_subject, router, result);
+        }
+
+        if check_code(&provider, &mut report, factory_subject, factory).await {
+            let subject = format!("{} {} pair", venue, MONITORED_PAIR);
+            match IVerifyFactory::new(factory, provider.clone()).get_pair(weth, usdc).call().await {
+                Ok(pair) if pair.is_zero() => {
+                    report.push(&subject, factory, Err(format!("{} has no {} pair", factory_subject, MONITORED_PAIR)));
+                },
+                Ok(pair) => {
+                    check_code(&provider, &mut report, &subject, pair).await;
+                },
+                Err(e) => {
+                    report.push(&subject, factory, Err(format!("getPair() failed: {}", e)));
+                },
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+async fn check_code(provider: &HttpProvider, report: &mut VerificationReport, subject: &str, address: Address) -> bool {
+    let result = match provider.get_code(address, None).await {
+        Ok(code) if code.is_empty() => Err("no contract code at this address".to_string()),
+        Ok(code) => Ok(format!("{} bytes of code", code.len())),
+        Err(e) => Err(format!("eth_getCode failed: {}", e)),
+    };
+    report.push(subject, address, result)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use actix_web::{web, App, HttpResponse, HttpServer};
+    use ethers::abi::{self, Token};
+    use std::collections::HashMap;
+
+    /// What the mock chain has deployed at an address
+    #[derive(Debug, Clone)]
+    pub enum MockContract {
+        Router { factory: Address },
+        Factory { pair: Address },
+        Token { decimals: u8 },
+        Pair,
+    }
+
+    /// The contracts in `config`, deployed as configured on Polygon.
+    pub fn mock_contracts(config: &AppConfig) -> HashMap<Address, MockContract> {
+        let address = |value: &str| Address::from_str(value).unwrap();
+        let (quickswap_pair, sushiswap_pair) = (Address::repeat_byte(0x01), Address::repeat_byte(0x02));
+        HashMap::from([
+            (address(&config.dex.quickswap_router), MockContract::Router { factory: address(&config.dex.quickswap_factory) }),
+            (address(&config.dex.sushiswap_router), MockContract::Router { factory: address(&config.dex.sushiswap_factory) }),
+            (address(&config.dex.quickswap_factory), MockContract::Factory { pair: quickswap_pair }),
+            (address(&config.dex.sushiswap_factory), MockContract::Factory { pair: sushiswap_pair }),
+            (address(&config.tokens.weth), MockContract::Token { decimals: WETH_DECIMALS as u8 }),
+            (address(&config.tokens.usdc), MockContract::Token { decimals: USDC_DECIMALS as u8 }),
+            (quickswap_pair, MockContract::Pair),
+            (sushiswap_pair, MockContract::Pair),
+        ])
+    }
+
+    struct MockChain {
+        contracts: HashMap<Address, MockContract>,
+        block: u64,
+    }
+
+    async fn handle_rpc(chain: web::Data<MockChain>, body: web::Json<serde_json::Value>) -> HttpResponse {
+        let params = &body["params"];
+        let contract = |value: &serde_json::Value| value.as_str()
+            .and_then(|address| Address::from_str(address).ok())
+            .and_then(|address| chain.contracts.get(&address));
+
+        let result = match body["method"].as_str().unwrap_or_default() {
+            "eth_chainId" => serde_json::json!("0x89"),
+            "eth_blockNumber" => serde_json::json!(format!("{:#x}", chain.block)),
+            "eth_getCode" => serde_json::json!(if contract(&params[0]).is_some() { "0x6080" } else { "0x" }),
+            "eth_call" => {
+                let call = &params[0];
+                let data = call["input"].as_str().or(call["data"].as_str()).unwrap_or_default();
+                let selector = ethers::utils::hex::decode(data.trim_start_matches("0x")).unwrap_or_default();
+                let output = match (contract(&call["to"]), selector.get(..4)) {
+                    (Some(&MockContract::Router { factory }), Some(s)) if s == &ethers::utils::id("factory()")[..] => {
+                        abi::encode(&[Token::Address(factory)])
+                    },
+                    (Some(&MockContract::Factory { pair }), Some(s)) if s == &ethers::utils::id("getPair(address,address)")[..] => {
+                        abi::encode(&[Token::Address(pair)])
+                    },
+                    (Some(&MockContract::Token { decimals }), Some(s)) if s == &ethers::utils::id("decimals()")[..] => {
+                        abi::encode(&[Token::Uint(decimals.into())])
+                    },
+                    _ => Vec::new(),
+                };
+                serde_json::json!(format!("0x{}", ethers::utils::hex::encode(output)))
+            },
+            _ => serde_json::Value::Null,
+        };
+
+        HttpResponse::Ok().json(serde_json::json!({ "jsonrpc": "2.0", "id": body["id"], "result": result }))
+    }
+
+    /// Serves the chain ID, code and view calls for `contracts`.
+    pub fn start_mock_chain(contracts: HashMap<Address, MockContract>) -> String {
+        start_mock_chain_at(contracts, 0)
+    }
+
+    /// Like `start_mock_chain`, with the chain head at `block`.
+    pub fn start_mock_chain_at(contracts: HashMap<Address, MockContract>, block: u64) -> String {
+        let chain = web::Data::new(MockChain { contracts, block });
+        let server = HttpServer::new(move || App::new().app_data(chain.clone()).route("/", web::post().to(handle_rpc)))
+            .workers(1)
+            .bind(("127.0.0.1", 0))
+            .unwrap();
+
+        let addr = server.addrs()[0];
+        actix_web::rt::spawn(server.run());
+        format!("http://{}", addr)
+    }
+
+    fn config() -> AppConfig {
+        toml::from_str(&std::fs::read_to_string("config/config.toml").unwrap()).unwrap()
+    }
+
+    #[actix_web::test]
+    async fn test_verifies_configured_contracts() {
+        let config = config();
+        let provider = Arc::new(crate::rpc::http_provider(&start_mock_chain(mock_contracts(&config))).unwrap());
+
+        let report = verify_contracts(provider, &config).await.unwrap();
+        assert!(report.is_ok(), "{}", report);
+        assert_eq!(report.checks.len(), 12);
+    }
+
+    #[actix_web::test]
+    async fn test_reports_every_mismatch() {
+        let config = config();
+        let usdc = Address::from_str(&config.tokens.usdc).unwrap();
+        let quickswap_router = Address::from_str(&config.dex.quickswap_router).unwrap();
+        let sushiswap_factory = Address::from_str(&config.dex.sushiswap_factory).unwrap();
+
+        let mut contracts = mock_contracts(&config);
+        contracts.insert(usdc, MockContract::Token { decimals: 18 });
+        contracts.insert(quickswap_router, MockContract::Router { factory: sushiswap_factory });
+        contracts.insert(sushiswap_factory, MockContract::Factory { pair: Address::zero() });
+        let provider = Arc::new(crate::rpc::http_provider(&start_mock_chain(contracts)).unwrap());
+
+        let report = verify_contracts(provider, &config).await.unwrap();
+        let failed: Vec<(&str, &str)> = report.checks.iter()
+            .filter(|check| !check.ok)
+            .map(|check| (check.subject.as_str(), check.detail.as_str()))
+            .collect();
+        assert_eq!(failed, vec![
+            ("tokens.usdc", "decimals() is 18, expected 6"),
+            ("dex.quickswap_router", "factory() is 0xc35DADB65012eC5796536bD9864eD8773aBc74C4, not dex.quickswap_factory"),
+            ("SushiSwap WETH/USDC pair", "dex.sushiswap_factory has no WETH/USDC pair"),
+        ]);
+
+        let error = report.ensure().unwrap_err().to_string();
+        assert!(error.contains("FAIL  tokens.usdc"));
+    }
+}